@@ -22,7 +22,7 @@ from datetime import date, datetime
 from importlib.metadata import version as pkg_version
 from importlib.resources import files
 from pathlib import Path
-from urllib.parse import unquote
+from urllib.parse import parse_qs, unquote, urlparse
 
 PORT_RANGE = (10600, 10700)
 PORT_FILE = "port.json"
@@ -161,6 +161,14 @@ def build_frontmatter(meta: dict) -> str:
     return "\n".join(lines)
 
 
+def task_sort_key(task: dict):
+    """Tasks with an explicit integer `order` come first, the rest by filename."""
+    order = task["meta"].get("order")
+    if isinstance(order, int) and not isinstance(order, bool):
+        return (0, order, task["filename"])
+    return (1, 0, task["filename"])
+
+
 def slugify(text: str) -> str:
     slug = text.lower().strip()
     slug = re.sub(r"[^\w\s-]", "", slug)
@@ -283,6 +291,7 @@ class Board:
                         "body": body,
                         "column": col["name"],
                     })
+            tasks.sort(key=task_sort_key)
             board["columns"].append({
                 **col,
                 "tasks": tasks,
@@ -318,6 +327,8 @@ class Board:
             meta["due"] = data["due"]
         if data.get("branch"):
             meta["branch"] = data["branch"]
+        if data.get("author"):
+            meta["author"] = data["author"]
 
         description = data.get("description", "")
         body = f"\n## Description\n{description}\n\n## Acceptance Criteria\n\n\n## Notes\n"
@@ -361,6 +372,30 @@ class Board:
         shutil.move(str(src), str(dst_dir / filename))
         return True
 
+    def reorder_task(self, column: str, filename: str, index: int) -> bool:
+        """Move a task to `index` within its column, renumbering `order`.
+
+        Only files whose `order` actually changes are rewritten.
+        """
+        col_dir = self.root / column
+        if not (col_dir / filename).exists():
+            return False
+        tasks = []
+        for f in col_dir.glob("*.md"):
+            fm, body = parse_frontmatter(f.read_text())
+            tasks.append({"filename": f.name, "meta": fm, "body": body, "path": f})
+        tasks.sort(key=task_sort_key)
+        moved = next(t for t in tasks if t["filename"] == filename)
+        tasks.remove(moved)
+        tasks.insert(max(0, min(index, len(tasks))), moved)
+        for i, t in enumerate(tasks):
+            order = t["meta"].get("order")
+            # `True == 1` in Python, so compare types too
+            if type(order) is not int or order != i:
+                t["meta"]["order"] = i
+                t["path"].write_text(build_frontmatter(t["meta"]) + "\n" + t["body"])
+        return True
+
     def delete_task(self, column: str, filename: str) -> bool:
         path = self.root / column / filename
         if not path.exists():
@@ -471,6 +506,8 @@ class ResourceStore:
             if not current.exists():
                 continue
             fm, body = parse_frontmatter(current.read_text())
+            rev_dir = d / "revisions"
+            fm["revision_count"] = len(list(rev_dir.glob("*.md"))) if rev_dir.is_dir() else 0
             resources.append({
                 "dir_name": d.name,
                 "meta": fm,
@@ -641,7 +678,9 @@ class BoardHandler(http.server.BaseHTTPRequestHandler):
         return json.loads(raw)
 
     def _route(self, method: str):
-        path = unquote(self.path)
+        parsed = urlparse(self.path)
+        path = unquote(parsed.path)
+        self.query = parse_qs(parsed.query)
 
         # Serve frontend
         if method == "GET" and path == "/":
@@ -690,6 +729,20 @@ class BoardHandler(http.server.BaseHTTPRequestHandler):
             result = self.board.create_task(data)
             return self._send_json(result, 201)
 
+        if path == "/api/task/reorder" and method == "PATCH":
+            data = self._read_body()
+            column, filename, index = data.get("column"), data.get("filename"), data.get("index")
+            if column not in self.board.column_names():
+                return self._send_error(400, "Unknown column")
+            if not isinstance(filename, str) or not filename.endswith(".md") or "/" in filename or "\\" in filename:
+                return self._send_error(400, "Invalid filename")
+            if type(index) is not int:
+                return self._send_error(400, "index must be an integer")
+            ok = self.board.reorder_task(column, filename, index)
+            if ok:
+                return self._send_json({"ok": True})
+            return self._send_error(404, "Task not found")
+
         if path == "/api/task/move" and method == "PATCH":
             data = self._read_body()
             ok = self.board.move_task(data["filename"], data["from_column"], data["to_column"])
@@ -752,7 +805,7 @@ class BoardHandler(http.server.BaseHTTPRequestHandler):
         """Route /api/prompts or /api/reports requests. Returns True if handled, None if not."""
         # GET /api/{type} — list all
         if path == prefix and method == "GET":
-            self._send_json(store.list_resources())
+            self._send_json(self._paginate(store.list_resources()))
             return True
 
         # POST /api/{type} — create
@@ -817,6 +870,17 @@ class BoardHandler(http.server.BaseHTTPRequestHandler):
 
         return None
 
+    def _paginate(self, items: list) -> list:
+        """Apply optional ?offset=N&limit=M query params to a list response."""
+        try:
+            offset = max(int(self.query.get("offset", ["0"])[0]), 0)
+            limit = int(self.query["limit"][0]) if "limit" in self.query else None
+        except ValueError:
+            return items
+        if limit is None:
+            return items[offset:]
+        return items[offset:offset + max(limit, 0)]
+
     def _get_activity(self, limit: int = 50) -> list[dict]:
         """Collect recent file changes across tasks, prompts, and documents."""
         entries = []
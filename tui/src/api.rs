@@ -1,142 +1,569 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::{Client, Method, Request, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 
 use crate::model::*;
 
+/// One page of a prompt/document listing.
+#[derive(Debug, Clone, Default)]
+pub struct ResourcePage {
+    pub items: Vec<Resource>,
+    /// The server returned a full page, so more items may follow
+    pub has_more: bool,
+}
+
+impl ResourcePage {
+    /// Servers that ignore `limit` return everything, which is never "more".
+    fn from_items(items: Vec<Resource>, limit: usize) -> Self {
+        let has_more = items.len() == limit;
+        Self { items, has_more }
+    }
+}
+
+/// Idempotent GETs are tried this many times before the error is returned.
+const GET_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; later retries wait proportionally longer.
+const RETRY_BACKOFF: Duration = Duration::from_millis(150);
+
+/// A non-success HTTP response from the server.
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound {
+        path: String,
+    },
+    Status {
+        status: StatusCode,
+        path: String,
+        snippet: String,
+    },
+    /// A write refused locally because the client runs with `--read-only`
+    ReadOnly,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotFound { path } => write!(f, "{path} not found"),
+            ApiError::Status {
+                status,
+                path,
+                snippet,
+            } if snippet.is_empty() => write!(f, "{path} returned {status}"),
+            ApiError::Status {
+                status,
+                path,
+                snippet,
+            } => write!(f, "{path} returned {status}: {snippet}"),
+            ApiError::ReadOnly => write!(f, "read-only mode"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Whether the error (possibly wrapped in context) is a 404 from the server.
+pub fn is_not_found(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<ApiError>(),
+        Some(ApiError::NotFound { .. })
+    )
+}
+
+/// Whether a failed GET is worth retrying: connection problems and 5xx
+/// responses, but not 4xx or malformed bodies.
+fn is_transient(err: &anyhow::Error) -> bool {
+    if let Some(api_err) = err.downcast_ref::<ApiError>() {
+        return matches!(api_err, ApiError::Status { status, .. } if status.is_server_error());
+    }
+    err.downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| e.is_connect() || e.is_timeout() || e.is_request())
+}
+
+/// Turn a non-2xx response into an `ApiError`, keeping a short excerpt of the
+/// body (the server's `{"error": ...}` message when present).
+async fn check_status(resp: Response) -> Result<Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let path = resp.url().path().to_string();
+    if status == StatusCode::NOT_FOUND {
+        return Err(ApiError::NotFound { path }.into());
+    }
+    let body = resp.text().await.unwrap_or_default();
+    let snippet = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v.get("error")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| body.split_whitespace().collect::<Vec<_>>().join(" "));
+    let snippet = snippet.chars().take(120).collect();
+    Err(ApiError::Status {
+        status,
+        path,
+        snippet,
+    }
+    .into())
+}
+
 #[derive(Clone)]
 pub struct ApiClient {
     base_url: String,
     client: Client,
+    read_only: bool,
+    api_prefix: String,
+    events_path: Option<String>,
+    // Shared with clones, so requests made by the poller count too
+    last_request: Arc<Mutex<Option<RecordedRequest>>>,
+}
+
+/// An API request as it was sent, kept so it can be copied as `curl`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl RecordedRequest {
+    fn of(request: &Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            url: request.url().to_string(),
+            headers: request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+                .collect(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+        }
+    }
+
+    /// A shell command repeating the request.
+    pub fn to_curl(&self) -> String {
+        let mut parts = vec!["curl".to_string()];
+        if self.method != Method::GET {
+            parts.push(format!("-X {}", self.method));
+        }
+        parts.push(shell_quote(&self.url));
+        for (name, value) in &self.headers {
+            parts.push(format!("-H {}", shell_quote(&format!("{name}: {value}"))));
+        }
+        if let Some(body) = &self.body {
+            parts.push(format!("--data-raw {}", shell_quote(body)));
+        }
+        parts.join(" ")
+    }
+}
+
+/// Single-quote `s` for POSIX shells.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Path the server's API is mounted under, below the base URL.
+pub const DEFAULT_API_PREFIX: &str = "/api";
+
+/// Trim whitespace and trailing slashes so paths can be appended, keeping
+/// any path prefix (`https://host/tools/mdboard/` -> `https://host/tools/mdboard`).
+pub fn normalize_base_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_string()
+}
+
+/// Append `path` to a normalized base URL with exactly one slash between.
+pub fn join_url(base: &str, path: &str) -> String {
+    format!("{base}/{}", path.trim_start_matches('/'))
+}
+
+/// Base URL of a server found through `port.json`, optionally mounted under
+/// `base_path` (e.g. `/tools/mdboard`).
+pub fn local_url(port: u64, base_path: &str) -> String {
+    let base_path = base_path.trim().trim_matches('/');
+    if base_path.is_empty() {
+        format!("http://localhost:{port}")
+    } else {
+        join_url(&format!("http://localhost:{port}"), base_path)
+    }
 }
 
 impl ApiClient {
     pub fn new(base_url: &str) -> Self {
         Self {
-            base_url: base_url.trim_end_matches('/').to_string(),
+            base_url: normalize_base_url(base_url),
             client: Client::new(),
+            read_only: false,
+            api_prefix: DEFAULT_API_PREFIX.to_string(),
+            events_path: None,
+            last_request: Arc::default(),
         }
     }
 
+    /// Mount point of the API under the base URL (`--api-prefix`); empty or
+    /// `/` means the base URL itself.
+    pub fn api_prefix(mut self, prefix: &str) -> Self {
+        let prefix = prefix.trim().trim_matches('/');
+        self.api_prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("/{prefix}")
+        };
+        self
+    }
+
+    /// Path of the SSE stream under the base URL (`--events-path`). Without
+    /// one it is `events` under the API prefix.
+    pub fn events_path(mut self, path: Option<&str>) -> Self {
+        self.events_path = path.map(|p| p.trim().to_string());
+        self
+    }
+
+    /// Refuse every write (`--read-only`).
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Start a mutating request. Every write goes through here, so read-only
+    /// mode only has to be enforced once.
+    fn write(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        if self.read_only {
+            return Err(ApiError::ReadOnly.into());
+        }
+        Ok(self.client.request(method, self.url(path)))
+    }
+
+    /// Send a request, remembering it for `last_request`.
+    async fn send(&self, builder: RequestBuilder) -> reqwest::Result<Response> {
+        let request = builder.build()?;
+        if let Ok(mut last) = self.last_request.lock() {
+            *last = Some(RecordedRequest::of(&request));
+        }
+        self.client.execute(request).await
+    }
+
+    /// The most recent API request, from this client or a clone of it.
+    pub fn last_request(&self) -> Option<RecordedRequest> {
+        self.last_request.lock().ok()?.clone()
+    }
+
+    /// Full URL of an API `path` (`/board`, ...) under the base URL and
+    /// API prefix.
+    fn url(&self, path: &str) -> String {
+        join_url(&self.base_url, &format!("{}{path}", self.api_prefix))
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     pub async fn version(&self) -> Result<VersionInfo> {
         let resp = self
-            .client
-            .get(format!("{}/api/version", self.base_url))
-            .send()
+            .send(self.client.get(self.url("/version")))
             .await
             .context("Failed to connect to mdboard server")?;
-        resp.json().await.context("Invalid version response")
+        check_status(resp)
+            .await?
+            .json()
+            .await
+            .context("Invalid version response")
     }
 
     pub async fn config(&self) -> Result<Config> {
-        let resp = self
-            .client
-            .get(format!("{}/api/config", self.base_url))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid config response")
+        self.get_json("/config", &[], "config").await
     }
 
     pub async fn board(&self) -> Result<Board> {
-        let resp = self
-            .client
-            .get(format!("{}/api/board", self.base_url))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid board response")
+        self.get_json("/board", &[], "board").await
     }
 
     pub async fn get_task(&self, column: &str, filename: &str) -> Result<Task> {
-        let resp = self
-            .client
-            .get(format!("{}/api/task/{}/{}", self.base_url, column, filename))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid task response")
+        self.get_json(&format!("/task/{column}/{filename}"), &[], "task")
+            .await
+    }
+
+    pub async fn create_task(
+        &self,
+        column: &str,
+        title: &str,
+        assignee: &str,
+        scopes: &[String],
+        body: &str,
+        author: &str,
+    ) -> Result<Task> {
+        let request = self
+            .write(Method::POST, "/task")?
+            .json(&serde_json::json!({
+                "column": column,
+                "title": title,
+                "assignee": assignee,
+                "scopes": scopes,
+                "description": body,
+                "author": author,
+            }));
+        let resp = self.send(request).await?;
+        check_status(resp)
+            .await?
+            .json()
+            .await
+            .context("Invalid create task response")
+    }
+
+    pub async fn reorder_task(&self, column: &str, filename: &str, index: usize) -> Result<()> {
+        let request = self
+            .write(Method::PATCH, "/task/reorder")?
+            .json(&serde_json::json!({
+                "column": column,
+                "filename": filename,
+                "index": index,
+            }));
+        let resp = self.send(request).await?;
+        check_status(resp).await?;
+        Ok(())
+    }
+
+    pub async fn delete_task(&self, column: &str, filename: &str) -> Result<()> {
+        let request = self.write(Method::DELETE, &format!("/task/{column}/{filename}"))?;
+        let resp = self.send(request).await?;
+        check_status(resp).await?;
+        Ok(())
+    }
+
+    pub async fn set_assignee(&self, column: &str, filename: &str, assignee: &str) -> Result<Task> {
+        self.update_task(
+            column,
+            filename,
+            serde_json::json!({ "assignee": assignee }),
+        )
+        .await
+    }
+
+    /// Always sent as a list, whatever form the file used: the server would
+    /// split a bare string into single-character scopes.
+    pub async fn set_scopes(
+        &self,
+        column: &str,
+        filename: &str,
+        scopes: &[String],
+    ) -> Result<Task> {
+        self.update_task(column, filename, serde_json::json!({ "scopes": scopes }))
+            .await
+    }
+
+    /// PUT changed frontmatter fields; the server leaves the others alone.
+    async fn update_task(
+        &self,
+        column: &str,
+        filename: &str,
+        fields: serde_json::Value,
+    ) -> Result<Task> {
+        let request = self
+            .write(Method::PUT, &format!("/task/{column}/{filename}"))?
+            .json(&fields);
+        let resp = self.send(request).await?;
+        check_status(resp)
+            .await?
+            .json()
+            .await
+            .context("Invalid update task response")
+    }
+
+    pub async fn move_task(
+        &self,
+        filename: &str,
+        from_column: &str,
+        to_column: &str,
+    ) -> Result<()> {
+        let request = self
+            .write(Method::PATCH, "/task/move")?
+            .json(&serde_json::json!({
+                "filename": filename,
+                "from_column": from_column,
+                "to_column": to_column,
+            }));
+        let resp = self.send(request).await?;
+        check_status(resp).await?;
+        Ok(())
     }
 
     pub async fn get_comments(&self, task_id: &str) -> Result<Vec<Comment>> {
-        let resp = self
-            .client
-            .get(format!("{}/api/comments/{}", self.base_url, task_id))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid comments response")
+        self.get_json(&format!("/comments/{task_id}"), &[], "comments")
+            .await
     }
 
-    pub async fn list_prompts(&self) -> Result<Vec<Resource>> {
-        let resp = self
-            .client
-            .get(format!("{}/api/prompts", self.base_url))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid prompts response")
+    pub async fn list_prompts(&self, offset: usize, limit: usize) -> Result<ResourcePage> {
+        let query = [("offset", offset), ("limit", limit)];
+        let items: Vec<Resource> = self.get_json("/prompts", &query, "prompts").await?;
+        Ok(ResourcePage::from_items(items, limit))
     }
 
     pub async fn get_prompt(&self, dir_name: &str) -> Result<Resource> {
-        let resp = self
-            .client
-            .get(format!("{}/api/prompts/{}", self.base_url, dir_name))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid prompt response")
+        self.get_json(&format!("/prompts/{dir_name}"), &[], "prompt")
+            .await
     }
 
     pub async fn list_prompt_revisions(&self, dir_name: &str) -> Result<Vec<Revision>> {
-        let resp = self
-            .client
-            .get(format!(
-                "{}/api/prompts/{}/revisions",
-                self.base_url, dir_name
-            ))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid revisions response")
+        self.get_json(
+            &format!("/prompts/{dir_name}/revisions"),
+            &[],
+            "revisions",
+        )
+        .await
     }
 
-    pub async fn list_documents(&self) -> Result<Vec<Resource>> {
-        let resp = self
-            .client
-            .get(format!("{}/api/documents", self.base_url))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid documents response")
+    pub async fn list_documents(&self, offset: usize, limit: usize) -> Result<ResourcePage> {
+        let query = [("offset", offset), ("limit", limit)];
+        let items: Vec<Resource> = self.get_json("/documents", &query, "documents").await?;
+        Ok(ResourcePage::from_items(items, limit))
     }
 
     pub async fn get_document(&self, dir_name: &str) -> Result<Resource> {
-        let resp = self
-            .client
-            .get(format!("{}/api/documents/{}", self.base_url, dir_name))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid document response")
+        self.get_json(&format!("/documents/{dir_name}"), &[], "document")
+            .await
     }
 
     pub async fn list_document_revisions(&self, dir_name: &str) -> Result<Vec<Revision>> {
-        let resp = self
-            .client
-            .get(format!(
-                "{}/api/documents/{}/revisions",
-                self.base_url, dir_name
-            ))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid revisions response")
+        self.get_json(
+            &format!("/documents/{dir_name}/revisions"),
+            &[],
+            "revisions",
+        )
+        .await
     }
 
     pub async fn activity(&self) -> Result<Vec<ActivityEntry>> {
-        let resp = self
+        self.get_json("/activity", &[], "activity").await
+    }
+
+    /// GET `path` and decode the JSON body, failing on non-success statuses.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, usize)],
+        what: &str,
+    ) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            match self.try_get_json(path, query, what).await {
+                Err(e) if attempt < GET_ATTEMPTS && is_transient(&e) => {
+                    tokio::time::sleep(RETRY_BACKOFF * attempt).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn try_get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, usize)],
+        what: &str,
+    ) -> Result<T> {
+        let request = self
             .client
-            .get(format!("{}/api/activity", self.base_url))
-            .send()
-            .await?;
-        resp.json().await.context("Invalid activity response")
+            .get(self.url(path))
+            .query(query);
+        let resp = self.send(request).await?;
+        let resp = check_status(resp).await?;
+        resp.json()
+            .await
+            .with_context(|| format!("Invalid {what} response"))
     }
 
     pub fn events_url(&self) -> String {
-        format!("{}/api/events", self.base_url)
+        match &self.events_path {
+            Some(path) => join_url(&self.base_url, path),
+            None => self.url("/events"),
+        }
     }
 
     pub fn client(&self) -> &Client {
         &self.client
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_error(code: u16) -> anyhow::Error {
+        ApiError::Status {
+            status: StatusCode::from_u16(code).unwrap(),
+            path: "/api/board".into(),
+            snippet: String::new(),
+        }
+        .into()
+    }
+
+    #[tokio::test]
+    async fn read_only_client_refuses_writes_before_sending() {
+        // Nothing listens on port 9; a request that got sent would fail differently
+        let api = ApiClient::new("http://127.0.0.1:9").read_only(true);
+        let err = api.delete_task("todo", "001.md").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ApiError>(), Some(ApiError::ReadOnly)));
+        let err = api.move_task("001.md", "todo", "done").await.unwrap_err();
+        assert_eq!(err.to_string(), "read-only mode");
+    }
+
+    #[test]
+    fn joins_paths_under_base_url_prefixes() {
+        for base in ["http://localhost:10600", "http://localhost:10600/"] {
+            let api = ApiClient::new(base);
+            assert_eq!(api.base_url(), "http://localhost:10600");
+            assert_eq!(api.events_url(), "http://localhost:10600/api/events");
+        }
+        for base in ["https://host/tools/mdboard", " https://host/tools/mdboard// "] {
+            let api = ApiClient::new(base);
+            assert_eq!(api.base_url(), "https://host/tools/mdboard");
+            assert_eq!(api.url("/board"), "https://host/tools/mdboard/api/board");
+            assert_eq!(api.events_url(), "https://host/tools/mdboard/api/events");
+        }
+        let api = ApiClient::new("http://h").api_prefix("v2/api/");
+        assert_eq!(api.url("/board"), "http://h/v2/api/board");
+        assert_eq!(api.events_url(), "http://h/v2/api/events");
+        let api = api.api_prefix("/").events_path(Some("/sse"));
+        assert_eq!(api.url("/board"), "http://h/board");
+        assert_eq!(api.events_url(), "http://h/sse");
+        assert_eq!(join_url("https://host/x", "api/board"), "https://host/x/api/board");
+
+        assert_eq!(local_url(10600, ""), "http://localhost:10600");
+        assert_eq!(local_url(10600, "/"), "http://localhost:10600");
+        assert_eq!(local_url(10600, "tools/mdboard/"), "http://localhost:10600/tools/mdboard");
+        assert_eq!(local_url(10600, "/tools/mdboard"), "http://localhost:10600/tools/mdboard");
+    }
+
+    #[test]
+    fn recorded_requests_become_curl_commands() {
+        let api = ApiClient::new("http://h");
+        let request = api
+            .client
+            .request(Method::PATCH, api.url("/task/move"))
+            .json(&serde_json::json!({"filename": "it's.md"}))
+            .build()
+            .unwrap();
+        assert_eq!(
+            RecordedRequest::of(&request).to_curl(),
+            r#"curl -X PATCH 'http://h/api/task/move' -H 'content-type: application/json' --data-raw '{"filename":"it'\''s.md"}'"#
+        );
+        let request = api.client.get(api.url("/board")).build().unwrap();
+        assert_eq!(RecordedRequest::of(&request).to_curl(), "curl 'http://h/api/board'");
+    }
+
+    #[test]
+    fn retries_only_server_errors() {
+        assert!(is_transient(&status_error(503)));
+        assert!(!is_transient(&status_error(400)));
+        let not_found: anyhow::Error = ApiError::NotFound {
+            path: "/api/task/todo/x.md".into(),
+        }
+        .into();
+        assert!(!is_transient(&not_found));
+        assert!(is_not_found(&not_found.context("Invalid task response")));
+    }
+}
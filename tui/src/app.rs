@@ -1,6 +1,28 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::api::ResourcePage;
+use crate::dates;
+use crate::input::TextInput;
+use crate::links;
 use crate::model::*;
+use crate::poll::LoadedWindow;
+use crate::quick_open::{QuickOpenItem, QuickOpenTarget};
+use crate::search::{HitField, SearchIndex};
+use crate::theme;
+use crate::ui::board::count_checkboxes;
+use crate::ui::markdown::MarkdownCache;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How long a transient status-bar message stays visible.
+pub const STATUS_MESSAGE_TTL: Duration = Duration::from_secs(4);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum View {
     Board,
     Prompts,
@@ -54,6 +76,12 @@ pub enum Overlay {
         task: Task,
         comments: Vec<Comment>,
         scroll: usize,
+        search: Option<String>, // in-overlay find query, highlighted in the body
+        visual: Option<(usize, usize)>, // V selection: (anchor, cursor) body source lines
+        comments_collapsed: bool,       // C: show only the "Comments (N)" line
+        gone: bool,                     // deleted on the server since it was opened
+        sections: BTreeSet<usize>,      // <details> sections toggled with Enter, by index
+        links: Option<usize>,           // Some(selected) while the O link picker is shown
     },
     ResourceDetail {
         resource: Resource,
@@ -61,13 +89,153 @@ pub enum Overlay {
         current_rev: Option<usize>, // None = current, Some(idx) = viewing revision
         scroll: usize,
         resource_type: ResourceType,
+        toc: Option<usize>, // Some(selected heading) while the contents panel is shown
+        search: Option<String>,
+        visual: Option<(usize, usize)>,
+        gone: bool,
+        sections: BTreeSet<usize>,
+        links: Option<usize>,
     },
     Help {
         scroll: usize,
     },
+    Debug,
+    NewTask {
+        column: String,
+        fields: Vec<TextInput>, // one per NEW_TASK_FIELDS entry
+        focused: usize,
+        error: Option<String>,
+    },
+    QuickOpen {
+        title: &'static str, // "Quick open" (Ctrl+P) or "Recent" (Ctrl+R)
+        query: TextInput,
+        items: Vec<QuickOpenItem>,
+        matches: Vec<usize>, // indices into items, best first
+        selected: usize,
+    },
+    AssigneePicker {
+        column: String,
+        filename: String,
+        query: TextInput,
+        known: Vec<String>,   // assignees seen on the board, plus --user
+        options: Vec<String>, // filtered from known/query; "" means unassign
+        selected: usize,
+    },
+    /// `y` runs the action, `n` or Esc cancels it
+    Confirm {
+        message: String,
+        action: ConfirmAction,
+    },
+    ColumnPicker {
+        columns: Vec<usize>, // overflow columns beyond --max-columns
+        selected: usize,
+    },
+    /// `%`: every task with checkboxes, nearest to done first
+    Progress {
+        rows: Vec<ProgressRow>,
+        selected: usize,
+    },
+    /// What `--prompt-command` printed for a prompt
+    CommandOutput {
+        title: String,
+        output: String,
+        scroll: usize,
+    },
+    ScopeEditor {
+        column: String,
+        filename: String,
+        query: TextInput,
+        known: Vec<String>,   // config scopes plus those used on the board
+        chosen: Vec<String>,  // the task's scopes as edited so far
+        options: Vec<String>, // filtered from known/chosen/query
+        selected: usize,
+    },
+}
+
+/// Labels of the new-task form fields, in tab order.
+pub const NEW_TASK_FIELDS: [&str; 3] = ["Title", "Assignee", "Scopes (comma-separated)"];
+
+/// Picker rows for `query`: known names containing it (case-insensitive),
+/// led by the typed name itself when it is new. An empty query offers
+/// "" (unassign) followed by every known name.
+pub fn assignee_options(known: &[String], query: &str) -> Vec<String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return std::iter::once(String::new())
+            .chain(known.iter().cloned())
+            .collect();
+    }
+    let needle = query.to_lowercase();
+    let mut options: Vec<String> = known
+        .iter()
+        .filter(|name| name.to_lowercase().contains(&needle))
+        .cloned()
+        .collect();
+    if !known.iter().any(|name| name.eq_ignore_ascii_case(query)) {
+        options.insert(0, query.to_string());
+    }
+    options
+}
+
+/// Scope editor rows for `query`: every known or chosen scope containing it
+/// (case-insensitive), led by the typed scope itself when it is new.
+pub fn scope_options(known: &[String], chosen: &[String], query: &str) -> Vec<String> {
+    let mut all: Vec<String> = known.to_vec();
+    for scope in chosen {
+        if !all.iter().any(|s| s.eq_ignore_ascii_case(scope)) {
+            all.push(scope.clone());
+        }
+    }
+    all.sort_by_key(|s| s.to_lowercase());
+    let query = query.trim();
+    if query.is_empty() {
+        return all;
+    }
+    let needle = query.to_lowercase();
+    let mut options: Vec<String> = all
+        .iter()
+        .filter(|s| s.to_lowercase().contains(&needle))
+        .cloned()
+        .collect();
+    if !all.iter().any(|s| s.eq_ignore_ascii_case(query)) {
+        options.insert(0, query.to_string());
+    }
+    options
 }
 
+/// Add `scope` to `chosen`, or remove it if already there (case-insensitive).
+pub fn toggle_scope(chosen: &mut Vec<String>, scope: &str) {
+    let before = chosen.len();
+    chosen.retain(|s| !s.eq_ignore_ascii_case(scope));
+    if chosen.len() == before {
+        chosen.push(scope.to_string());
+    }
+}
+
+/// A single-line prompt shown on top of the current view or overlay.
+#[derive(Debug, Clone)]
+pub struct InputPrompt {
+    pub label: String,
+    pub input: TextInput,
+    pub action: InputAction,
+}
+
+/// What to do with the prompt's value on submit.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputAction {
+    /// Write the open task overlay to the entered path
+    ExportTask,
+    /// Find text in the open task/resource overlay
+    OverlaySearch,
+    /// Highlight board tasks matching the entered text
+    BoardSearch,
+    /// Add a scope to every marked board task
+    BulkAddScope,
+    /// Run a `:` command line
+    Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ResourceType {
     Prompt,
     Document,
@@ -80,6 +248,14 @@ impl ResourceType {
             ResourceType::Document => "Document",
         }
     }
+
+    /// Directory name under the data dir (and API path segment).
+    pub fn collection(self) -> &'static str {
+        match self {
+            ResourceType::Prompt => "prompts",
+            ResourceType::Document => "documents",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -87,9 +263,174 @@ pub enum ConnectionState {
     Connected,
     Disconnected,
     Connecting,
+    /// Browsing an `--offline` snapshot; no server
+    Offline,
+}
+
+/// Default `--narrow-width`: the board below this many terminal columns
+/// shows only the selected column.
+pub const DEFAULT_NARROW_WIDTH: u16 = 60;
+
+/// A task watched with `w`. Polled board updates are compared against the
+/// last seen column and content hash to raise a notification.
+#[derive(Debug, Clone)]
+pub struct WatchedTask {
+    pub column: String,
+    pub filename: String,
+    pub title: String,
+    pub hash: u64,
 }
 
+/// Pending "jump to column" prefix on the board.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnJump {
+    /// `m` pressed — waiting for a column number (1-9)
+    Number,
+    /// `f` pressed — column titles show letter hints
+    Hint,
+    /// `M` pressed with tasks marked — the number picks where to move them
+    MoveMarked,
+}
+
+/// A destructive action held back by the confirmation overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfirmAction {
+    /// Move every marked task to the column at this index
+    MoveMarked(usize),
+    DeleteTask { column: String, filename: String },
+}
+
+/// Keys used for column hints in `ColumnJump::Hint` mode, in column order.
+pub const COLUMN_HINT_KEYS: [char; 9] = ['a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l'];
+
+/// What a card does when its metadata line is wider than the card
+/// (`--card-meta`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CardMetaPolicy {
+    /// Show fields in order until one doesn't fit, then `…`
+    Truncate,
+    /// Leave out whole fields, least important first (`--card-meta-drop`)
+    Drop,
+}
+
+/// A field of the card metadata line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CardField {
+    Id,
+    Priority,
+    Status,
+    Assignee,
+    Scopes,
+    Progress,
+    Due,
+}
+
+/// Default `--card-meta-drop`: first dropped first.
+pub const DEFAULT_CARD_DROP_ORDER: [CardField; 7] = [
+    CardField::Due,
+    CardField::Scopes,
+    CardField::Progress,
+    CardField::Id,
+    CardField::Status,
+    CardField::Assignee,
+    CardField::Priority,
+];
+
+/// `--age-days` default: cards are fresh up to a week old and stale after a month.
+pub const DEFAULT_AGE_DAYS: (u32, u32) = (7, 30);
+
+/// Order of tasks within each board column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardSort {
+    /// Server order (the `order` field, then filename)
+    Manual,
+    /// Highest priority first; tasks without a priority keep server order at the end
+    Priority,
+}
+
+impl BoardSort {
+    pub fn label(self) -> &'static str {
+        match self {
+            BoardSort::Manual => "manual",
+            BoardSort::Priority => "priority",
+        }
+    }
+}
+
+/// Order of the prompt or document list, cycled with `s`. Only the display
+/// is sorted; the loaded list keeps the server's order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceSort {
+    Server,
+    /// Most recently updated first
+    Updated,
+    /// Most recently created first
+    Created,
+    /// By title, case-insensitively
+    Title,
+    /// Most revised first
+    Revision,
+}
+
+impl ResourceSort {
+    pub fn next(self) -> Self {
+        match self {
+            ResourceSort::Server => ResourceSort::Updated,
+            ResourceSort::Updated => ResourceSort::Created,
+            ResourceSort::Created => ResourceSort::Title,
+            ResourceSort::Title => ResourceSort::Revision,
+            ResourceSort::Revision => ResourceSort::Server,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ResourceSort::Server => "server order",
+            ResourceSort::Updated => "updated",
+            ResourceSort::Created => "created",
+            ResourceSort::Title => "title",
+            ResourceSort::Revision => "revision",
+        }
+    }
+
+    /// Indices of `list` in this order. Items missing the field (or with an
+    /// unparseable date) go last; ties keep server order.
+    pub fn order(self, list: &[Resource]) -> Vec<usize> {
+        use std::cmp::Reverse;
+        let mut order: Vec<usize> = (0..list.len()).collect();
+        // Reverse puts the newest first and None after every date
+        let newest = |raw: &str| Reverse(dates::parse_local(raw));
+        match self {
+            ResourceSort::Server => {}
+            ResourceSort::Updated => order.sort_by_cached_key(|&i| newest(&list[i].meta.updated)),
+            ResourceSort::Created => order.sort_by_cached_key(|&i| newest(&list[i].meta.created)),
+            ResourceSort::Title => order.sort_by_cached_key(|&i| list[i].display_title().to_lowercase()),
+            ResourceSort::Revision => order.sort_by_key(|&i| Reverse(list[i].meta.revision)),
+        }
+        order
+    }
+}
+
+/// A task with checkboxes in the `%` progress overview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressRow {
+    pub column: String,
+    pub filename: String,
+    pub title: String,
+    pub checked: usize,
+    pub total: usize,
+}
+
+/// The selected task, prompt and document by identity, captured before a
+/// full refresh so the selection can follow them to their new positions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectionAnchor {
+    task: Option<(String, String)>, // (column, filename)
+    prompt: Option<String>,
+    document: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum Focus {
     TabBar,
     Content,
@@ -98,23 +439,62 @@ pub enum Focus {
 pub struct App {
     pub view: View,
     pub overlay: Option<Overlay>,
+    pub input: Option<InputPrompt>,
     pub should_quit: bool,
+    pub force_quit: bool, // Ctrl+C: quit without waiting for pending writes
     pub focus: Focus,
 
     // Data
     pub version: Option<VersionInfo>,
     pub board: Option<Board>,
     pub config: Option<Config>,
+    pub scope_colors: HashMap<String, Color>, // derived from config, keyed lowercase
+    pub activity_colors: HashMap<String, Color>, // settings.activity_colors, keyed lowercase
+    pub status_colors: HashMap<String, Color>, // settings.status_colors, keyed lowercase
     pub prompts: Vec<Resource>,
     pub documents: Vec<Resource>,
     pub activity: Vec<ActivityEntry>,
 
+    // Lazy loading of prompt/document lists
+    pub prompts_has_more: bool,
+    pub documents_has_more: bool,
+    pub loaded_window: Arc<LoadedWindow>,
+
     // Navigation state
     pub board_col: usize,
     pub board_row: Vec<usize>, // per-column selected row
-    pub prompt_index: usize,
-    pub document_index: usize,
-    pub activity_index: usize,
+    pub board_scroll: Vec<Option<usize>>, // per-column first card scrolled to with Ctrl+e/Ctrl+y; None follows the selection
+    pub keep_row: bool,        // h/l land on the same row index instead of the column's saved row
+    pub wrap_lists: bool,      // --wrap-lists: j/k wrap around in the prompt, document and activity lists
+    pub confirm: bool,         // ask before destructive actions (off with --no-confirm)
+    pub read_only: bool,       // --read-only: write keys are refused, the API client too
+    pub active_assignee: Option<String>, // board shows only this assignee's tasks (cycled with @)
+    pub active_scope: Option<String>, // board shows only tasks with this scope (`:filter scope`)
+    pub active_status: Option<String>, // board shows only tasks with this status (cycled with t)
+    pub board_search: Option<String>, // `/` on the board: highlight matching cards
+    pub board_matches: Vec<(String, String)>, // (column, filename) matching board_search
+    pub search_index: SearchIndex, // re-indexed whenever tasks or a resource list change
+    pub prompt_index: usize,   // position in `shown_resources(Prompt)`
+    pub document_index: usize, // position in `shown_resources(Document)`
+    pub prompt_sort: ResourceSort,
+    pub document_sort: ResourceSort,
+    pub activity_index: usize, // index into `filtered_activity()`
+    pub hidden_activity_types: HashSet<String>, // entry types hidden with t/p/d
+    pub column_jump: Option<ColumnJump>,
+    pub board_sort: BoardSort,
+    pub focus_mode: bool, // task detail fills the screen, no tab/status bar
+    pub line_numbers: bool, // gutter in task/resource overlays, toggled with L
+    pub metadata_table: bool, // one aligned line per metadata field in overlays, toggled with m
+    pub raw_markdown: bool, // overlay bodies shown as written instead of rendered, toggled with R
+    // Last scroll of closed task/resource overlays: key -> (content hash, scroll)
+    pub scroll_memory: HashMap<String, (u64, usize)>,
+    pub markdown_cache: MarkdownCache, // rendered body of the open overlay
+    pub marked: HashSet<(String, String)>, // (column, filename) toggled with Space for bulk actions
+    pub watched: Vec<WatchedTask>,
+    pub recent: Vec<QuickOpenItem>, // recently opened items, most recent first
+
+    // Newest activity mtime seen when last leaving the Activity view (see seen.rs)
+    pub last_viewed_mtime: Option<f64>,
 
     // Connection
     pub connection: ConnectionState,
@@ -123,6 +503,61 @@ pub struct App {
 
     // Loading
     pub loading: bool,
+
+    // Transient message shown in the status bar
+    pub status_message: Option<(String, Instant)>,
+
+    // Local data directory (used to resolve relative paths in bodies)
+    pub data_dir: PathBuf,
+
+    // Set by state changes; the main loop only redraws when true
+    pub dirty: bool,
+
+    // Board columns hidden via --hide-columns or `x` (lowercased names/labels)
+    pub hidden_columns: HashSet<String>,
+    // E / --hide-empty-columns: columns without tasks are hidden too
+    pub hide_empty_columns: bool,
+
+    // Column widths from --column-weights (lowercased name/label -> weight),
+    // overriding `weight` in the server config
+    pub column_weight_overrides: HashMap<String, String>,
+
+    // Column from --column, selected once the first board arrives
+    pub start_column: Option<String>,
+
+    // Formats for links copied with `y` (see links.rs)
+    pub task_link_format: String,
+    pub resource_link_format: String,
+
+    // --prompt-command: shell command `P` pipes a prompt body into. The
+    // main loop takes `prompt_run` (command, body) and runs it in the
+    // background; `prompt_running` blocks a second run until it reports back
+    pub prompt_command: Option<String>,
+    pub prompt_run: Option<(String, String)>,
+    pub prompt_running: bool,
+
+    // strftime format for displayed dates (see dates.rs)
+    pub date_format: String,
+    pub narrow_width: u16, // below this terminal width the board shows one column at a time
+    pub max_columns: usize, // --max-columns: columns beyond this collapse into "+N more" (0 = all)
+    pub card_meta: CardMetaPolicy,
+    pub card_drop_order: Vec<CardField>, // --card-meta-drop, first dropped first
+    pub age_colors: bool, // H: card indicators colored by how long since the task changed
+    pub age_days: (u32, u32), // --age-days: (fresh up to, stale from)
+
+    // Save time of the --offline snapshot being browsed
+    pub offline_since: Option<String>,
+
+    // Who is using this client (--user / $USER); empty if unknown
+    pub user: String,
+
+    // Diagnostics for the debug overlay
+    pub server_url: String,
+    pub sse_reconnects: u32,
+
+    // Connecting screen: why the server isn't reachable yet, and `r` pressed there
+    pub connect_error: Option<String>,
+    pub retry_connect: bool,
 }
 
 impl App {
@@ -130,31 +565,818 @@ impl App {
         Self {
             view: View::Board,
             overlay: None,
+            input: None,
             should_quit: false,
+            force_quit: false,
             focus: Focus::Content,
             version: None,
             board: None,
             config: None,
+            scope_colors: HashMap::new(),
+            activity_colors: HashMap::new(),
+            status_colors: HashMap::new(),
             prompts: vec![],
             documents: vec![],
             activity: vec![],
+            prompts_has_more: false,
+            documents_has_more: false,
+            loaded_window: Arc::new(LoadedWindow::new()),
             board_col: 0,
             board_row: vec![],
+            board_scroll: vec![],
+            keep_row: false,
+            wrap_lists: false,
+            confirm: true,
+            read_only: false,
+            active_assignee: None,
+            active_scope: None,
+            active_status: None,
+            board_search: None,
+            board_matches: vec![],
+            search_index: SearchIndex::default(),
             prompt_index: 0,
             document_index: 0,
+            prompt_sort: ResourceSort::Server,
+            document_sort: ResourceSort::Server,
             activity_index: 0,
+            hidden_activity_types: HashSet::new(),
+            column_jump: None,
+            board_sort: BoardSort::Manual,
+            focus_mode: false,
+            line_numbers: false,
+            metadata_table: false,
+            raw_markdown: false,
+            scroll_memory: HashMap::new(),
+            markdown_cache: MarkdownCache::default(),
+            marked: HashSet::new(),
+            watched: Vec::new(),
+            recent: Vec::new(),
+            last_viewed_mtime: None,
             connection: ConnectionState::Connecting,
             last_poll: None,
             poll_hashes: None,
             loading: true,
+            status_message: None,
+            data_dir: PathBuf::from(".mdboard"),
+            dirty: true,
+            hidden_columns: HashSet::new(),
+            hide_empty_columns: false,
+            column_weight_overrides: HashMap::new(),
+            start_column: None,
+            task_link_format: links::DEFAULT_TASK_LINK.to_string(),
+            resource_link_format: links::DEFAULT_RESOURCE_LINK.to_string(),
+            prompt_command: None,
+            prompt_run: None,
+            prompt_running: false,
+            date_format: dates::DEFAULT_DATE_FORMAT.to_string(),
+            narrow_width: DEFAULT_NARROW_WIDTH,
+            max_columns: 0,
+            card_meta: CardMetaPolicy::Truncate,
+            card_drop_order: DEFAULT_CARD_DROP_ORDER.to_vec(),
+            age_colors: false,
+            age_days: DEFAULT_AGE_DAYS,
+            offline_since: None,
+            user: String::new(),
+            server_url: String::new(),
+            sse_reconnects: 0,
+            connect_error: None,
+            retry_connect: false,
         }
     }
 
+    /// Store the server config and recompute state derived from it.
+    pub fn apply_config(&mut self, config: Config) {
+        self.scope_colors = config
+            .scope_colors()
+            .into_iter()
+            .filter_map(|(scope, color)| Some((scope.to_lowercase(), theme::parse_color(&color)?)))
+            .collect();
+        self.activity_colors = config
+            .activity_colors()
+            .into_iter()
+            .filter_map(|(kind, color)| Some((kind.to_lowercase(), theme::parse_color(&color)?)))
+            .collect();
+        self.status_colors = config
+            .status_colors()
+            .into_iter()
+            .filter_map(|(status, color)| Some((status.to_lowercase(), theme::parse_color(&color)?)))
+            .collect();
+        self.config = Some(config);
+    }
+
+    /// Replace the board with a freshly fetched one, applying the column sort.
+    pub fn set_board(&mut self, board: Board) {
+        self.board = Some(board);
+        // Forget marks on tasks that were moved or deleted elsewhere
+        let marked = std::mem::take(&mut self.marked);
+        self.marked = marked
+            .into_iter()
+            .filter(|(column, filename)| self.find_task(column, filename).is_some())
+            .collect();
+        self.sort_board();
+        self.ensure_board_row_vec();
+        self.clamp_indices();
+        // A live update keeps the search, matched against the new tasks
+        self.tasks_changed();
+    }
+
+    /// Re-index the tasks after any change to them and match the board
+    /// search again.
+    fn tasks_changed(&mut self) {
+        self.search_index.index_tasks(self.board.as_ref());
+        self.refresh_board_matches();
+    }
+
+    /// Set or clear the board search. Returns the number of matching tasks.
+    pub fn set_board_search(&mut self, query: Option<String>) -> usize {
+        self.board_search = query.filter(|q| !q.is_empty());
+        self.refresh_board_matches();
+        self.board_matches.len()
+    }
+
+    /// Recompute `board_matches` for the current query from the search
+    /// index: tasks whose title, filename, assignee or scopes match.
+    pub fn refresh_board_matches(&mut self) {
+        let Some(query) = self.board_search.as_deref() else {
+            self.board_matches.clear();
+            return;
+        };
+        let mut matches: Vec<(String, String)> = self
+            .search_index
+            .query(query)
+            .into_iter()
+            .filter(|hit| hit.field != HitField::Body)
+            .filter_map(|hit| match hit.target {
+                QuickOpenTarget::Task { column, filename, .. } => Some((column, filename)),
+                QuickOpenTarget::Resource { .. } => None,
+            })
+            .collect();
+        // Keep board order rather than rank order
+        let position = |(column, filename): &(String, String)| {
+            self.board
+                .iter()
+                .flat_map(|b| &b.columns)
+                .flat_map(|c| c.tasks.iter().map(move |t| (&c.name, &t.filename)))
+                .position(|(c, f)| c == column && f == filename)
+        };
+        matches.sort_by_cached_key(position);
+        self.board_matches = matches;
+    }
+
+    pub fn is_board_match(&self, task: &Task) -> bool {
+        self.board_matches
+            .iter()
+            .any(|(column, filename)| *column == task.column && *filename == task.filename)
+    }
+
+    /// Select the first shown task, in column then row order, that has open
+    /// checkboxes or no `completed` date. Returns false if every task is done.
+    pub fn select_first_incomplete(&mut self) -> bool {
+        let target = self.visible_columns().into_iter().find_map(|col| {
+            let tasks = &self.board.as_ref()?.columns.get(col)?.tasks;
+            self.shown_rows(col)
+                .into_iter()
+                .find(|&row| {
+                    let task = &tasks[row];
+                    let (checked, total) = count_checkboxes(&task.body);
+                    checked < total || task.meta.completed.is_empty()
+                })
+                .map(|row| (col, row))
+        });
+        let Some((col, row)) = target else {
+            return false;
+        };
+        self.board_col = col;
+        self.set_board_row(row);
+        true
+    }
+
+    /// Every task on the board with checkboxes, by completion (highest
+    /// first), then board order.
+    pub fn progress_rows(&self) -> Vec<ProgressRow> {
+        let mut rows: Vec<ProgressRow> = self
+            .board
+            .iter()
+            .flat_map(|b| &b.columns)
+            .flat_map(|c| c.tasks.iter().map(move |t| (c, t)))
+            .filter_map(|(col, task)| {
+                let (checked, total) = count_checkboxes(&task.body);
+                (total > 0).then(|| ProgressRow {
+                    column: col.name.clone(),
+                    filename: task.filename.clone(),
+                    title: task.display_title().to_string(),
+                    checked,
+                    total,
+                })
+            })
+            .collect();
+        // checked/total descending, compared without floats
+        rows.sort_by(|a, b| (b.checked * a.total).cmp(&(a.checked * b.total)));
+        rows
+    }
+
+    /// Select the next (`forward`) or previous shown task, in board order and
+    /// wrapping around, that shares a scope with the selected one. Returns
+    /// the number of other such tasks, so 0 means the selection stayed.
+    pub fn step_shared_scope(&mut self, forward: bool) -> usize {
+        let Some(selected) = self.selected_task() else {
+            return 0;
+        };
+        let scopes: Vec<String> = selected
+            .meta
+            .scopes
+            .as_vec()
+            .iter()
+            .map(|s| s.to_lowercase())
+            .collect();
+        let Some(board) = &self.board else {
+            return 0;
+        };
+        let current = (self.board_col, self.current_board_row());
+        let positions: Vec<(usize, usize)> = self
+            .visible_columns()
+            .into_iter()
+            .flat_map(|col| self.shown_rows(col).into_iter().map(move |row| (col, row)))
+            .filter(|&pos| {
+                pos != current
+                    && board.columns[pos.0].tasks[pos.1]
+                        .meta
+                        .scopes
+                        .as_vec()
+                        .iter()
+                        .any(|s| scopes.contains(&s.to_lowercase()))
+            })
+            .collect();
+        // Board order is column, then row, the same as tuple order
+        let target = if forward {
+            positions.iter().find(|&&pos| pos > current).or(positions.first())
+        } else {
+            positions.iter().rev().find(|&&pos| pos < current).or(positions.last())
+        };
+        if let Some(&(col, row)) = target {
+            self.board_col = col;
+            self.set_board_row(row);
+        }
+        positions.len()
+    }
+
+    /// Select the first matching task at or after the current position,
+    /// wrapping around the board.
+    pub fn select_first_board_match(&mut self) {
+        let Some(board) = &self.board else {
+            return;
+        };
+        let current = (self.board_col, self.current_board_row());
+        let positions: Vec<(usize, usize)> = board
+            .columns
+            .iter()
+            .enumerate()
+            .flat_map(|(ci, c)| c.tasks.iter().enumerate().map(move |(ri, t)| (ci, ri, t)))
+            .filter(|(_, _, t)| self.is_board_match(t) && self.matches_filter(t))
+            .map(|(ci, ri, _)| (ci, ri))
+            .collect();
+        let target = positions
+            .iter()
+            .find(|&&pos| pos >= current)
+            .or(positions.first())
+            .copied();
+        if let Some((col, row)) = target {
+            if self.visible_columns().contains(&col) {
+                self.board_col = col;
+                self.set_board_row(row);
+            }
+        }
+    }
+
+    fn sort_board(&mut self) {
+        let Some(board) = self.board.as_mut() else {
+            return;
+        };
+        if self.board_sort == BoardSort::Priority {
+            for col in &mut board.columns {
+                col.tasks
+                    .sort_by_key(|t| std::cmp::Reverse(t.meta.priority_level().unwrap_or(0)));
+            }
+        }
+    }
+
+    /// Replace a resource list with a freshly fetched first window.
+    pub fn set_resources(&mut self, rtype: ResourceType, page: ResourcePage) {
+        let (list, has_more) = self.resources_mut(rtype);
+        *list = page.items;
+        *has_more = page.has_more;
+        let len = list.len();
+        self.loaded_window.set(rtype, len);
+        self.reindex_resources(rtype);
+    }
+
+    /// Append the next page of a resource list. Items already present are
+    /// skipped, which also stops paging against servers that ignore `offset`.
+    pub fn append_resources(&mut self, rtype: ResourceType, page: ResourcePage) {
+        // A sorted list can place new items before the selected one
+        let selected = self.selected_resource(rtype).map(|r| r.dir_name.clone());
+        let (list, has_more) = self.resources_mut(rtype);
+        let before = list.len();
+        for item in page.items {
+            if !list.iter().any(|r| r.dir_name == item.dir_name) {
+                list.push(item);
+            }
+        }
+        *has_more = page.has_more && list.len() > before;
+        let len = list.len();
+        self.loaded_window.set(rtype, len);
+        self.reindex_resources(rtype);
+        if let Some(dir_name) = selected {
+            self.select_resource(rtype, &dir_name);
+        }
+    }
+
+    fn reindex_resources(&mut self, rtype: ResourceType) {
+        let list = match rtype {
+            ResourceType::Prompt => &self.prompts,
+            ResourceType::Document => &self.documents,
+        };
+        self.search_index.index_resources(rtype, list);
+    }
+
+    fn resources_mut(&mut self, rtype: ResourceType) -> (&mut Vec<Resource>, &mut bool) {
+        match rtype {
+            ResourceType::Prompt => (&mut self.prompts, &mut self.prompts_has_more),
+            ResourceType::Document => (&mut self.documents, &mut self.documents_has_more),
+        }
+    }
+
+    pub fn scope_color(&self, scope: &str) -> Color {
+        self.scope_colors
+            .get(&scope.to_lowercase())
+            .copied()
+            .unwrap_or(theme::SCOPE_FG)
+    }
+
+    /// Pill color for a task status: `settings.status_colors` first, then
+    /// red for blocked and yellow for review. Others get a neutral color.
+    pub fn status_color(&self, status: &str) -> Color {
+        let key = status.to_lowercase();
+        if let Some(color) = self.status_colors.get(&key) {
+            return *color;
+        }
+        match key.as_str() {
+            "blocked" => theme::RED,
+            "review" => theme::YELLOW,
+            _ => theme::TEXT_SECONDARY,
+        }
+    }
+
+    /// A frontmatter date in the configured `--date-format`.
+    pub fn format_date(&self, raw: &str) -> String {
+        dates::format_date(raw, &self.date_format)
+    }
+
+    /// True until the first data arrives from the server; the UI shows the
+    /// connecting screen instead of an empty board meanwhile.
+    pub fn awaiting_first_data(&self) -> bool {
+        self.offline_since.is_none() && self.board.is_none()
+    }
+
+    /// Request a redraw on the next loop iteration.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn set_status(&mut self, msg: impl Into<String>) {
+        self.status_message = Some((msg.into(), Instant::now()));
+        self.dirty = true;
+    }
+
+    /// Drop the status message once it has expired, so it disappears without input.
+    pub fn expire_status(&mut self) {
+        if self.status_message.is_some() && self.active_status().is_none() {
+            self.status_message = None;
+            self.dirty = true;
+        }
+    }
+
+    /// The status message, if it has not yet expired.
+    pub fn active_status(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < STATUS_MESSAGE_TTL)
+            .map(|(msg, _)| msg.as_str())
+    }
+
+    /// Number of visible (not hidden) board columns.
     pub fn column_count(&self) -> usize {
+        self.visible_columns().len()
+    }
+
+    /// Indices into `board.columns` of the columns that are not hidden. With
+    /// `hide_empty_columns` those without tasks are left out too, unless
+    /// that would leave none.
+    pub fn visible_columns(&self) -> Vec<usize> {
+        let Some(board) = &self.board else {
+            return Vec::new();
+        };
+        let shown = |hide_empty: bool| -> Vec<usize> {
+            board
+                .columns
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| !(self.is_column_hidden(c) || hide_empty && c.tasks.is_empty()))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        let visible = shown(self.hide_empty_columns);
+        if visible.is_empty() && self.hide_empty_columns {
+            return shown(false);
+        }
+        visible
+    }
+
+    /// Toggle hiding columns without tasks. Returns whether they are now hidden.
+    pub fn toggle_empty_columns(&mut self) -> bool {
+        self.hide_empty_columns = !self.hide_empty_columns;
+        self.clamp_indices();
+        self.hide_empty_columns
+    }
+
+    /// Relative widths for the board columns at `indices`, from
+    /// --column-weights or the config's `weight`, defaulting to 1. Scaled to
+    /// integers for `Constraint::Ratio`; any zero, negative or unparseable
+    /// weight makes the whole board fall back to equal widths.
+    pub fn column_weights(&self, indices: &[usize]) -> Vec<u32> {
+        let Some(board) = &self.board else {
+            return vec![1; indices.len()];
+        };
+        let config_columns = self.config.as_ref().map(|c| c.columns.as_slice()).unwrap_or_default();
+        let weights: Option<Vec<u32>> = indices
+            .iter()
+            .map(|&i| {
+                let col = board.columns.get(i)?;
+                let raw = [&col.name, &col.label]
+                    .iter()
+                    .find_map(|key| self.column_weight_overrides.get(&key.to_lowercase()))
+                    .or_else(|| {
+                        config_columns
+                            .iter()
+                            .find(|def| def.name == col.name)
+                            .and_then(|def| def.weight.as_ref())
+                    });
+                match raw {
+                    Some(raw) => parse_weight(raw),
+                    None => Some(100),
+                }
+            })
+            .collect();
+        weights.unwrap_or_else(|| vec![1; indices.len()])
+    }
+
+    /// `wip_limit` from the server config for board column `col`. Missing,
+    /// zero or unparseable limits mean no limit.
+    pub fn wip_limit(&self, col: usize) -> Option<usize> {
+        let name = &self.board.as_ref()?.columns.get(col)?.name;
+        self.config
+            .as_ref()?
+            .columns
+            .iter()
+            .find(|def| &def.name == name)?
+            .wip_limit
+            .as_deref()?
+            .trim()
+            .parse()
+            .ok()
+            .filter(|&limit| limit > 0)
+    }
+
+    /// Columns holding more tasks than their WIP limit, as (index, tasks, limit).
+    /// Hidden columns count too.
+    pub fn over_wip_columns(&self) -> Vec<(usize, usize, usize)> {
+        let Some(board) = &self.board else {
+            return vec![];
+        };
+        board
+            .columns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, col)| {
+                let limit = self.wip_limit(i)?;
+                (col.tasks.len() > limit).then_some((i, col.tasks.len(), limit))
+            })
+            .collect()
+    }
+
+    /// Select the next column over its WIP limit after the current one,
+    /// showing it if hidden. Returns false when no column is over.
+    pub fn jump_to_over_wip(&mut self) -> bool {
+        let over: Vec<usize> = self.over_wip_columns().into_iter().map(|(i, _, _)| i).collect();
+        let Some(&col) = over
+            .iter()
+            .find(|&&i| i > self.board_col)
+            .or_else(|| over.first())
+        else {
+            return false;
+        };
+        if let Some(c) = self.board.as_ref().and_then(|b| b.columns.get(col)) {
+            let (name, label) = (c.name.to_lowercase(), c.label.to_lowercase());
+            self.hidden_columns.remove(&name);
+            self.hidden_columns.remove(&label);
+        }
+        self.board_col = col;
+        self.clamp_indices();
+        true
+    }
+
+    /// Activity entries whose type isn't hidden, in feed order.
+    pub fn filtered_activity(&self) -> Vec<&ActivityEntry> {
+        self.activity
+            .iter()
+            .filter(|e| !self.hidden_activity_types.contains(&e.entry_type))
+            .collect()
+    }
+
+    /// Whether `entry` changed after the Activity view was last left. Nothing
+    /// is new before the first visit.
+    pub fn is_new_activity(&self, entry: &ActivityEntry) -> bool {
+        self.last_viewed_mtime.is_some_and(|seen| entry.mtime > seen)
+    }
+
+    /// Move the read marker up to the newest loaded activity entry.
+    pub fn mark_activity_viewed(&mut self) {
+        let newest = self.activity.iter().map(|e| e.mtime).fold(f64::NAN, f64::max);
+        if !newest.is_nan() && self.last_viewed_mtime.is_none_or(|seen| newest > seen) {
+            self.last_viewed_mtime = Some(newest);
+        }
+    }
+
+    pub fn selected_activity(&self) -> Option<&ActivityEntry> {
+        self.filtered_activity().get(self.activity_index).copied()
+    }
+
+    /// Show or hide activity entries of `entry_type`, keeping the selection
+    /// on the same entry when it stays visible. Returns whether it is now shown.
+    pub fn toggle_activity_type(&mut self, entry_type: &str) -> bool {
+        let selected = self
+            .selected_activity()
+            .and_then(|entry| self.activity.iter().position(|e| std::ptr::eq(e, entry)));
+        let shown = self.hidden_activity_types.remove(entry_type);
+        if !shown {
+            self.hidden_activity_types.insert(entry_type.to_string());
+        }
+        self.activity_index = match selected {
+            Some(i) if !self.hidden_activity_types.contains(&self.activity[i].entry_type) => self.activity[..i]
+                .iter()
+                .filter(|e| !self.hidden_activity_types.contains(&e.entry_type))
+                .count(),
+            _ => 0,
+        };
+        shown
+    }
+
+    pub fn is_column_hidden(&self, col: &Column) -> bool {
+        self.hidden_columns.contains(&col.name.to_lowercase())
+            || self.hidden_columns.contains(&col.label.to_lowercase())
+    }
+
+    /// Select the --column column, by name or label, once a board is loaded.
+    /// An unknown name warns and leaves the first column selected; a hidden
+    /// one is revealed.
+    pub fn apply_start_column(&mut self) {
+        if self.board.is_none() {
+            return;
+        }
+        let Some(wanted) = self.start_column.take() else {
+            return;
+        };
+        match self.column_by_name(&wanted) {
+            Some(col) => self.select_column(col),
+            None => {
+                self.board_col = self.visible_columns().first().copied().unwrap_or(0);
+                self.set_status(format!("No column named \"{wanted}\" — showing the first column"));
+            }
+        }
+        self.clamp_indices();
+    }
+
+    /// Move the column selection `delta` columns left/right among those on a
+    /// board `width` cells wide (see `on_screen_columns`). Returns false when
+    /// already at the edge.
+    /// With `keep_row` the row index carries over, clamped to the new column.
+    pub fn step_column(&mut self, delta: isize, width: u16) -> bool {
+        let columns = self.on_screen_columns(width);
+        let Some(pos) = columns.iter().position(|&i| i == self.board_col) else {
+            return false;
+        };
+        let Some(&col) = pos.checked_add_signed(delta).and_then(|p| columns.get(p)) else {
+            return false;
+        };
+        let row = self.current_board_row();
+        self.board_col = col;
+        if self.keep_row {
+            let last = self.current_column_tasks().len().saturating_sub(1);
+            self.set_board_row(row.min(last));
+            self.snap_row(col);
+        }
+        true
+    }
+
+    /// Columns laid out on a board `width` cells wide. With --max-columns,
+    /// the first N visible columns, except that a selected overflow column
+    /// takes the last place. Narrow boards keep every visible column, since
+    /// they show one at a time anyway.
+    pub fn on_screen_columns(&self, width: u16) -> Vec<usize> {
+        let visible = self.visible_columns();
+        if self.max_columns == 0 || visible.len() <= self.max_columns || self.is_narrow(width) {
+            return visible;
+        }
+        let mut shown = visible[..self.max_columns].to_vec();
+        if visible.contains(&self.board_col) && !shown.contains(&self.board_col) {
+            shown[self.max_columns - 1] = self.board_col;
+        }
+        shown
+    }
+
+    /// Visible columns collapsed into the "+N more" indicator.
+    pub fn overflow_columns(&self, width: u16) -> Vec<usize> {
+        let shown = self.on_screen_columns(width);
+        self.visible_columns()
+            .into_iter()
+            .filter(|i| !shown.contains(i))
+            .collect()
+    }
+
+    /// Whether `task` passes the assignee, scope and status filters.
+    pub fn matches_filter(&self, task: &Task) -> bool {
+        self.active_assignee
+            .as_ref()
+            .is_none_or(|name| task.meta.assignee.eq_ignore_ascii_case(name))
+            && self.active_scope.as_ref().is_none_or(|scope| {
+                task.meta.scopes.as_vec().iter().any(|s| s.eq_ignore_ascii_case(scope))
+            })
+            && self
+                .active_status
+                .as_ref()
+                .is_none_or(|status| task.meta.status().is_some_and(|s| s.eq_ignore_ascii_case(status)))
+    }
+
+    pub fn has_filter(&self) -> bool {
+        self.active_assignee.is_some() || self.active_scope.is_some() || self.active_status.is_some()
+    }
+
+    pub fn clear_filters(&mut self) {
+        self.active_assignee = None;
+        self.active_scope = None;
+        self.active_status = None;
+    }
+
+    /// Select board column `col`, revealing it if it was hidden.
+    pub fn select_column(&mut self, col: usize) {
+        if let Some(c) = self.board.as_ref().and_then(|b| b.columns.get(col)) {
+            let (name, label) = (c.name.to_lowercase(), c.label.to_lowercase());
+            self.hidden_columns.remove(&name);
+            self.hidden_columns.remove(&label);
+            if c.tasks.is_empty() {
+                self.hide_empty_columns = false;
+            }
+        }
+        self.board_col = col;
+        self.clamp_indices();
+    }
+
+    /// Index of the board column with this name or label, case-insensitively.
+    pub fn column_by_name(&self, wanted: &str) -> Option<usize> {
+        let key = wanted.trim().to_lowercase();
+        self.board
+            .as_ref()?
+            .columns
+            .iter()
+            .position(|c| c.name.to_lowercase() == key || c.label.to_lowercase() == key)
+    }
+
+    /// Rows of column `col` that pass the filter.
+    pub fn shown_rows(&self, col: usize) -> Vec<usize> {
         self.board
             .as_ref()
-            .map(|b| b.columns.len())
-            .unwrap_or(0)
+            .and_then(|b| b.columns.get(col))
+            .map(|c| {
+                c.tasks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| self.matches_filter(t))
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Move the row selection `delta` shown tasks down/up. Returns false when
+    /// already at the edge.
+    pub fn step_row(&mut self, delta: isize) -> bool {
+        let rows = self.shown_rows(self.board_col);
+        let row = self.current_board_row();
+        let Some(pos) = rows.iter().position(|&r| r == row) else {
+            return false;
+        };
+        match pos.checked_add_signed(delta).and_then(|p| rows.get(p)) {
+            Some(&next) => {
+                self.set_board_row(next);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Select the first (`last == false`) or last shown task of the column.
+    pub fn select_edge_row(&mut self, last: bool) {
+        let rows = self.shown_rows(self.board_col);
+        let edge = if last { rows.last() } else { rows.first() };
+        if let Some(&row) = edge {
+            self.set_board_row(row);
+        }
+    }
+
+    /// Cycle the assignee filter through the board's assignees and back to
+    /// showing everyone. Returns the new filter.
+    pub fn cycle_assignee_filter(&mut self) -> Option<String> {
+        let names = self.task_values(|t| Some(t.meta.assignee.as_str()));
+        self.active_assignee = next_value(&names, self.active_assignee.as_deref());
+        self.clamp_indices();
+        self.active_assignee.clone()
+    }
+
+    /// Cycle the status filter through the board's statuses and back to
+    /// showing every task. Returns the new filter.
+    pub fn cycle_status_filter(&mut self) -> Option<String> {
+        let statuses = self.task_values(|t| t.meta.status());
+        self.active_status = next_value(&statuses, self.active_status.as_deref());
+        self.clamp_indices();
+        self.active_status.clone()
+    }
+
+    /// Distinct non-empty values of a task field across the board, sorted
+    /// case-insensitively.
+    fn task_values<'a>(&'a self, field: impl Fn(&'a Task) -> Option<&'a str>) -> Vec<String> {
+        let mut values: Vec<String> = self
+            .board
+            .iter()
+            .flat_map(|b| &b.columns)
+            .flat_map(|c| &c.tasks)
+            .filter_map(field)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect();
+        values.sort_by_key(|value| value.to_lowercase());
+        values.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        values
+    }
+
+    /// Move column `col`'s row off a filtered-out task, to the next shown one
+    /// or else the previous.
+    fn snap_row(&mut self, col: usize) {
+        let rows = self.shown_rows(col);
+        let Some(row) = self.board_row.get(col).copied() else {
+            return;
+        };
+        if rows.is_empty() || rows.contains(&row) {
+            return;
+        }
+        let target = rows
+            .iter()
+            .find(|&&r| r > row)
+            .or(rows.last())
+            .copied()
+            .unwrap_or(0);
+        self.board_row[col] = target;
+    }
+
+    /// Whether a board of this width uses the single-column layout.
+    pub fn is_narrow(&self, width: u16) -> bool {
+        width < self.narrow_width
+    }
+
+    /// 1-based position of the selected column among the visible ones, and
+    /// how many are visible.
+    pub fn column_position(&self) -> Option<(usize, usize)> {
+        let visible = self.visible_columns();
+        let pos = visible.iter().position(|&i| i == self.board_col)?;
+        Some((pos + 1, visible.len()))
+    }
+
+    /// Hide the selected column, moving the selection to a neighbouring visible one.
+    /// Returns false if it is the last visible column.
+    pub fn hide_current_column(&mut self) -> bool {
+        if self.column_count() <= 1 {
+            return false;
+        }
+        let Some(name) = self
+            .board
+            .as_ref()
+            .and_then(|b| b.columns.get(self.board_col))
+            .map(|c| c.name.to_lowercase())
+        else {
+            return false;
+        };
+        self.hidden_columns.insert(name);
+        self.clamp_indices();
+        true
     }
 
     pub fn current_column_tasks(&self) -> &[Task] {
@@ -177,16 +1399,693 @@ impl App {
             self.board_row.push(0);
         }
         self.board_row[self.board_col] = row;
+        // Moving the selection brings it back into view
+        if let Some(scroll) = self.board_scroll.get_mut(self.board_col) {
+            *scroll = None;
+        }
+    }
+
+    /// Scroll column `col` to start at shown card `start`, independent of
+    /// the selection.
+    pub fn set_board_scroll(&mut self, col: usize, start: usize) {
+        if self.board_scroll.len() <= col {
+            self.board_scroll.resize(col + 1, None);
+        }
+        self.board_scroll[col] = Some(start);
+    }
+
+    /// Label shown over column `i` while a column jump is pending. Labels
+    /// number visible columns only.
+    pub fn column_jump_label(&self, i: usize) -> Option<String> {
+        let pos = self.visible_columns().iter().position(|&c| c == i)?;
+        match self.column_jump? {
+            ColumnJump::Number | ColumnJump::MoveMarked if pos < 9 => Some((pos + 1).to_string()),
+            ColumnJump::Hint => COLUMN_HINT_KEYS.get(pos).map(|c| c.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Resolve a key pressed while a column jump is pending to a column index.
+    pub fn column_jump_target(&self, c: char) -> Option<usize> {
+        let pos = match self.column_jump? {
+            ColumnJump::Number | ColumnJump::MoveMarked => {
+                c.to_digit(10).filter(|d| *d > 0).map(|d| d as usize - 1)
+            }
+            ColumnJump::Hint => COLUMN_HINT_KEYS.iter().position(|h| *h == c),
+        }?;
+        self.visible_columns().get(pos).copied()
+    }
+
+    /// The find query of the open task/resource overlay, if any.
+    pub fn overlay_search(&self) -> Option<&str> {
+        match &self.overlay {
+            Some(Overlay::TaskDetail { search, .. }) | Some(Overlay::ResourceDetail { search, .. }) => {
+                search.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// `<details>` sections toggled from their default state in the open
+    /// task or resource overlay.
+    pub fn overlay_sections(&self) -> &BTreeSet<usize> {
+        static NONE: BTreeSet<usize> = BTreeSet::new();
+        match &self.overlay {
+            Some(Overlay::TaskDetail { sections, .. }) | Some(Overlay::ResourceDetail { sections, .. }) => {
+                sections
+            }
+            _ => &NONE,
+        }
+    }
+
+    /// Markdown body shown by the task or resource overlay (the viewed
+    /// revision's body when browsing revisions).
+    pub fn overlay_body(&self) -> Option<&str> {
+        match &self.overlay {
+            Some(Overlay::TaskDetail { task, .. }) => Some(&task.body),
+            Some(Overlay::ResourceDetail {
+                resource,
+                revisions,
+                current_rev,
+                ..
+            }) => Some(
+                current_rev
+                    .and_then(|idx| revisions.get(idx))
+                    .map_or(&resource.body, |rev| &rev.body),
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn set_overlay_search(&mut self, query: Option<String>) {
+        if let Some(Overlay::TaskDetail { search, .. }) | Some(Overlay::ResourceDetail { search, .. }) =
+            &mut self.overlay
+        {
+            *search = query;
+        }
+    }
+
+    /// Mark or unmark the selected task for a bulk action.
+    pub fn toggle_marked(&mut self) {
+        let Some(key) = self
+            .selected_task()
+            .map(|t| (t.column.clone(), t.filename.clone()))
+        else {
+            return;
+        };
+        if !self.marked.remove(&key) {
+            self.marked.insert(key);
+        }
+    }
+
+    pub fn is_marked(&self, task: &Task) -> bool {
+        self.marked
+            .contains(&(task.column.clone(), task.filename.clone()))
+    }
+
+    /// Watch or unwatch the selected task. Returns the new state and the
+    /// task's title, or None with nothing selected.
+    pub fn toggle_watch(&mut self) -> Option<(bool, String)> {
+        let task = self.selected_task()?;
+        let title = task.display_title().to_string();
+        if let Some(pos) = self.watched.iter().position(|w| w.filename == task.filename) {
+            self.watched.remove(pos);
+            return Some((false, title));
+        }
+        self.watched.push(WatchedTask {
+            column: task.column.clone(),
+            filename: task.filename.clone(),
+            title: title.clone(),
+            hash: task_hash(task),
+        });
+        Some((true, title))
+    }
+
+    pub fn is_watched(&self, task: &Task) -> bool {
+        self.watched.iter().any(|w| w.filename == task.filename)
+    }
+
+    /// Compare watched tasks against the loaded board and describe what
+    /// changed since the last check. Deleted tasks stop being watched.
+    pub fn check_watched(&mut self) -> Vec<String> {
+        let Some(board) = self.board.as_ref() else {
+            return Vec::new();
+        };
+        let mut notes = Vec::new();
+        self.watched.retain_mut(|watched| {
+            let Some(task) = board
+                .columns
+                .iter()
+                .flat_map(|c| c.tasks.iter())
+                .find(|t| t.filename == watched.filename)
+            else {
+                notes.push(format!("Watched task \"{}\" was deleted", watched.title));
+                return false;
+            };
+            let hash = task_hash(task);
+            if task.column != watched.column {
+                let label = board
+                    .columns
+                    .iter()
+                    .find(|c| c.name == task.column)
+                    .map(|c| c.label.as_str())
+                    .filter(|l| !l.is_empty())
+                    .unwrap_or(&task.column);
+                notes.push(format!(
+                    "Watched task \"{}\" moved to {label}",
+                    task.display_title()
+                ));
+            } else if hash != watched.hash {
+                notes.push(format!("Watched task \"{}\" was updated", task.display_title()));
+            }
+            watched.column = task.column.clone();
+            watched.title = task.display_title().to_string();
+            watched.hash = hash;
+            true
+        });
+        notes
+    }
+
+    /// Assignees across the board plus the current user, sorted, each once
+    /// regardless of case.
+    pub fn known_assignees(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .board
+            .iter()
+            .flat_map(|b| &b.columns)
+            .flat_map(|c| &c.tasks)
+            .map(|t| t.meta.assignee.clone())
+            .chain(std::iter::once(self.user.clone()))
+            .filter(|name| !name.is_empty())
+            .collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        names
+    }
+
+    /// Scopes from the server config and those used on the board, sorted.
+    pub fn known_scopes(&self) -> Vec<String> {
+        let mut scopes: Vec<String> = Vec::new();
+        let used = self
+            .board
+            .iter()
+            .flat_map(|b| &b.columns)
+            .flat_map(|c| &c.tasks)
+            .flat_map(|t| t.meta.scopes.as_vec());
+        let configured = self.config.iter().flat_map(|c| c.scopes.iter().map(|s| s.as_str()));
+        for scope in configured.chain(used) {
+            if !scope.is_empty() && !scopes.iter().any(|s| s.eq_ignore_ascii_case(scope)) {
+                scopes.push(scope.to_string());
+            }
+        }
+        scopes.sort_by_key(|s| s.to_lowercase());
+        scopes
+    }
+
+    /// Set a task's assignee locally, ahead of the server confirming it.
+    pub fn set_task_assignee(&mut self, column: &str, filename: &str, assignee: &str) {
+        let task = self
+            .board
+            .as_mut()
+            .and_then(|b| b.columns.iter_mut().find(|c| c.name == column))
+            .and_then(|c| c.tasks.iter_mut().find(|t| t.filename == filename));
+        if let Some(task) = task {
+            task.meta.assignee = assignee.to_string();
+        }
+    }
+
+    /// Take a task off the board ahead of the server deleting it. Returns its
+    /// row, for `restore_task` if the delete fails.
+    pub fn remove_task(&mut self, column: &str, filename: &str) -> Option<(usize, Task)> {
+        let col = self.board.as_mut()?.columns.iter_mut().find(|c| c.name == column)?;
+        let row = col.tasks.iter().position(|t| t.filename == filename)?;
+        let task = col.tasks.remove(row);
+        self.marked.retain(|(c, f)| !(c == column && f == filename));
+        self.tasks_changed();
+        self.clamp_indices();
+        Some((row, task))
+    }
+
+    /// Put a task taken by `remove_task` back at its row and select it.
+    pub fn restore_task(&mut self, row: usize, task: Task) {
+        let Some(col) = self
+            .board
+            .as_mut()
+            .and_then(|b| b.columns.iter_mut().find(|c| c.name == task.column))
+        else {
+            return;
+        };
+        let (column, filename) = (task.column.clone(), task.filename.clone());
+        col.tasks.insert(row.min(col.tasks.len()), task);
+        self.tasks_changed();
+        self.select_task(&column, &filename);
+    }
+
+    /// Move the selected task past its neighbour `delta` shown rows away,
+    /// keeping it selected; tasks hidden by a filter stay where they are.
+    /// Returns (column, filename, new index), or None at the edges.
+    pub fn move_selected_task(&mut self, delta: isize) -> Option<(String, String, usize)> {
+        let rows = self.shown_rows(self.board_col);
+        let row = self.current_board_row();
+        let pos = rows.iter().position(|&r| r == row)?;
+        let target = *rows.get(pos.checked_add_signed(delta)?)?;
+        let col = self.board.as_mut()?.columns.get_mut(self.board_col)?;
+        // Same as the server's reorder: take the task out, insert it at `target`
+        let task = col.tasks.remove(row);
+        col.tasks.insert(target, task);
+        let moved = (col.name.clone(), col.tasks[target].filename.clone(), target);
+        self.set_board_row(target);
+        Some(moved)
+    }
+
+    /// Replace a single task in place (or move it to its new column) without
+    /// re-fetching the board. The selection stays on the same task where possible.
+    pub fn apply_task_update(&mut self, task: Task) {
+        let selected = self
+            .selected_task()
+            .map(|t| (t.column.clone(), t.filename.clone()));
+        let Some(board) = self.board.as_mut() else {
+            return;
+        };
+
+        let mut replaced = false;
+        for col in &mut board.columns {
+            if let Some(pos) = col.tasks.iter().position(|t| t.filename == task.filename) {
+                if col.name == task.column {
+                    col.tasks[pos] = task.clone();
+                    replaced = true;
+                } else {
+                    col.tasks.remove(pos);
+                }
+            }
+        }
+        if !replaced {
+            if let Some(col) = board.columns.iter_mut().find(|c| c.name == task.column) {
+                col.tasks.push(task.clone());
+            }
+        }
+        self.sort_board();
+        self.tasks_changed();
+
+        if let Some((column, filename)) = selected {
+            // The selected task may have moved columns; follow it by filename
+            let column = if filename == task.filename {
+                task.column
+            } else {
+                column
+            };
+            if !self.select_task(&column, &filename) {
+                self.clamp_indices();
+            }
+        }
+    }
+
+    /// Remember what is selected in each view; see `restore_selection`.
+    pub fn selection_anchor(&self) -> SelectionAnchor {
+        SelectionAnchor {
+            task: self
+                .selected_task()
+                .map(|t| (t.column.clone(), t.filename.clone())),
+            prompt: self
+                .selected_resource(ResourceType::Prompt)
+                .map(|r| r.dir_name.clone()),
+            document: self
+                .selected_resource(ResourceType::Document)
+                .map(|r| r.dir_name.clone()),
+        }
+    }
+
+    /// Select the anchored items again after the data was replaced. A task
+    /// moved meanwhile is followed to its new column; items that are gone
+    /// leave the clamped selection as it is.
+    pub fn restore_selection(&mut self, anchor: SelectionAnchor) {
+        if let Some((column, filename)) = anchor.task {
+            let column = self
+                .find_task(&column, &filename)
+                .map(|_| column.clone())
+                .or_else(|| {
+                    let board = self.board.as_ref()?;
+                    board
+                        .columns
+                        .iter()
+                        .find(|c| c.tasks.iter().any(|t| t.filename == filename))
+                        .map(|c| c.name.clone())
+                });
+            if let Some(column) = column {
+                self.select_task(&column, &filename);
+            }
+        }
+        if let Some(dir_name) = anchor.prompt {
+            self.select_resource(ResourceType::Prompt, &dir_name);
+        }
+        if let Some(dir_name) = anchor.document {
+            self.select_resource(ResourceType::Document, &dir_name);
+        }
+    }
+
+    /// Move the board selection to the given task. Returns false if not found.
+    pub fn select_task(&mut self, column: &str, filename: &str) -> bool {
+        let Some((col, row)) = self.board.as_ref().and_then(|b| {
+            b.columns.iter().enumerate().find_map(|(ci, c)| {
+                (c.name == column)
+                    .then(|| c.tasks.iter().position(|t| t.filename == filename))
+                    .flatten()
+                    .map(|ri| (ci, ri))
+            })
+        }) else {
+            return false;
+        };
+        // Jumping to a task in a hidden column (or filtered out) reveals it
+        let filtered_out = self
+            .board
+            .as_ref()
+            .and_then(|b| b.columns.get(col)?.tasks.get(row))
+            .is_some_and(|t| !self.matches_filter(t));
+        if filtered_out {
+            self.clear_filters();
+        }
+        if let Some(c) = self.board.as_ref().and_then(|b| b.columns.get(col)) {
+            let (name, label) = (c.name.to_lowercase(), c.label.to_lowercase());
+            self.hidden_columns.remove(&name);
+            self.hidden_columns.remove(&label);
+        }
+        self.board_col = col;
+        self.set_board_row(row);
+        true
     }
 
     pub fn selected_task(&self) -> Option<&Task> {
         let tasks = self.current_column_tasks();
         let row = self.current_board_row();
-        tasks.get(row)
+        tasks.get(row).filter(|t| self.matches_filter(t))
+    }
+
+    /// Look up a loaded task by column and filename.
+    pub fn find_task(&self, column: &str, filename: &str) -> Option<&Task> {
+        self.board
+            .as_ref()?
+            .columns
+            .iter()
+            .find(|c| c.name == column)?
+            .tasks
+            .iter()
+            .find(|t| t.filename == filename)
+    }
+
+    /// Column currently holding the task with this filename.
+    pub fn task_column(&self, filename: &str) -> Option<&str> {
+        self.board
+            .as_ref()?
+            .columns
+            .iter()
+            .find(|c| c.tasks.iter().any(|t| t.filename == filename))
+            .map(|c| c.name.as_str())
+    }
+
+    /// Column and filename of the loaded task with frontmatter `id`. Numeric
+    /// ids match their zero-padded string forms (`7` finds `"007"`).
+    pub fn locate_task_by_id(&self, id: &serde_json::Value) -> Option<(String, String)> {
+        fn key(id: &serde_json::Value) -> Option<String> {
+            let text = match id {
+                serde_json::Value::String(s) => s.trim().to_string(),
+                serde_json::Value::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            Some(match text.parse::<u64>() {
+                Ok(n) => n.to_string(),
+                Err(_) => text,
+            })
+        }
+        let wanted = key(id)?;
+        self.board.as_ref()?.columns.iter().find_map(|c| {
+            c.tasks
+                .iter()
+                .find(|t| t.meta.id.as_ref().and_then(key).as_ref() == Some(&wanted))
+                .map(|t| (c.name.clone(), t.filename.clone()))
+        })
+    }
+
+    /// Look up a loaded prompt or document by directory name.
+    pub fn find_resource(&self, rtype: ResourceType, dir_name: &str) -> Option<&Resource> {
+        let list = match rtype {
+            ResourceType::Prompt => &self.prompts,
+            ResourceType::Document => &self.documents,
+        };
+        list.iter().find(|r| r.dir_name == dir_name)
+    }
+
+    /// Bring the open task or resource overlay up to date with freshly
+    /// polled data, keeping its scroll position. An item missing from the
+    /// new data is marked gone; resources only count as missing when the
+    /// whole list is loaded.
+    pub fn refresh_open_overlay(&mut self) {
+        match &self.overlay {
+            Some(Overlay::TaskDetail { task, .. }) => {
+                let Some(board) = &self.board else {
+                    return;
+                };
+                let fresh = board
+                    .columns
+                    .iter()
+                    .flat_map(|c| &c.tasks)
+                    .find(|t| t.filename == task.filename)
+                    .filter(|t| t.column != task.column || task_hash(t) != task_hash(task))
+                    .cloned();
+                let missing = !board.columns.iter().any(|c| c.tasks.iter().any(|t| t.filename == task.filename));
+                if let Some(Overlay::TaskDetail { task, visual, gone, .. }) = &mut self.overlay {
+                    if let Some(fresh) = fresh {
+                        *task = fresh;
+                        *visual = None;
+                    }
+                    *gone = missing;
+                }
+            }
+            Some(Overlay::ResourceDetail {
+                resource,
+                resource_type,
+                ..
+            }) => {
+                let rtype = *resource_type;
+                let current = self.find_resource(rtype, &resource.dir_name);
+                let fresh = current
+                    .filter(|r| r.body != resource.body || r.meta.revision != resource.meta.revision)
+                    .cloned();
+                let has_more = match rtype {
+                    ResourceType::Prompt => self.prompts_has_more,
+                    ResourceType::Document => self.documents_has_more,
+                };
+                let missing = current.is_none() && !has_more;
+                if let Some(Overlay::ResourceDetail {
+                    resource,
+                    current_rev,
+                    visual,
+                    gone,
+                    ..
+                }) = &mut self.overlay
+                {
+                    if let Some(fresh) = fresh {
+                        *resource = fresh;
+                        if current_rev.is_none() {
+                            *visual = None;
+                        }
+                    }
+                    *gone = missing;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn selected_resource(&self, rtype: ResourceType) -> Option<&Resource> {
+        let (list, index) = match rtype {
+            ResourceType::Prompt => (&self.prompts, self.prompt_index),
+            ResourceType::Document => (&self.documents, self.document_index),
+        };
+        let i = *self.shown_resources(rtype).get(index)?;
+        list.get(i)
+    }
+
+    /// Indices into the prompt or document list in the order it is shown.
+    pub fn shown_resources(&self, rtype: ResourceType) -> Vec<usize> {
+        match rtype {
+            ResourceType::Prompt => self.prompt_sort.order(&self.prompts),
+            ResourceType::Document => self.document_sort.order(&self.documents),
+        }
+    }
+
+    /// Select a loaded prompt or document by directory name. Returns false
+    /// if it is not loaded.
+    pub fn select_resource(&mut self, rtype: ResourceType, dir_name: &str) -> bool {
+        let list = match rtype {
+            ResourceType::Prompt => &self.prompts,
+            ResourceType::Document => &self.documents,
+        };
+        let Some(pos) = self
+            .shown_resources(rtype)
+            .iter()
+            .position(|&i| list[i].dir_name == dir_name)
+        else {
+            return false;
+        };
+        match rtype {
+            ResourceType::Prompt => self.prompt_index = pos,
+            ResourceType::Document => self.document_index = pos,
+        }
+        true
+    }
+
+    /// `s` in a list view: sort by the next field, keeping the selected item.
+    pub fn cycle_resource_sort(&mut self, rtype: ResourceType) -> ResourceSort {
+        let selected = self.selected_resource(rtype).map(|r| r.dir_name.clone());
+        let sort = match rtype {
+            ResourceType::Prompt => &mut self.prompt_sort,
+            ResourceType::Document => &mut self.document_sort,
+        };
+        *sort = sort.next();
+        let sort = *sort;
+        if let Some(dir_name) = selected {
+            self.select_resource(rtype, &dir_name);
+        }
+        sort
+    }
+
+    /// Path from the current view down to the selected item or open overlay,
+    /// e.g. `["Board", "In Progress", "\"Fix login bug\""]`.
+    pub fn breadcrumb(&self) -> Vec<String> {
+        let mut crumbs = vec![self.view.label().to_string()];
+        match &self.overlay {
+            Some(Overlay::TaskDetail { task, .. }) => {
+                if let Some(col) = self.board.as_ref().and_then(|b| {
+                    b.columns.iter().find(|c| c.name == task.column)
+                }) {
+                    crumbs.push(col.display_label().to_string());
+                }
+                crumbs.push(format!("\"{}\"", task.display_title()));
+                return crumbs;
+            }
+            Some(Overlay::ResourceDetail {
+                resource,
+                revisions,
+                current_rev,
+                ..
+            }) => {
+                let rev = match current_rev {
+                    Some(idx) => revisions.get(*idx).and_then(|r| r.meta.revision),
+                    None => resource.meta.revision,
+                };
+                let mut crumb = format!("\"{}\"", resource.display_title());
+                if let Some(rev) = rev {
+                    crumb.push_str(&format!(" (rev {rev})"));
+                }
+                crumbs.push(crumb);
+                return crumbs;
+            }
+            _ => {}
+        }
+
+        match self.view {
+            View::Board => {
+                if let Some(col) = self.board.as_ref().and_then(|b| b.columns.get(self.board_col)) {
+                    crumbs.push(col.display_label().to_string());
+                }
+                if let Some(task) = self.selected_task() {
+                    crumbs.push(format!("\"{}\"", task.display_title()));
+                }
+            }
+            View::Prompts | View::Documents => {
+                let rtype = if self.view == View::Prompts {
+                    ResourceType::Prompt
+                } else {
+                    ResourceType::Document
+                };
+                if let Some(res) = self.selected_resource(rtype) {
+                    crumbs.push(format!("\"{}\"", res.display_title()));
+                }
+            }
+            View::Activity => {
+                if let Some(entry) = self.selected_activity() {
+                    crumbs.push(format!("\"{}\"", entry.title));
+                }
+            }
+        }
+        match &self.overlay {
+            Some(Overlay::Help { .. }) => crumbs.push("Help".to_string()),
+            Some(Overlay::NewTask { .. }) => crumbs.push("New task".to_string()),
+            _ => {}
+        }
+        crumbs
+    }
+
+    /// Identity and content hash of the open task or current-revision
+    /// resource, for remembering its scroll position.
+    fn scroll_memory_key(&self) -> Option<(String, u64)> {
+        match &self.overlay {
+            Some(Overlay::TaskDetail { task, .. }) => {
+                Some((format!("task:{}", task.filename), task_hash(task)))
+            }
+            Some(Overlay::ResourceDetail {
+                resource,
+                resource_type,
+                current_rev: None,
+                ..
+            }) => {
+                use std::hash::{Hash, Hasher};
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                resource.meta.revision.hash(&mut hasher);
+                resource.body.hash(&mut hasher);
+                let key = format!("{}:{}", resource_type.collection(), resource.dir_name);
+                Some((key, hasher.finish()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Remember the open overlay's scroll position, before closing it.
+    pub fn remember_scroll(&mut self) {
+        let scroll = match &self.overlay {
+            Some(Overlay::TaskDetail { scroll, .. }) | Some(Overlay::ResourceDetail { scroll, .. }) => {
+                *scroll
+            }
+            _ => return,
+        };
+        if let Some((key, hash)) = self.scroll_memory_key() {
+            self.scroll_memory.insert(key, (hash, scroll));
+        }
+    }
+
+    /// Scroll a just-opened overlay to where it was left, unless its content
+    /// changed since.
+    pub fn restore_scroll(&mut self) {
+        let Some((key, hash)) = self.scroll_memory_key() else {
+            return;
+        };
+        let remembered = match self.scroll_memory.get(&key) {
+            Some(&(h, scroll)) if h == hash => scroll,
+            Some(_) => {
+                self.scroll_memory.remove(&key);
+                return;
+            }
+            None => return,
+        };
+        if let Some(Overlay::TaskDetail { scroll, .. }) | Some(Overlay::ResourceDetail { scroll, .. }) =
+            &mut self.overlay
+        {
+            *scroll = remembered;
+        }
+    }
+
+    /// Terminal window title: `mdboard: MyProject — Board`.
+    pub fn window_title(&self) -> String {
+        match &self.version {
+            Some(v) if !v.project.is_empty() => {
+                format!("mdboard: {} — {}", v.project, self.view.label())
+            }
+            _ => format!("mdboard — {}", self.view.label()),
+        }
     }
 
     pub fn ensure_board_row_vec(&mut self) {
-        let ncols = self.column_count();
+        let ncols = self.board.as_ref().map_or(0, |b| b.columns.len());
         if self.board_row.len() < ncols {
             self.board_row.resize(ncols, 0);
         }
@@ -194,9 +2093,16 @@ impl App {
 
     /// Clamp all navigation indices to valid ranges.
     pub fn clamp_indices(&mut self) {
-        let ncols = self.column_count();
-        if ncols > 0 && self.board_col >= ncols {
-            self.board_col = ncols - 1;
+        // Keep the selection on a visible column, preferring the nearest one to the left
+        let visible = self.visible_columns();
+        if !visible.is_empty() && !visible.contains(&self.board_col) {
+            self.board_col = visible
+                .iter()
+                .rev()
+                .find(|&&i| i < self.board_col)
+                .or(visible.first())
+                .copied()
+                .unwrap_or(0);
         }
         self.ensure_board_row_vec();
         if let Some(board) = &self.board {
@@ -208,14 +2114,769 @@ impl App {
                 }
             }
         }
+        for col in 0..self.board_row.len() {
+            self.snap_row(col);
+        }
+        // Manual scroll can't start past a column's last shown task; rendering
+        // clamps further so the column doesn't scroll past its end
+        let ncols = self.board.as_ref().map_or(0, |b| b.columns.len());
+        self.board_scroll.truncate(ncols);
+        for col in 0..self.board_scroll.len() {
+            let shown = self.shown_rows(col).len();
+            if let Some(start) = self.board_scroll[col] {
+                self.board_scroll[col] = shown.checked_sub(1).map(|last| start.min(last));
+            }
+        }
         if !self.prompts.is_empty() && self.prompt_index >= self.prompts.len() {
             self.prompt_index = self.prompts.len() - 1;
         }
         if !self.documents.is_empty() && self.document_index >= self.documents.len() {
             self.document_index = self.documents.len() - 1;
         }
-        if !self.activity.is_empty() && self.activity_index >= self.activity.len() {
-            self.activity_index = self.activity.len() - 1;
+        let shown = self.filtered_activity().len();
+        if shown > 0 && self.activity_index >= shown {
+            self.activity_index = shown - 1;
+        }
+    }
+}
+
+/// Hash of a task's frontmatter and body, used to notice edits to watched
+/// tasks. Unmodeled fields are hashed in sorted order so the result does not
+/// depend on map iteration order.
+fn task_hash(task: &Task) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let meta = &task.meta;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    meta.title.hash(&mut hasher);
+    meta.assignee.hash(&mut hasher);
+    meta.scopes.as_vec().hash(&mut hasher);
+    meta.priority.hash(&mut hasher);
+    meta.status.hash(&mut hasher);
+    meta.due.hash(&mut hasher);
+    meta.branch.hash(&mut hasher);
+    meta.completed.hash(&mut hasher);
+    meta.extra_fields().hash(&mut hasher);
+    task.body.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The value after `current` in `values` (case-insensitively), the first
+/// one without a current value, and None after the last.
+fn next_value(values: &[String], current: Option<&str>) -> Option<String> {
+    match current {
+        None => values.first(),
+        Some(current) => values
+            .iter()
+            .position(|v| v.eq_ignore_ascii_case(current))
+            .and_then(|i| values.get(i + 1)),
+    }
+    .cloned()
+}
+
+/// A positive weight in hundredths, so `1.5` and `2` keep their ratio.
+fn parse_weight(raw: &str) -> Option<u32> {
+    let weight: f64 = raw.trim().parse().ok()?;
+    if !weight.is_finite() || weight <= 0.0 || weight > 10_000.0 {
+        return None;
+    }
+    Some(((weight * 100.0).round() as u32).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(json: serde_json::Value) -> Board {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn assignee_options_offer_unassign_and_new_names() {
+        let known = vec!["alice".to_string(), "bob".to_string()];
+        assert_eq!(assignee_options(&known, ""), vec!["", "alice", "bob"]);
+        assert_eq!(assignee_options(&known, "AL"), vec!["AL", "alice"]);
+        assert_eq!(assignee_options(&known, "Alice"), vec!["alice"]);
+        assert_eq!(assignee_options(&known, "carol"), vec!["carol"]);
+    }
+
+    #[test]
+    fn max_columns_collapses_the_rest_and_reveals_the_selection() {
+        let columns: Vec<serde_json::Value> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|name| serde_json::json!({"name": name, "tasks": []}))
+            .collect();
+        let mut app = App::new();
+        app.set_board(board(serde_json::json!({"columns": columns})));
+        app.max_columns = 3;
+        assert_eq!(app.on_screen_columns(200), vec![0, 1, 2]);
+        assert_eq!(app.overflow_columns(200), vec![3, 4]);
+
+        app.board_col = 2;
+        assert!(!app.step_column(1, 200));
+        app.board_col = 4;
+        assert_eq!(app.on_screen_columns(200), vec![0, 1, 4]);
+        assert_eq!(app.overflow_columns(200), vec![2, 3]);
+
+        // Narrow boards step through every column one at a time
+        assert_eq!(app.on_screen_columns(40), vec![0, 1, 2, 3, 4]);
+        app.max_columns = 0;
+        assert!(app.overflow_columns(200).is_empty());
+    }
+
+    #[test]
+    fn removed_tasks_can_be_restored_in_place() {
+        let initial = board(serde_json::json!({"columns": [
+            {"name": "todo", "tasks": [
+                {"filename": "001-a.md", "column": "todo", "meta": {}, "body": ""},
+                {"filename": "002-b.md", "column": "todo", "meta": {}, "body": ""},
+            ]},
+        ]}));
+        let mut app = App::new();
+        app.set_board(initial);
+        app.set_board_row(1);
+        app.toggle_marked();
+
+        let (row, task) = app.remove_task("todo", "002-b.md").unwrap();
+        assert_eq!(row, 1);
+        assert_eq!(app.current_board_row(), 0);
+        assert!(app.marked.is_empty());
+        assert!(app.remove_task("todo", "002-b.md").is_none());
+
+        app.set_board_row(0);
+        app.restore_task(row, task);
+        assert_eq!(app.selected_task().unwrap().filename, "002-b.md");
+        assert_eq!(app.current_column_tasks().len(), 2);
+    }
+
+    #[test]
+    fn wip_limits_flag_full_columns_and_jump_between_them() {
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [
+                    {"filename": "1.md"}, {"filename": "2.md"}, {"filename": "3.md"},
+                ]},
+                {"name": "doing", "label": "Doing", "tasks": [{"filename": "4.md"}, {"filename": "5.md"}]},
+                {"name": "review", "tasks": [{"filename": "6.md"}, {"filename": "7.md"}]},
+            ]})),
+        );
+        app.apply_config(
+            serde_json::from_value(serde_json::json!({"columns": [
+                {"name": "todo", "wip_limit": "none"},
+                {"name": "doing", "wip_limit": 1},
+                {"name": "review", "wip_limit": "1"},
+            ]}))
+            .unwrap(),
+        );
+        assert_eq!(app.wip_limit(0), None);
+        assert_eq!(app.over_wip_columns(), vec![(1, 2, 1), (2, 2, 1)]);
+
+        app.hidden_columns.insert("doing".into());
+        assert!(app.jump_to_over_wip());
+        assert_eq!(app.board_col, 1);
+        assert!(app.hidden_columns.is_empty());
+        app.jump_to_over_wip();
+        assert_eq!(app.board_col, 2);
+        app.jump_to_over_wip();
+        assert_eq!(app.board_col, 1);
+    }
+
+    #[test]
+    fn selection_follows_items_through_a_full_refresh() {
+        let page = |names: &[&str]| ResourcePage {
+            items: names
+                .iter()
+                .map(|n| serde_json::from_value(serde_json::json!({"dir_name": n})).unwrap())
+                .collect(),
+            has_more: false,
+        };
+        let mut app = App::new();
+        app.set_board(board(serde_json::json!({"columns": [
+            {"name": "todo", "tasks": [
+                {"filename": "a.md", "column": "todo"}, {"filename": "b.md", "column": "todo"},
+            ]},
+            {"name": "done", "tasks": []},
+        ]})));
+        app.set_board_row(1);
+        app.set_resources(ResourceType::Prompt, page(&["p1", "p2", "p3"]));
+        app.prompt_index = 2;
+
+        let anchor = app.selection_anchor();
+        // The server restarted: a task was added before b.md, and b.md moved
+        app.set_board(board(serde_json::json!({"columns": [
+            {"name": "todo", "tasks": [
+                {"filename": "new.md", "column": "todo"}, {"filename": "a.md", "column": "todo"},
+            ]},
+            {"name": "done", "tasks": [
+                {"filename": "c.md", "column": "done"}, {"filename": "b.md", "column": "done"},
+            ]},
+        ]})));
+        app.set_resources(ResourceType::Prompt, page(&["p0", "p3", "p1"]));
+        app.clamp_indices();
+        app.restore_selection(anchor);
+
+        assert_eq!(app.selected_task().unwrap().filename, "b.md");
+        assert_eq!(app.selected_resource(ResourceType::Prompt).unwrap().dir_name, "p3");
+    }
+
+    #[test]
+    fn resource_sort_is_view_only_and_keeps_the_selection() {
+        let items = serde_json::from_value(serde_json::json!([
+            {"dir_name": "a", "meta": {"title": "beta", "updated": "2024-06-01", "revision": 2}},
+            {"dir_name": "b", "meta": {"title": "Alpha", "created": "2024-05-01T10:00:00Z"}},
+            {"dir_name": "c", "meta": {"title": "gamma", "updated": "2024-06-03T08:00:00", "created": "2024-05-02"}},
+        ]))
+        .unwrap();
+        let mut app = App::new();
+        app.set_resources(ResourceType::Document, ResourcePage { items, has_more: false });
+        app.document_index = 1;
+        let shown = |app: &App| -> Vec<String> {
+            let rows = app.shown_resources(ResourceType::Document);
+            rows.iter().map(|&i| app.documents[i].dir_name.clone()).collect()
+        };
+        let selected = |app: &App| app.selected_resource(ResourceType::Document).unwrap().dir_name.clone();
+
+        assert_eq!(app.cycle_resource_sort(ResourceType::Document), ResourceSort::Updated);
+        // Undated items go last
+        assert_eq!(shown(&app), ["c", "a", "b"]);
+        assert_eq!(selected(&app), "b");
+        app.cycle_resource_sort(ResourceType::Document);
+        assert_eq!(shown(&app), ["c", "b", "a"]);
+        app.cycle_resource_sort(ResourceType::Document);
+        assert_eq!(shown(&app), ["b", "a", "c"]);
+        app.cycle_resource_sort(ResourceType::Document);
+        assert_eq!(shown(&app), ["a", "b", "c"]);
+        assert_eq!(selected(&app), "b");
+        assert_eq!(app.cycle_resource_sort(ResourceType::Document), ResourceSort::Server);
+        // The loaded list itself was never reordered
+        let loaded: Vec<&str> = app.documents.iter().map(|r| r.dir_name.as_str()).collect();
+        assert_eq!(loaded, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn scope_navigation_visits_tasks_sharing_a_scope() {
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [
+                    {"filename": "a.md", "meta": {"scopes": ["auth", "web"]}},
+                    {"filename": "b.md", "meta": {"scopes": ["infra"]}},
+                    {"filename": "c.md", "meta": {"scopes": "Web"}},
+                ]},
+                {"name": "done", "tasks": [{"filename": "d.md", "meta": {"scopes": ["auth"]}}]},
+            ]})),
+        );
+        assert_eq!(app.step_shared_scope(true), 2);
+        assert_eq!(app.selected_task().unwrap().filename, "c.md");
+
+        app.select_task("todo", "a.md");
+        app.step_shared_scope(false);
+        assert_eq!(app.selected_task().unwrap().filename, "d.md");
+        // d.md only has auth, shared with a.md; wraps to the start
+        assert_eq!(app.step_shared_scope(true), 1);
+        assert_eq!(app.selected_task().unwrap().filename, "a.md");
+
+        app.select_task("todo", "b.md");
+        assert_eq!(app.step_shared_scope(true), 0);
+        assert_eq!(app.selected_task().unwrap().filename, "b.md");
+    }
+
+    #[test]
+    fn tasks_are_located_by_frontmatter_id() {
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [{"filename": "a.md", "meta": {"id": "007"}}]},
+                {"name": "done", "tasks": [{"filename": "b.md", "meta": {"id": "ab12cd"}}]},
+            ]})),
+        );
+        let found = |id| app.locate_task_by_id(&id);
+        assert_eq!(found(serde_json::json!(7)), Some(("todo".into(), "a.md".into())));
+        assert_eq!(found(serde_json::json!("ab12cd")), Some(("done".into(), "b.md".into())));
+        assert_eq!(found(serde_json::json!("ab12")), None);
+        assert_eq!(found(serde_json::Value::Null), None);
+    }
+
+    #[test]
+    fn connecting_screen_lasts_until_a_board_or_snapshot() {
+        let mut app = App::new();
+        assert!(app.awaiting_first_data());
+        app.set_board(board(serde_json::json!({"columns": []})));
+        assert!(!app.awaiting_first_data());
+
+        let mut app = App::new();
+        app.offline_since = Some("2024-06-01T09:30:00".to_string());
+        assert!(!app.awaiting_first_data());
+    }
+
+    #[test]
+    fn activity_read_marker_only_moves_forward() {
+        let entries = |mtimes: &[f64]| -> Vec<ActivityEntry> {
+            mtimes
+                .iter()
+                .map(|m| serde_json::from_value(serde_json::json!({"type": "task", "mtime": m})).unwrap())
+                .collect()
+        };
+        let mut app = App::new();
+        app.activity = entries(&[30.0, 10.0]);
+        assert!(!app.is_new_activity(&app.activity[0]));
+        app.mark_activity_viewed();
+        assert_eq!(app.last_viewed_mtime, Some(30.0));
+
+        app.activity = entries(&[45.0, 30.0]);
+        assert!(app.is_new_activity(&app.activity[0]));
+        assert!(!app.is_new_activity(&app.activity[1]));
+
+        // A shorter feed never moves the marker back
+        app.last_viewed_mtime = Some(50.0);
+        app.mark_activity_viewed();
+        assert_eq!(app.last_viewed_mtime, Some(50.0));
+    }
+
+    #[test]
+    fn scope_options_merge_known_and_chosen() {
+        let known = vec!["auth".to_string(), "UI".to_string()];
+        let mut chosen = vec!["legacy".to_string()];
+        assert_eq!(scope_options(&known, &chosen, ""), vec!["auth", "legacy", "UI"]);
+        assert_eq!(scope_options(&known, &chosen, "u"), vec!["u", "auth", "UI"]);
+        assert_eq!(scope_options(&known, &chosen, "ui"), vec!["UI"]);
+
+        toggle_scope(&mut chosen, "ui");
+        toggle_scope(&mut chosen, "LEGACY");
+        assert_eq!(chosen, vec!["ui"]);
+    }
+
+    #[test]
+    fn watched_tasks_report_moves_edits_and_deletes() {
+        let with_task = |column: &str, body: &str| -> Board {
+            let task = serde_json::json!({
+                "filename": "001-a.md", "column": column,
+                "meta": {"title": "A"}, "body": body,
+            });
+            let tasks = |name: &str| if name == column { vec![task.clone()] } else { vec![] };
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": tasks("todo")},
+                {"name": "review", "label": "Review", "tasks": tasks("review")},
+            ]}))
+        };
+        let mut app = App::new();
+        app.set_board(with_task("todo", "one"));
+        assert_eq!(app.toggle_watch(), Some((true, "A".to_string())));
+
+        app.set_board(with_task("todo", "one"));
+        assert!(app.check_watched().is_empty());
+        app.set_board(with_task("todo", "two"));
+        assert_eq!(app.check_watched(), vec!["Watched task \"A\" was updated"]);
+        app.set_board(with_task("review", "two"));
+        assert_eq!(app.check_watched(), vec!["Watched task \"A\" moved to Review"]);
+
+        app.set_board(board(serde_json::json!({"columns": []})));
+        assert_eq!(app.check_watched(), vec!["Watched task \"A\" was deleted"]);
+        assert!(app.watched.is_empty());
+    }
+
+    #[test]
+    fn keep_row_carries_the_row_across_columns() {
+        let tasks = |n: usize| -> Vec<serde_json::Value> {
+            (0..n)
+                .map(|i| serde_json::json!({"filename": format!("{i}.md"), "meta": {}, "body": ""}))
+                .collect()
+        };
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "a", "tasks": tasks(5)},
+                {"name": "b", "tasks": tasks(2)},
+                {"name": "c", "tasks": tasks(5)},
+            ]})),
+        );
+        app.set_board_row(3);
+        app.step_column(1, 200);
+        assert_eq!(app.current_board_row(), 0);
+
+        app.keep_row = true;
+        app.step_column(-1, 200);
+        assert_eq!(app.current_board_row(), 0);
+        app.set_board_row(3);
+        app.step_column(1, 200);
+        assert_eq!((app.board_col, app.current_board_row()), (1, 1));
+        app.step_column(1, 200);
+        assert_eq!(app.current_board_row(), 1);
+    }
+
+    #[test]
+    fn activity_filters_keep_the_selected_entry() {
+        let mut app = App::new();
+        app.activity = ["task", "document", "prompt", "document"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| serde_json::from_value(serde_json::json!({"type": t, "title": i.to_string()})).unwrap())
+            .collect();
+        app.activity_index = 3;
+
+        assert!(!app.toggle_activity_type("prompt"));
+        assert_eq!(app.filtered_activity().len(), 3);
+        assert_eq!(app.selected_activity().unwrap().title, "3");
+        assert_eq!(app.activity_index, 2);
+
+        assert!(!app.toggle_activity_type("document"));
+        assert_eq!(app.activity_index, 0);
+        assert_eq!(app.selected_activity().unwrap().title, "0");
+
+        assert!(app.toggle_activity_type("prompt"));
+        assert_eq!(app.filtered_activity().len(), 2);
+        assert_eq!(app.selected_activity().unwrap().title, "0");
+    }
+
+    #[test]
+    fn assignee_filter_keeps_the_cursor_on_shown_tasks() {
+        let task = |name: &str, assignee: &str| {
+            serde_json::json!({"filename": format!("{name}.md"), "column": "todo",
+                               "meta": {"assignee": assignee}, "body": ""})
+        };
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [task("a", "bob"), task("b", "alice"), task("c", ""), task("d", "Bob")]},
+            ]})),
+        );
+        app.set_board_row(2);
+        assert_eq!(app.known_assignees(), ["alice", "bob"]);
+
+        assert_eq!(app.cycle_assignee_filter().as_deref(), Some("alice"));
+        assert_eq!(app.current_board_row(), 1);
+        assert!(!app.step_row(1));
+
+        assert_eq!(app.cycle_assignee_filter().as_deref(), Some("bob"));
+        assert_eq!(app.current_board_row(), 3);
+        assert_eq!(app.shown_rows(0), vec![0, 3]);
+        assert!(app.step_row(-1));
+        assert_eq!(app.selected_task().unwrap().filename, "a.md");
+
+        // Selecting a filtered-out task clears the filter
+        assert!(app.select_task("todo", "b.md"));
+        assert_eq!(app.active_assignee, None);
+
+        app.cycle_assignee_filter();
+        app.cycle_assignee_filter();
+        assert_eq!(app.cycle_assignee_filter(), None);
+    }
+
+    #[test]
+    fn moving_a_task_skips_filtered_out_neighbours() {
+        let task = |name: &str, assignee: &str| {
+            serde_json::json!({"filename": format!("{name}.md"), "column": "todo",
+                               "meta": {"assignee": assignee}, "body": ""})
+        };
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [task("a", "bob"), task("b", "alice"), task("c", "bob")]},
+            ]})),
+        );
+        let names = |app: &App| -> Vec<String> {
+            app.board.as_ref().unwrap().columns[0].tasks.iter().map(|t| t.filename.clone()).collect()
+        };
+        app.active_assignee = Some("bob".into());
+
+        assert_eq!(app.move_selected_task(-1), None);
+        assert_eq!(app.move_selected_task(1), Some(("todo".into(), "a.md".into(), 2)));
+        assert_eq!(names(&app), ["b.md", "c.md", "a.md"]);
+        assert_eq!(app.selected_task().unwrap().filename, "a.md");
+        assert_eq!(app.move_selected_task(1), None);
+
+        assert_eq!(app.move_selected_task(-1), Some(("todo".into(), "a.md".into(), 1)));
+        assert_eq!(names(&app), ["b.md", "a.md", "c.md"]);
+    }
+
+    #[test]
+    fn selecting_a_column_lands_on_a_shown_task() {
+        let task = |name: &str, assignee: &str| {
+            serde_json::json!({"filename": format!("{name}.md"), "column": "todo",
+                               "meta": {"assignee": assignee}, "body": ""})
+        };
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [task("a", ""), task("b", ""), task("c", "")]},
+                {"name": "doing", "tasks": [task("d", "")]},
+                {"name": "done", "tasks": [task("e", "bob"), task("f", "alice"), task("g", "alice")]},
+            ]})),
+        );
+        app.board_row = vec![2, 5, 2];
+
+        app.select_column(1);
+        assert_eq!(app.selected_task().unwrap().filename, "d.md");
+
+        app.active_assignee = Some("bob".into());
+        app.select_column(2);
+        assert_eq!(app.selected_task().unwrap().filename, "e.md");
+    }
+
+    #[test]
+    fn status_filter_and_pill_colors() {
+        let task = |name: &str, status: &str| {
+            serde_json::json!({"filename": format!("{name}.md"), "column": "todo",
+                               "meta": {"status": status}, "body": ""})
+        };
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [task("a", "review"), task("b", " "), task("c", "Blocked")]},
+            ]})),
+        );
+
+        assert_eq!(app.cycle_status_filter().as_deref(), Some("Blocked"));
+        assert_eq!(app.shown_rows(0), vec![2]);
+        assert_eq!(app.cycle_status_filter().as_deref(), Some("review"));
+        assert_eq!(app.shown_rows(0), vec![0]);
+        assert_eq!(app.cycle_status_filter(), None);
+
+        assert_eq!(app.status_color("BLOCKED"), theme::RED);
+        assert_eq!(app.status_color("wip"), theme::TEXT_SECONDARY);
+        app.apply_config(
+            serde_json::from_str(r#"{"columns": [], "settings": {"status_colors": ["blocked=magenta"]}}"#)
+                .unwrap(),
+        );
+        assert_eq!(app.status_color("Blocked"), Color::Rgb(255, 0, 255));
+        assert_eq!(app.status_color("review"), theme::YELLOW);
+    }
+
+    #[test]
+    fn scroll_memory_restores_unchanged_items() {
+        let open = |app: &mut App, body: &str| {
+            let task = serde_json::from_value(serde_json::json!({
+                "filename": "001-a.md", "column": "todo", "meta": {}, "body": body,
+            }))
+            .unwrap();
+            app.overlay = Some(Overlay::TaskDetail {
+                task,
+                comments: vec![],
+                scroll: 0,
+                search: None,
+                visual: None,
+                comments_collapsed: false,
+                gone: false,
+                sections: BTreeSet::new(),
+                links: None,
+            });
+            app.restore_scroll();
+        };
+        let scroll = |app: &App| match &app.overlay {
+            Some(Overlay::TaskDetail { scroll, .. }) => *scroll,
+            _ => unreachable!(),
+        };
+        let mut app = App::new();
+        open(&mut app, "one");
+        if let Some(Overlay::TaskDetail { scroll, .. }) = &mut app.overlay {
+            *scroll = 12;
+        }
+        app.remember_scroll();
+
+        open(&mut app, "one");
+        assert_eq!(scroll(&app), 12);
+        open(&mut app, "edited");
+        assert_eq!(scroll(&app), 0);
+        assert!(app.scroll_memory.is_empty());
+    }
+
+    #[test]
+    fn empty_columns_hide_until_they_get_tasks() {
+        let with_done = |done: serde_json::Value| -> Board {
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [{"filename": "a.md", "meta": {}, "body": ""}]},
+                {"name": "doing", "tasks": []},
+                {"name": "done", "tasks": done},
+            ]}))
+        };
+        let mut app = App::new();
+        app.set_board(with_done(serde_json::json!([])));
+        assert!(app.toggle_empty_columns());
+        assert_eq!(app.visible_columns(), vec![0]);
+        assert_eq!(app.column_count(), 1);
+
+        // A task arriving on refresh brings its column back
+        app.set_board(with_done(serde_json::json!([{"filename": "b.md", "meta": {}, "body": ""}])));
+        assert_eq!(app.visible_columns(), vec![0, 2]);
+        app.board_col = 2;
+        app.set_board(with_done(serde_json::json!([])));
+        assert_eq!(app.board_col, 0);
+
+        // Selecting an empty column explicitly shows empty columns again
+        app.select_column(1);
+        assert!(!app.hide_empty_columns);
+        assert_eq!(app.board_col, 1);
+
+        // A board without any tasks keeps its columns
+        app.set_board(board(serde_json::json!({"columns": [{"name": "todo", "tasks": []}]})));
+        app.toggle_empty_columns();
+        assert_eq!(app.visible_columns(), vec![0]);
+    }
+
+    #[test]
+    fn start_column_matches_names_and_labels() {
+        let initial = board(serde_json::json!({"columns": [
+            {"name": "todo", "label": "To Do", "tasks": []},
+            {"name": "review", "label": "Review", "tasks": []},
+        ]}));
+        let mut app = App::new();
+        app.hidden_columns.insert("review".into());
+        app.start_column = Some("Review".into());
+        app.set_board(initial);
+        app.apply_start_column();
+        assert_eq!(app.board_col, 1);
+        assert!(app.hidden_columns.is_empty());
+        assert_eq!(app.start_column, None);
+
+        app.start_column = Some("nope".into());
+        app.apply_start_column();
+        assert_eq!(app.board_col, 0);
+        assert!(app.active_status().is_some_and(|m| m.contains("\"nope\"")));
+    }
+
+    #[test]
+    fn open_task_overlay_follows_board_updates() {
+        let with_tasks = |tasks: serde_json::Value| -> Board {
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": tasks},
+                {"name": "done", "tasks": []},
+            ]}))
+        };
+        let task = serde_json::json!({"filename": "001-a.md", "column": "todo", "meta": {}, "body": "old"});
+        let mut app = App::new();
+        app.set_board(with_tasks(serde_json::json!([task.clone()])));
+        app.overlay = Some(Overlay::TaskDetail {
+            task: serde_json::from_value(task).unwrap(),
+            comments: vec![],
+            scroll: 7,
+            search: None,
+            visual: Some((0, 0)),
+            comments_collapsed: false,
+            gone: false,
+            sections: BTreeSet::new(),
+            links: None,
+        });
+        let state = |app: &App| match &app.overlay {
+            Some(Overlay::TaskDetail {
+                task,
+                scroll,
+                visual,
+                gone,
+                ..
+            }) => (task.body.clone(), *scroll, visual.is_some(), *gone),
+            _ => unreachable!(),
+        };
+
+        app.refresh_open_overlay();
+        assert_eq!(state(&app), ("old".into(), 7, true, false));
+
+        app.set_board(with_tasks(serde_json::json!([
+            {"filename": "001-a.md", "column": "todo", "meta": {}, "body": "new"},
+        ])));
+        app.refresh_open_overlay();
+        assert_eq!(state(&app), ("new".into(), 7, false, false));
+
+        app.set_board(with_tasks(serde_json::json!([])));
+        app.refresh_open_overlay();
+        assert_eq!(state(&app), ("new".into(), 7, false, true));
+    }
+
+    #[test]
+    fn board_search_is_recomputed_on_refresh() {
+        let with_title = |title: &str| -> Board {
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [
+                    {"filename": "001-a.md", "column": "todo", "meta": {"title": "Fix login"}, "body": ""},
+                    {"filename": "002-b.md", "column": "todo", "meta": {"title": title, "scopes": ["auth"]}, "body": ""},
+                ]},
+            ]}))
+        };
+        let mut app = App::new();
+        app.set_board(with_title("Docs"));
+        assert_eq!(app.set_board_search(Some("LOG".into())), 1);
+        app.set_board(with_title("Logout button"));
+        assert_eq!(app.board_search.as_deref(), Some("LOG"));
+        assert_eq!(app.board_matches.len(), 2);
+
+        assert_eq!(app.set_board_search(Some("auth".into())), 1);
+        app.select_first_board_match();
+        assert_eq!(app.selected_task().unwrap().filename, "002-b.md");
+        assert_eq!(app.set_board_search(Some(String::new())), 0);
+        assert_eq!(app.board_search, None);
+    }
+
+    #[test]
+    fn progress_rows_list_checklists_nearest_to_done_first() {
+        let task = |name: &str, body: &str| serde_json::json!({"filename": name, "body": body});
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "todo", "tasks": [
+                    task("a.md", "- [x] one\n- [ ] two\n- [ ] three"),
+                    task("b.md", "no checklist"),
+                    task("c.md", "- [x] one\n- [ ] two"),
+                ]},
+                {"name": "done", "tasks": [task("d.md", "- [x] one\n- [x] two"), task("e.md", "- [ ] x\n- [X] y")]},
+            ]})),
+        );
+        let rows = app.progress_rows();
+        let order: Vec<&str> = rows.iter().map(|r| r.filename.as_str()).collect();
+        assert_eq!(order, ["d.md", "c.md", "e.md", "a.md"]);
+        assert_eq!((rows[3].column.as_str(), rows[3].checked, rows[3].total), ("todo", 1, 3));
+    }
+
+    #[test]
+    fn jumps_to_the_first_incomplete_task() {
+        let task = |name: &str, completed: &str, body: &str| {
+            serde_json::json!({"filename": name, "meta": {"completed": completed}, "body": body})
+        };
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "done", "tasks": [task("a.md", "2024-06-01", "- [x] ok")]},
+                {"name": "review", "tasks": [
+                    task("b.md", "2024-06-01", ""),
+                    task("c.md", "2024-06-01", "- [x] ok\n- [ ] left"),
+                ]},
+            ]})),
+        );
+        assert!(app.select_first_incomplete());
+        assert_eq!((app.board_col, app.current_board_row()), (1, 1));
+
+        app.hidden_columns.insert("review".into());
+        assert!(!app.select_first_incomplete());
+    }
+
+    #[test]
+    fn column_weights_default_to_one_and_reject_bad_values() {
+        let mut app = App::new();
+        app.set_board(
+            board(serde_json::json!({"columns": [
+                {"name": "backlog", "tasks": []},
+                {"name": "todo", "label": "To Do", "tasks": []},
+                {"name": "done", "tasks": []},
+            ]})),
+        );
+        let config = |weights: serde_json::Value| -> Config {
+            serde_json::from_value(serde_json::json!({"columns": [
+                {"name": "backlog", "weight": weights[0]},
+                {"name": "todo", "weight": weights[1]},
+                {"name": "done"},
+            ]}))
+            .unwrap()
+        };
+
+        assert_eq!(app.column_weights(&[0, 1, 2]), vec![100, 100, 100]);
+        app.apply_config(config(serde_json::json!([2, "1.5"])));
+        assert_eq!(app.column_weights(&[0, 1, 2]), vec![200, 150, 100]);
+        assert_eq!(app.column_weights(&[1, 2]), vec![150, 100]);
+
+        app.column_weight_overrides.insert("to do".into(), "3".into());
+        assert_eq!(app.column_weights(&[0, 1, 2]), vec![200, 300, 100]);
+
+        for bad in [serde_json::json!(0), serde_json::json!(-1), serde_json::json!("wide")] {
+            app.apply_config(config(serde_json::json!([bad, 1])));
+            assert_eq!(app.column_weights(&[0, 1, 2]), vec![1, 1, 1]);
         }
     }
 }
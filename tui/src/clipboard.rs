@@ -0,0 +1,12 @@
+use std::io::{self, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// Copy text to the system clipboard via the OSC 52 terminal escape. Works
+/// over SSH and in most modern terminals (tmux needs `set-clipboard on`).
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut out = io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    out.flush()
+}
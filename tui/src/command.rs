@@ -0,0 +1,140 @@
+//! The `:` command line: exact, typed commands for actions that otherwise
+//! take several keys.
+//!
+//! ```text
+//! q | quit                       quit
+//! view board|prompts|documents|activity
+//! filter assignee NAME           board shows only NAME's tasks
+//! filter scope NAME              board shows only tasks with the scope
+//! filter status NAME             board shows only tasks with the status
+//! filter                         clear board filters
+//! goto COLUMN[/FILE]             select a column, or a task in it
+//! refresh                        re-fetch the current view
+//! home                           start in this view and focus next time
+//! help                           key bindings
+//! ```
+
+use anyhow::{anyhow, bail, Result};
+use clap::ValueEnum;
+
+use crate::app::View;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Quit,
+    View(View),
+    Filter(Filter),
+    Goto {
+        column: String,
+        filename: Option<String>,
+    },
+    Refresh,
+    Home,
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Assignee(String),
+    Scope(String),
+    Status(String),
+    Clear,
+}
+
+/// Parse one command line, without the leading `:`.
+pub fn parse(line: &str) -> Result<Command> {
+    let line = line.trim().trim_start_matches(':');
+    let (name, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let args = args.trim();
+    let no_args = |command: Command| {
+        if args.is_empty() {
+            Ok(command)
+        } else {
+            Err(anyhow!("{name} takes no arguments"))
+        }
+    };
+    match name {
+        "q" | "quit" => no_args(Command::Quit),
+        "refresh" => no_args(Command::Refresh),
+        "home" => no_args(Command::Home),
+        "help" => no_args(Command::Help),
+        "view" => {
+            let view = View::from_str(args, true).map_err(|_| {
+                anyhow!("Unknown view \"{args}\" (board, prompts, documents, activity)")
+            })?;
+            Ok(Command::View(view))
+        }
+        "filter" => {
+            let (kind, value) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+            let value = value.trim().trim_start_matches(['@', '[']).trim_end_matches(']');
+            match (kind, value) {
+                ("", _) => Ok(Command::Filter(Filter::Clear)),
+                ("assignee" | "scope" | "status", "") => bail!("filter {kind} needs a name"),
+                ("assignee", name) => Ok(Command::Filter(Filter::Assignee(name.to_string()))),
+                ("scope", name) => Ok(Command::Filter(Filter::Scope(name.to_string()))),
+                ("status", name) => Ok(Command::Filter(Filter::Status(name.to_string()))),
+                _ => bail!("Unknown filter \"{kind}\" (assignee, scope, status)"),
+            }
+        }
+        "goto" => {
+            if args.is_empty() {
+                bail!("goto needs a column, optionally with /FILE");
+            }
+            let (column, filename) = match args.split_once('/') {
+                Some((column, filename)) => (column, Some(filename.trim().to_string())),
+                None => (args, None),
+            };
+            Ok(Command::Goto {
+                column: column.trim().to_string(),
+                filename: filename.filter(|f| !f.is_empty()),
+            })
+        }
+        "" => bail!("No command given"),
+        _ => bail!("Unknown command \"{name}\""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_command_grammar() {
+        assert_eq!(parse(":q").unwrap(), Command::Quit);
+        assert_eq!(parse("home").unwrap(), Command::Home);
+        assert_eq!(parse("view Documents").unwrap(), Command::View(View::Documents));
+        assert_eq!(
+            parse("filter scope [auth]").unwrap(),
+            Command::Filter(Filter::Scope("auth".to_string()))
+        );
+        assert_eq!(
+            parse("filter assignee @alice").unwrap(),
+            Command::Filter(Filter::Assignee("alice".to_string()))
+        );
+        assert_eq!(
+            parse("filter status blocked").unwrap(),
+            Command::Filter(Filter::Status("blocked".to_string()))
+        );
+        assert_eq!(parse("filter").unwrap(), Command::Filter(Filter::Clear));
+        assert_eq!(
+            parse("goto todo/001-fix.md").unwrap(),
+            Command::Goto {
+                column: "todo".to_string(),
+                filename: Some("001-fix.md".to_string())
+            }
+        );
+        assert_eq!(
+            parse("goto In Progress").unwrap(),
+            Command::Goto {
+                column: "In Progress".to_string(),
+                filename: None
+            }
+        );
+
+        let error = |line: &str| parse(line).unwrap_err().to_string();
+        assert_eq!(error("frobnicate"), "Unknown command \"frobnicate\"");
+        assert_eq!(error("q now"), "q takes no arguments");
+        assert_eq!(error("filter scope"), "filter scope needs a name");
+        assert!(error("view kanban").starts_with("Unknown view"));
+    }
+}
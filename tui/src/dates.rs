@@ -0,0 +1,158 @@
+//! Display formatting for frontmatter dates (`--date-format`).
+
+use std::fmt::{Display, Write};
+
+use anyhow::{bail, Result};
+use chrono::format::{Fixed, Item, Numeric, StrftimeItems};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
+
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Timestamps without an offset the server may send.
+const NAIVE_PATTERNS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Reject strftime strings chrono cannot render, and offset or timestamp
+/// specs (`%z`, `%Z`, `%s`), which dates without an offset can't fill in.
+pub fn validate_format(format: &str) -> Result<()> {
+    let unsupported = StrftimeItems::new(format).any(|item| {
+        matches!(
+            item,
+            Item::Error
+                | Item::Numeric(Numeric::Timestamp, _)
+                | Item::Fixed(
+                    Fixed::TimezoneName
+                        | Fixed::TimezoneOffset
+                        | Fixed::TimezoneOffsetZ
+                        | Fixed::TimezoneOffsetColon
+                        | Fixed::TimezoneOffsetColonZ
+                        | Fixed::TimezoneOffsetDoubleColon
+                        | Fixed::TimezoneOffsetTripleColon
+                )
+        )
+    });
+    if unsupported {
+        bail!("Invalid --date-format {format:?}");
+    }
+    Ok(())
+}
+
+/// Format an ISO date or timestamp from the server. Timestamps with an offset
+/// are shown in local time; plain dates drop the time part of `format`.
+/// Anything unparseable is returned unchanged.
+pub fn format_date(raw: &str, format: &str) -> String {
+    let raw = raw.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return render(dt.with_timezone(&Local).format(format), raw);
+    }
+    for pattern in NAIVE_PATTERNS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(raw, pattern) {
+            return render(dt.format(format), raw);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        return render(date.format(&date_part(format)), raw);
+    }
+    raw.to_string()
+}
+
+/// A formatted date, or `raw` when the format needs a field the value lacks
+/// (chrono's `to_string` would panic).
+fn render(formatted: impl Display, raw: &str) -> String {
+    let mut out = String::new();
+    match write!(out, "{formatted}") {
+        Ok(()) => out,
+        Err(_) => raw.to_string(),
+    }
+}
+
+/// Local time of an ISO date or timestamp; plain dates are taken as midnight.
+pub fn parse_local(raw: &str) -> Option<NaiveDateTime> {
+    let raw = raw.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.with_timezone(&Local).naive_local());
+    }
+    NAIVE_PATTERNS
+        .iter()
+        .find_map(|pattern| NaiveDateTime::parse_from_str(raw, pattern).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+}
+
+/// `format` without its time specifiers and the separators after them:
+/// `%Y-%m-%d %H:%M` -> `%Y-%m-%d`, `%H:%M %d/%m` -> `%d/%m`.
+fn date_part(format: &str) -> String {
+    const TIME_SPECS: &str = "HIMSpPTRXklrf";
+    const SEPARATORS: [char; 7] = [' ', ',', 'T', '@', '-', ':', '/'];
+    let mut date = String::new();
+    let mut rest = format;
+    while let Some(start) = rest.find('%') {
+        date.push_str(&rest[..start]);
+        let spec = &rest[start..];
+        // `%-H`, `%_H` and `%0H` pad differently but are still time specs
+        let body = spec[1..].trim_start_matches(['-', '_', '0', '.', '3', '6', '9']);
+        match body.chars().next() {
+            Some(c) if TIME_SPECS.contains(c) => {
+                rest = body[c.len_utf8()..].trim_start_matches(SEPARATORS);
+            }
+            Some(c) => {
+                let len = spec.len() - body.len() + c.len_utf8();
+                date.push_str(&spec[..len]);
+                rest = &spec[len..];
+            }
+            None => {
+                date.push_str(spec);
+                rest = "";
+            }
+        }
+    }
+    date.push_str(rest);
+    let date = date.trim_end_matches(SEPARATORS);
+    if date.is_empty() {
+        "%Y-%m-%d".to_string()
+    } else {
+        date.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_timestamps_and_plain_dates() {
+        assert_eq!(
+            format_date("2024-06-01T09:30:12.123456", DEFAULT_DATE_FORMAT),
+            "2024-06-01 09:30"
+        );
+        assert_eq!(format_date("2024-06-01", DEFAULT_DATE_FORMAT), "2024-06-01");
+        assert_eq!(format_date("2024-06-01", "%d %b %Y, %H:%M"), "01 Jun 2024");
+        assert_eq!(format_date("2024-06-01", "%H:%M"), "2024-06-01");
+        assert_eq!(format_date("2024-06-01", "%H:%M %d/%m"), "01/06");
+        assert_eq!(format_date("2024-06-01", "%-I%p, %e %b"), " 1 Jun");
+        assert_eq!(format_date("2024-06-01", "100%% %d"), "100% 01");
+        // %c needs a time, which a plain date doesn't have
+        assert_eq!(format_date("2024-06-01", "%c"), "2024-06-01");
+        assert_eq!(format_date("next week", DEFAULT_DATE_FORMAT), "next week");
+        assert_eq!(format_date("", DEFAULT_DATE_FORMAT), "");
+    }
+
+    #[test]
+    fn rejects_invalid_formats() {
+        assert!(validate_format(DEFAULT_DATE_FORMAT).is_ok());
+        assert!(validate_format("%d %b").is_ok());
+        assert!(validate_format("%Q").is_err());
+        for format in ["%s", "%z", "%Z", "%:z", "%d %H:%M %::z"] {
+            assert!(validate_format(format).is_err(), "{format}");
+            // Formatting anyway falls back instead of panicking
+            assert_eq!(format_date("2024-06-01", format), "2024-06-01");
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! `--log-file`: JSON lines describing poll messages, connection changes
+//! and key presses, for debugging reports like "the board didn't update".
+//!
+//! Lines are handed to a background task over a channel, so a slow disk
+//! never stalls the UI. Quitting waits briefly for that task to catch up.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+/// Handle for appending events. The disabled log drops everything.
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    tx: Option<mpsc::UnboundedSender<String>>,
+    /// Lines sent but not yet written
+    pending: Arc<AtomicUsize>,
+}
+
+impl EventLog {
+    /// Open `path` for appending and start the writer task.
+    pub async fn open(path: &Path) -> Result<Self> {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .with_context(|| format!("Cannot open log file {path:?}"))?;
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        let pending = Arc::new(AtomicUsize::new(0));
+        let written = pending.clone();
+        tokio::spawn(async move {
+            while let Some(line) = rx.recv().await {
+                let ok = file.write_all(line.as_bytes()).await.is_ok();
+                let _ = file.flush().await;
+                written.fetch_sub(1, Ordering::SeqCst);
+                if !ok {
+                    // Nothing will be written anymore
+                    written.store(0, Ordering::SeqCst);
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            tx: Some(tx),
+            pending,
+        })
+    }
+
+    /// Number of lines still waiting to be written.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Wait until every line is written or `timeout` passes. Returns whether
+    /// the log caught up.
+    pub async fn flush(&self, timeout: Duration) -> bool {
+        let start = Instant::now();
+        while self.pending() > 0 {
+            if start.elapsed() >= timeout {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        true
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.tx.is_some()
+    }
+
+    /// Append `{"ts": ..., "event": kind, ...fields}`.
+    pub fn log(&self, kind: &str, fields: Value) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        if tx.send(format_line(&chrono::Local::now().to_rfc3339(), kind, fields)).is_err() {
+            self.pending.store(0, Ordering::SeqCst);
+        }
+    }
+}
+
+fn format_line(ts: &str, kind: &str, fields: Value) -> String {
+    let mut entry = json!({ "ts": ts, "event": kind });
+    if let (Some(entry), Value::Object(fields)) = (entry.as_object_mut(), fields) {
+        entry.extend(fields);
+    }
+    format!("{entry}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_merge_fields_with_timestamp_and_kind() {
+        let line = format_line("2024-06-01T09:30:00+02:00", "key", json!({"code": "Char('j')"}));
+        assert!(line.ends_with('\n') && !line.trim_end().contains('\n'));
+        let entry: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            entry,
+            json!({"ts": "2024-06-01T09:30:00+02:00", "event": "key", "code": "Char('j')"})
+        );
+
+        let entry: Value = serde_json::from_str(&format_line("t", "poll", Value::Null)).unwrap();
+        assert_eq!(entry, json!({"ts": "t", "event": "poll"}));
+    }
+
+    #[tokio::test]
+    async fn flush_waits_for_queued_lines() {
+        let path = std::env::temp_dir().join(format!("mdboard-eventlog-{}.jsonl", std::process::id()));
+        let log = EventLog::open(&path).await.unwrap();
+        for i in 0..100 {
+            log.log("key", json!({ "i": i }));
+        }
+        assert!(log.flush(Duration::from_secs(5)).await);
+        assert_eq!(log.pending(), 0);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 100);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(EventLog::default().flush(Duration::ZERO).await);
+    }
+}
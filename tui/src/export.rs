@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde_json::json;
+
+use crate::api::ApiClient;
+use crate::model::{Board, Comment, Task};
+use crate::ui::board::count_checkboxes;
+
+/// Output format for `--export-board`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BoardFormat {
+    Json,
+    Md,
+}
+
+/// Fetch the board and render it in the requested format.
+pub async fn export_board(api: &ApiClient, format: BoardFormat) -> Result<String> {
+    let board = api.board().await?;
+    Ok(match format {
+        BoardFormat::Json => serde_json::to_string_pretty(&board_to_json(&board))? + "\n",
+        BoardFormat::Md => board_to_markdown(&board),
+    })
+}
+
+/// Structured summary of the board: columns with their tasks' metadata.
+pub fn board_to_json(board: &Board) -> serde_json::Value {
+    let columns: Vec<serde_json::Value> = board
+        .columns
+        .iter()
+        .map(|col| {
+            let tasks: Vec<serde_json::Value> = col
+                .tasks
+                .iter()
+                .map(|task| {
+                    let (checked, total) = count_checkboxes(&task.body);
+                    json!({
+                        "filename": task.filename,
+                        "id": task.meta.id,
+                        "title": task.meta.title,
+                        "assignee": task.meta.assignee,
+                        "status": task.meta.status(),
+                        "scopes": task.meta.scopes.as_vec(),
+                        "created": task.meta.created,
+                        "due": task.meta.due,
+                        "completed": task.meta.completed,
+                        "checkboxes": { "checked": checked, "total": total },
+                    })
+                })
+                .collect();
+            json!({
+                "name": col.name,
+                "label": col.label,
+                "tasks": tasks,
+            })
+        })
+        .collect();
+    json!({ "columns": columns })
+}
+
+/// One section per column with task titles as a checklist.
+pub fn board_to_markdown(board: &Board) -> String {
+    let mut out = String::from("# Board\n");
+    for col in &board.columns {
+        let label = if col.label.is_empty() { &col.name } else { &col.label };
+        out.push_str(&format!("\n## {label} ({})\n\n", col.tasks.len()));
+        for task in &col.tasks {
+            let title = if task.meta.title.is_empty() {
+                &task.filename
+            } else {
+                &task.meta.title
+            };
+            let mark = if task.meta.completed.is_empty() { ' ' } else { 'x' };
+            out.push_str(&format!("- [{mark}] {title}"));
+            if !task.meta.assignee.is_empty() {
+                out.push_str(&format!(" (@{})", task.meta.assignee));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Fetch a task by an export spec of the form `task:<column>/<filename>`
+/// and render it as markdown.
+pub async fn export_spec(api: &ApiClient, spec: &str) -> Result<String> {
+    let path = spec
+        .strip_prefix("task:")
+        .with_context(|| format!("Unsupported export spec {spec:?} (expected task:<column>/<filename>)"))?;
+    let (column, filename) = path
+        .split_once('/')
+        .with_context(|| format!("Invalid task path {path:?} (expected <column>/<filename>)"))?;
+
+    let task = api.get_task(column, filename).await?;
+    let comments = match task.meta.id.as_ref().map(json_scalar) {
+        Some(id) if !id.is_empty() => api.get_comments(&id).await.unwrap_or_default(),
+        _ => vec![],
+    };
+    Ok(task_to_markdown(&task, &comments))
+}
+
+/// Serialize a task back to frontmatter + body, followed by its comments.
+pub fn task_to_markdown(task: &Task, comments: &[Comment]) -> String {
+    let meta = &task.meta;
+    let mut out = String::from("---\n");
+
+    if let Some(id) = &meta.id {
+        out.push_str(&format!("id: {}\n", json_scalar(id)));
+    }
+    out.push_str(&format!("title: {}\n", meta.title));
+    out.push_str(&format!("assignee: {}\n", meta.assignee));
+    out.push_str(&format!("scopes: [{}]\n", meta.scopes.as_vec().join(", ")));
+    if let Some(status) = meta.status() {
+        out.push_str(&format!("status: {status}\n"));
+    }
+    if let Some(priority) = meta.priority.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        out.push_str(&format!("priority: {priority}\n"));
+    }
+    for (key, value) in [
+        ("created", &meta.created),
+        ("due", &meta.due),
+        ("branch", &meta.branch),
+        ("completed", &meta.completed),
+    ] {
+        if !value.is_empty() {
+            out.push_str(&format!("{key}: {value}\n"));
+        }
+    }
+    out.push_str("---\n\n");
+    out.push_str(task.body.trim_end());
+    out.push('\n');
+
+    if !comments.is_empty() {
+        out.push_str("\n## Comments\n");
+        for comment in comments {
+            out.push_str(&format!(
+                "\n### @{} — {}\n\n{}\n",
+                comment.meta.author,
+                comment.meta.created,
+                comment.body.trim_end()
+            ));
+        }
+    }
+
+    out
+}
+
+/// The task body as GitHub-flavored markdown for pasting into a PR: checkbox
+/// items are written as `- [ ]` / `- [x]` whatever bullet they used, and
+/// lines copied from the rendered view (`✓` / `○`) are turned back into
+/// checkboxes. With `with_title` the task title comes first as a heading.
+pub fn task_to_checklist(task: &Task, with_title: bool) -> String {
+    let mut out = String::new();
+    if with_title {
+        out.push_str(&format!("## {}\n\n", task.display_title()));
+    }
+    for line in task.body.trim_end().lines() {
+        out.push_str(&gfm_checkbox_line(line));
+        out.push('\n');
+    }
+    out
+}
+
+fn gfm_checkbox_line(line: &str) -> String {
+    let rest = line.trim_start();
+    let indent = &line[..line.len() - rest.len()];
+    let item = rest
+        .strip_prefix(['-', '*', '+'])
+        .and_then(|r| r.strip_prefix(' '))
+        .and_then(|r| {
+            r.strip_prefix("[ ]")
+                .map(|text| (' ', text))
+                .or_else(|| r.strip_prefix("[x]").or_else(|| r.strip_prefix("[X]")).map(|text| ('x', text)))
+        })
+        .or_else(|| rest.strip_prefix("✓ ").map(|text| ('x', text)))
+        .or_else(|| rest.strip_prefix("○ ").map(|text| (' ', text)));
+    match item {
+        Some((mark, text)) => format!("{indent}- [{mark}] {}", text.trim_start()).trim_end().to_string(),
+        None => line.to_string(),
+    }
+}
+
+/// Render a JSON scalar without the quotes `Value::to_string` adds to strings.
+pub fn json_scalar(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checklist_normalizes_checkboxes_and_keeps_other_lines() {
+        let task: Task = serde_json::from_value(json!({
+            "filename": "001.md",
+            "column": "todo",
+            "meta": {"title": "Fix login"},
+            "body": "Steps:\n* [X] reproduce\n  + [ ] write test\n✓ patched\n○ released\n- plain item\n\n",
+        }))
+        .unwrap();
+        assert_eq!(
+            task_to_checklist(&task, false),
+            "Steps:\n- [x] reproduce\n  - [ ] write test\n- [x] patched\n- [ ] released\n- plain item\n"
+        );
+        assert!(task_to_checklist(&task, true).starts_with("## Fix login\n\nSteps:\n"));
+    }
+
+    #[test]
+    fn markdown_keeps_priority_and_status() {
+        let task: Task = serde_json::from_value(json!({
+            "filename": "001.md",
+            "column": "todo",
+            "meta": {"title": "Fix login", "priority": 2, "status": "blocked"},
+            "body": "Body\n",
+        }))
+        .unwrap();
+        let markdown = task_to_markdown(&task, &[]);
+        assert!(markdown.contains("\nstatus: blocked\npriority: 2\n"), "{markdown}");
+    }
+}
@@ -0,0 +1,63 @@
+//! The view and focus the TUI starts in, kept across sessions.
+//!
+//! `:home` stores them in `home.json` in the state directory (see state.rs),
+//! next to the recent history. `--home-view` and `--focus` override the stored values.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Focus, View};
+use crate::state;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Stored {
+    #[serde(default)]
+    view: String,
+    #[serde(default)]
+    focus: String,
+}
+
+pub fn state_path() -> Option<PathBuf> {
+    state::path("home.json")
+}
+
+/// The stored home, or None without one. Names this version doesn't know
+/// fall back to the board and content focus.
+pub fn load(path: &Path) -> Option<(View, Focus)> {
+    let stored: Stored = state::read(path).ok()?;
+    Some((
+        View::from_str(&stored.view, true).unwrap_or(View::Board),
+        Focus::from_str(&stored.focus, true).unwrap_or(Focus::Content),
+    ))
+}
+
+pub fn save(path: &Path, view: View, focus: Focus) -> Result<()> {
+    let name = |value: Option<clap::builder::PossibleValue>| {
+        value.map(|v| v.get_name().to_string()).unwrap_or_default()
+    };
+    let stored = Stored {
+        view: name(view.to_possible_value()),
+        focus: name(focus.to_possible_value()),
+    };
+    state::write(path, &stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_falls_back_to_the_board() {
+        let path = std::env::temp_dir().join(format!("mdboard-home-{}.json", std::process::id()));
+        assert_eq!(load(&path), None);
+        save(&path, View::Activity, Focus::TabBar).unwrap();
+        assert_eq!(load(&path), Some((View::Activity, Focus::TabBar)));
+
+        std::fs::write(&path, r#"{"view": "kanban", "focus": "sidebar"}"#).unwrap();
+        assert_eq!(load(&path), Some((View::Board, Focus::Content)));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
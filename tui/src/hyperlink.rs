@@ -0,0 +1,183 @@
+//! OSC 8 hyperlinks for markdown links in detail overlays.
+//!
+//! Ratatui cells cannot carry a link target, so after each draw the cells
+//! rendered in the link style are written again wrapped in OSC 8 escapes.
+//! Terminals without OSC 8 support ignore the escapes and keep the plain
+//! underlined text.
+
+use std::io::{self, Write};
+
+use crossterm::cursor::{RestorePosition, SavePosition};
+use ratatui::backend::Backend;
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::style::Modifier;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{App, Overlay};
+use crate::theme;
+use crate::ui::markdown::{find_links, LinkRef};
+
+/// Links in the markdown shown by the open overlay.
+pub fn overlay_links(app: &App) -> Vec<LinkRef> {
+    match &app.overlay {
+        Some(Overlay::TaskDetail { task, comments, .. }) => std::iter::once(&task.body)
+            .chain(comments.iter().map(|c| &c.body))
+            .flat_map(|body| find_links(body))
+            .collect(),
+        _ => app.overlay_body().map(find_links).unwrap_or_default(),
+    }
+}
+
+/// `overlay_links` with each target once, for the `O` link picker.
+pub fn picker_links(app: &App) -> Vec<LinkRef> {
+    let mut links = overlay_links(app);
+    let mut seen = Vec::new();
+    links.retain(|link| {
+        let new = !seen.contains(&link.url);
+        seen.push(link.url.clone());
+        new
+    });
+    links
+}
+
+/// Write the link cells of the frame just drawn again as OSC 8 hyperlinks.
+/// The cursor is restored afterwards so input prompts keep their caret.
+pub fn emit<B: Backend + Write>(backend: &mut B, buffer: &Buffer, links: &[LinkRef]) -> io::Result<()> {
+    let runs = link_runs(buffer, links);
+    if runs.is_empty() {
+        return Ok(());
+    }
+
+    let mut cells = Vec::new();
+    for run in runs {
+        let last = run.cells.len() - 1;
+        for (i, (x, mut cell)) in run.cells.into_iter().enumerate() {
+            let mut symbol = cell.symbol().to_string();
+            if i == 0 {
+                symbol = format!("\x1b]8;;{}\x07{symbol}", run.url);
+            }
+            if i == last {
+                symbol.push_str("\x1b]8;;\x07");
+            }
+            cell.set_symbol(&symbol);
+            cells.push((x, run.y, cell));
+        }
+    }
+
+    crossterm::queue!(backend, SavePosition)?;
+    backend.draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell)))?;
+    crossterm::queue!(backend, RestorePosition)?;
+    Backend::flush(backend)
+}
+
+/// Link-styled cells on one row and the target they belong to.
+#[derive(Debug)]
+struct LinkRun {
+    y: u16,
+    cells: Vec<(u16, Cell)>,
+    url: String,
+}
+
+/// Find runs of link-styled cells and match each to the link whose label
+/// contains its text. A label wrapped across rows yields one run per row.
+fn link_runs(buffer: &Buffer, links: &[LinkRef]) -> Vec<LinkRun> {
+    // Control characters in a target would end the escape early
+    let labels: Vec<(String, &str)> = links
+        .iter()
+        .filter(|link| !link.url.contains(char::is_control))
+        .map(|link| (link.display_text(), link.url.as_str()))
+        .collect();
+    if labels.is_empty() {
+        return Vec::new();
+    }
+
+    let area = buffer.area;
+    let mut runs = Vec::new();
+    for y in area.top()..area.bottom() {
+        let mut cells: Vec<(u16, Cell)> = Vec::new();
+        let mut x = area.left();
+        while x <= area.right() {
+            let cell = (x < area.right())
+                .then(|| &buffer[(x, y)])
+                .filter(|cell| is_link_cell(cell));
+            if let Some(cell) = cell {
+                cells.push((x, cell.clone()));
+                // Wide characters cover the cells after them
+                x += cell.symbol().width().max(1) as u16;
+                continue;
+            }
+            if !cells.is_empty() {
+                let text: String = cells.iter().map(|(_, c)| c.symbol()).collect();
+                let url = labels
+                    .iter()
+                    .find(|(label, _)| label.contains(text.trim()))
+                    .map(|(_, url)| url.to_string());
+                let cells = std::mem::take(&mut cells);
+                if let Some(url) = url {
+                    runs.push(LinkRun { y, cells, url });
+                }
+            }
+            x += 1;
+        }
+    }
+    runs
+}
+
+fn is_link_cell(cell: &Cell) -> bool {
+    cell.fg == theme::LINK_FG && cell.modifier.contains(Modifier::UNDERLINED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::widgets::{Paragraph, Widget};
+
+    use crate::ui::markdown::markdown_to_lines;
+
+    #[test]
+    fn matches_rendered_link_cells_to_targets() {
+        let body = "Read [the docs](https://x.dev) or <https://a.b>.\n[**bold** link](https://c.d)";
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 40, 2));
+        Paragraph::new(markdown_to_lines(body)).render(buffer.area, &mut buffer);
+
+        let runs = link_runs(&buffer, &find_links(body));
+        let summary: Vec<(u16, u16, usize, &str)> = runs
+            .iter()
+            .map(|r| (r.y, r.cells[0].0, r.cells.len(), r.url.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, 5, 8, "https://x.dev"),
+                (0, 17, 11, "https://a.b"),
+                (1, 0, 9, "https://c.d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn picker_lists_each_target_once() {
+        let mut app = App::new();
+        app.overlay = Some(Overlay::TaskDetail {
+            task: serde_json::from_value(serde_json::json!({
+                "filename": "a.md", "column": "todo", "meta": {},
+                "body": "[docs](https://x.dev), [again](https://x.dev) and <https://a.b>",
+            }))
+            .unwrap(),
+            comments: serde_json::from_value(serde_json::json!([
+                {"filename": "c1.md", "body": "see [spec](specs/a.md)"},
+            ]))
+            .unwrap(),
+            scroll: 0,
+            search: None,
+            visual: None,
+            comments_collapsed: false,
+            gone: false,
+            sections: Default::default(),
+            links: None,
+        });
+        let urls: Vec<String> = picker_links(&app).into_iter().map(|l| l.url).collect();
+        assert_eq!(urls, ["https://x.dev", "https://a.b", "specs/a.md"]);
+    }
+}
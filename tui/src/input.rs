@@ -0,0 +1,90 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_width::UnicodeWidthStr;
+
+/// Outcome of feeding a key to a `TextInput`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputOutcome {
+    Submit,
+    Cancel,
+    Edited,
+    Ignored,
+}
+
+/// Single-line text input with a cursor (measured in chars).
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    pub value: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new(value: impl Into<String>) -> Self {
+        let value = value.into();
+        let cursor = value.chars().count();
+        Self { value, cursor }
+    }
+
+    /// Display column of the cursor: the width of the text before it, so
+    /// the terminal caret lands after wide characters.
+    pub fn cursor_column(&self) -> usize {
+        self.value[..self.byte_index(self.cursor)].width()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> InputOutcome {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => return InputOutcome::Submit,
+            KeyCode::Esc => return InputOutcome::Cancel,
+            KeyCode::Char('u') if ctrl => {
+                self.value.clear();
+                self.cursor = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                let at = self.byte_index(self.cursor);
+                self.value.insert(at, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let at = self.byte_index(self.cursor);
+                self.value.remove(at);
+            }
+            KeyCode::Delete if self.cursor < self.value.chars().count() => {
+                let at = self.byte_index(self.cursor);
+                self.value.remove(at);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.value.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.chars().count(),
+            _ => return InputOutcome::Ignored,
+        }
+        InputOutcome::Edited
+    }
+
+    fn byte_index(&self, char_idx: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_idx)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_column_counts_display_width() {
+        let mut input = TextInput::new("日本a🎉");
+        assert_eq!(input.cursor_column(), 7);
+        input.handle_key(KeyCode::Left.into());
+        assert_eq!(input.cursor_column(), 5);
+        input.handle_key(KeyCode::Left.into());
+        input.handle_key(KeyCode::Left.into());
+        assert_eq!(input.cursor_column(), 2);
+        input.handle_key(KeyCode::Char('x').into());
+        assert_eq!((input.value.as_str(), input.cursor_column()), ("日x本a🎉", 3));
+    }
+}
@@ -0,0 +1,75 @@
+//! Deep links into the mdboard web UI for tasks and resources.
+
+use crate::app::ResourceType;
+use crate::export::json_scalar;
+use crate::model::{Resource, Task};
+
+pub const DEFAULT_TASK_LINK: &str = "{base_url}/board?task={id}";
+pub const DEFAULT_RESOURCE_LINK: &str = "{base_url}/{collection}?item={dir_name}";
+
+/// Link for a task, or `column/filename` when the task has no id.
+pub fn task_link(format: &str, base_url: &str, task: &Task) -> String {
+    let Some(id) = task.meta.id.as_ref().map(json_scalar) else {
+        return format!("{}/{}", task.column, task.filename);
+    };
+    fill(
+        format,
+        &[
+            ("base_url", base_url),
+            ("id", &id),
+            ("column", &task.column),
+            ("filename", &task.filename),
+        ],
+    )
+}
+
+pub fn resource_link(format: &str, base_url: &str, rtype: ResourceType, res: &Resource) -> String {
+    fill(
+        format,
+        &[
+            ("base_url", base_url),
+            ("collection", rtype.collection()),
+            ("dir_name", &res.dir_name),
+        ],
+    )
+}
+
+/// Replace `{key}` placeholders; unknown placeholders are left as-is.
+fn fill(format: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(format.to_string(), |acc, (key, value)| {
+        acc.replace(&format!("{{{key}}}"), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: Option<serde_json::Value>) -> Task {
+        let mut task: Task = serde_json::from_value(serde_json::json!({
+            "filename": "001-fix-login.md",
+            "column": "todo",
+        }))
+        .unwrap();
+        task.meta.id = id;
+        task
+    }
+
+    #[test]
+    fn task_link_fills_placeholders() {
+        let t = task(Some(serde_json::json!(1)));
+        assert_eq!(
+            task_link(DEFAULT_TASK_LINK, "http://localhost:10600", &t),
+            "http://localhost:10600/board?task=1"
+        );
+        assert_eq!(
+            task_link("{base_url}/t/{column}/{filename}#{id}", "http://h", &t),
+            "http://h/t/todo/001-fix-login.md#1"
+        );
+    }
+
+    #[test]
+    fn task_link_without_id_falls_back_to_path() {
+        assert_eq!(task_link(DEFAULT_TASK_LINK, "http://h", &task(None)), "todo/001-fix-login.md");
+    }
+}
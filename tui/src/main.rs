@@ -1,27 +1,59 @@
 mod api;
 mod app;
+mod clipboard;
+mod command;
+mod dates;
+mod eventlog;
+mod export;
+mod home;
+mod hyperlink;
+mod input;
+mod links;
 #[allow(dead_code)]
 mod model;
+mod open;
 mod poll;
+mod prompt;
+mod quick_open;
+mod recent;
+mod search;
+mod seen;
+mod snapshot;
+mod state;
 mod theme;
 mod ui;
 
-use std::io;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent,
+    MouseEventKind,
+};
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use crossterm::{execute};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
+use serde_json::json;
 use tokio::sync::mpsc;
 
 use crate::api::ApiClient;
-use crate::app::{App, ConnectionState, Focus, Overlay, ResourceType, View};
-use crate::poll::{PollMessage, spawn_poller};
+use crate::command::{Command, Filter};
+use crate::app::{
+    assignee_options, scope_options, toggle_scope, App, BoardSort, CardField, CardMetaPolicy,
+    ColumnJump, ConfirmAction, DEFAULT_AGE_DAYS, DEFAULT_CARD_DROP_ORDER,
+    ConnectionState, Focus, InputAction, InputPrompt,
+    NEW_TASK_FIELDS, Overlay, ResourceType, View,
+};
+use crate::eventlog::EventLog;
+use crate::input::{InputOutcome, TextInput};
+use crate::poll::{PAGE_SIZE, PollMessage, spawn_poller};
+use crate::quick_open::{QuickOpenItem, QuickOpenTarget};
+use crate::search::HitField;
 
 #[derive(Parser)]
 #[command(name = "mdboard-tui", about = "Terminal UI for mdboard")]
@@ -33,37 +65,226 @@ struct Cli {
     /// Data directory (for port.json discovery)
     #[arg(long, default_value = ".mdboard")]
     dir: String,
+
+    /// Path prefix the discovered server is mounted under (e.g. /tools/mdboard);
+    /// with --url, include the prefix in the URL instead
+    #[arg(long, value_name = "PATH", conflicts_with = "url")]
+    base_path: Option<String>,
+
+    /// Path the server's API is mounted under, below the server URL
+    #[arg(long, value_name = "PATH", default_value = api::DEFAULT_API_PREFIX)]
+    api_prefix: String,
+
+    /// Path of the server-sent events stream, below the server URL [default: <api-prefix>/events]
+    #[arg(long, value_name = "PATH")]
+    events_path: Option<String>,
+
+    /// Print an item as markdown to stdout and exit (e.g. task:todo/001-my-task.md)
+    #[arg(long, value_name = "SPEC")]
+    export: Option<String>,
+
+    /// Print the whole board to stdout and exit
+    #[arg(long, value_name = "FORMAT", conflicts_with = "export")]
+    export_board: Option<export::BoardFormat>,
+
+    /// View to open on start, instead of the one saved with :home [default: board]
+    #[arg(long = "home-view", visible_alias = "view", value_name = "VIEW")]
+    home_view: Option<View>,
+
+    /// Start with focus on the tab bar or the view's content [default: content]
+    #[arg(long, value_name = "FOCUS")]
+    focus: Option<Focus>,
+
+    /// Board column to select on start, by name or label
+    #[arg(long, value_name = "NAME")]
+    column: Option<String>,
+
+    /// Board columns to hide, by name or label (comma-separated)
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    hide_columns: Vec<String>,
+
+    /// Relative board column widths, by name or label (e.g. todo=2,done=0.5)
+    #[arg(long, value_name = "NAME=WEIGHT", value_delimiter = ',')]
+    column_weights: Vec<String>,
+
+    /// Keep the row index when moving between columns (toggle with `=`)
+    #[arg(long)]
+    keep_row: bool,
+
+    /// Hide board columns without tasks (toggle with `E`)
+    #[arg(long)]
+    hide_empty_columns: bool,
+
+    /// In the prompt, document and activity lists, j on the last item goes to
+    /// the first and k on the first to the last, instead of the tab bar
+    #[arg(long)]
+    wrap_lists: bool,
+
+    /// Run moves and deletes without asking for confirmation
+    #[arg(long)]
+    no_confirm: bool,
+
+    /// Never write to the server, for demos and shared screens
+    #[arg(long, alias = "safe")]
+    read_only: bool,
+
+    /// Link copied with `y` for tasks ({base_url}, {id}, {column}, {filename})
+    #[arg(long, value_name = "FORMAT", default_value = links::DEFAULT_TASK_LINK)]
+    task_link_format: String,
+
+    /// Link copied with `y` for prompts/documents ({base_url}, {collection}, {dir_name})
+    #[arg(long, value_name = "FORMAT", default_value = links::DEFAULT_RESOURCE_LINK)]
+    resource_link_format: String,
+
+    /// Shell command `P` pipes the open prompt into, showing what it prints (e.g. "llm -m gpt-4o")
+    #[arg(long, value_name = "CMD")]
+    prompt_command: Option<String>,
+
+    /// Milliseconds between UI ticks (poll message handling, status expiry)
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    tick_rate: u64,
+
+    /// Your name, shown in the header and recorded on tasks you create (default: $USER)
+    #[arg(long)]
+    user: Option<String>,
+
+    /// strftime-style format for created/updated/due/completed dates
+    #[arg(long, value_name = "FORMAT", default_value = dates::DEFAULT_DATE_FORMAT)]
+    date_format: String,
+
+    /// Save the loaded board, prompts, documents and activity to this file on quit
+    #[arg(long, value_name = "FILE")]
+    snapshot: Option<PathBuf>,
+
+    /// Browse a file written by --snapshot, read-only, without a server
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["export", "export_board"])]
+    offline: Option<PathBuf>,
+
+    /// Don't make markdown links clickable with OSC 8 escapes
+    #[arg(long)]
+    no_hyperlinks: bool,
+
+    /// Scroll with the mouse wheel; the terminal's own selection and link clicks then need Shift
+    #[arg(long)]
+    mouse: bool,
+
+    /// Don't set the terminal window title
+    #[arg(long)]
+    no_title: bool,
+
+    /// Terminal width below which the board shows one column at a time (0 disables)
+    #[arg(long, value_name = "COLUMNS", default_value_t = app::DEFAULT_NARROW_WIDTH)]
+    narrow_width: u16,
+
+    /// Show at most this many board columns; the rest collapse into "+N more" (0 shows all)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    max_columns: usize,
+
+    /// When card metadata doesn't fit: cut it with "…" or drop whole fields
+    #[arg(long, value_name = "POLICY", default_value = "truncate")]
+    card_meta: CardMetaPolicy,
+
+    /// Fields --card-meta drop leaves out, first dropped first
+    #[arg(
+        long,
+        value_name = "FIELDS",
+        value_delimiter = ',',
+        value_enum,
+        default_values_t = DEFAULT_CARD_DROP_ORDER
+    )]
+    card_meta_drop: Vec<CardField>,
+
+    /// Age in days up to which cards count as fresh, and from which they are stale (H)
+    #[arg(
+        long,
+        value_name = "FRESH,STALE",
+        value_delimiter = ',',
+        default_values_t = [DEFAULT_AGE_DAYS.0, DEFAULT_AGE_DAYS.1]
+    )]
+    age_days: Vec<u32>,
+
+    /// Append JSON lines for poll messages, connection changes and keys to this file
+    #[arg(long, value_name = "FILE")]
+    log_file: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    let base_url = match cli.url {
-        Some(url) => url,
-        None => discover_url(&cli.dir)?,
+    dates::validate_format(&cli.date_format)?;
+    if let Some(entry) = cli.column_weights.iter().find(|e| !e.contains('=')) {
+        anyhow::bail!("Invalid --column-weights entry {entry:?}, expected NAME=WEIGHT");
+    }
+    if !matches!(cli.age_days[..], [fresh, stale] if fresh < stale) {
+        anyhow::bail!("Invalid --age-days, expected FRESH,STALE with FRESH below STALE");
+    }
+    let snapshot = cli.offline.as_deref().map(snapshot::load).transpose()?;
+    // Exports need a server; the UI instead shows the connecting screen,
+    // where `r` retries discovery
+    let exporting = cli.export.is_some() || cli.export_board.is_some();
+    let (base_url, discover_error) = match &cli.url {
+        Some(url) => (url.clone(), None),
+        None if snapshot.is_some() => (String::new(), None),
+        None => match discover_url(&cli.dir, cli.base_path.as_deref()) {
+            Ok(url) => (url, None),
+            Err(e) if !exporting => (String::new(), Some(e)),
+            Err(e) => return Err(e),
+        },
     };
 
-    let api = ApiClient::new(&base_url);
+    let api = api_client(&cli, &base_url);
+
+    if let Some(spec) = &cli.export {
+        print!("{}", export::export_spec(&api, spec).await?);
+        return Ok(());
+    }
+    if let Some(format) = cli.export_board {
+        print!("{}", export::export_board(&api, format).await?);
+        return Ok(());
+    }
 
     // Set up terminal
     terminal::enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    if cli.mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    if !cli.no_title {
+        // Save the current title on the xterm title stack for restoring on exit
+        write!(stdout, "{PUSH_TITLE}")?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = run_app(&mut terminal, api).await;
+    let result = run_app(&mut terminal, api, &cli, snapshot, discover_error).await;
 
     // Restore terminal
     terminal::disable_raw_mode()?;
+    if cli.mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    if !cli.no_title {
+        write!(terminal.backend_mut(), "{POP_TITLE}")?;
+    }
     terminal.show_cursor()?;
 
     result
 }
 
-fn discover_url(dir: &str) -> Result<String> {
+/// xterm window title stack; terminals without it ignore these.
+const PUSH_TITLE: &str = "\x1b[22;0t";
+const POP_TITLE: &str = "\x1b[23;0t";
+
+/// How long quitting waits for queued writes before giving up on them.
+const QUIT_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a `--prompt-command` may run before it is killed.
+const PROMPT_COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn discover_url(dir: &str, base_path: Option<&str>) -> Result<String> {
     let port_file = PathBuf::from(dir).join("port.json");
     let content = std::fs::read_to_string(&port_file)
         .with_context(|| format!("Cannot read {port_file:?} — is the mdboard server running?\nStart it with: cd server && uv run mdboard --dir ../.mdboard\nOr specify --url manually."))?;
@@ -72,41 +293,228 @@ fn discover_url(dir: &str) -> Result<String> {
     let port = info["port"]
         .as_u64()
         .context("port.json missing 'port' field")?;
-    Ok(format!("http://localhost:{port}"))
+    // port.json may also name the prefix, for servers behind a local proxy
+    let base_path = base_path
+        .or_else(|| info["base_path"].as_str())
+        .unwrap_or_default();
+    Ok(api::local_url(port, base_path))
+}
+
+fn api_client(cli: &Cli, url: &str) -> ApiClient {
+    ApiClient::new(url)
+        .read_only(cli.read_only)
+        .api_prefix(&cli.api_prefix)
+        .events_path(cli.events_path.as_deref())
+}
+
+/// Server URL from `--url`, or discovered again from port.json.
+fn server_url(cli: &Cli) -> Result<String> {
+    match &cli.url {
+        Some(url) => Ok(url.clone()),
+        None => discover_url(&cli.dir, cli.base_path.as_deref()),
+    }
 }
 
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    api: ApiClient,
+    mut api: ApiClient,
+    cli: &Cli,
+    snapshot: Option<snapshot::Snapshot>,
+    discover_error: Option<anyhow::Error>,
 ) -> Result<()> {
     let mut app = App::new();
+    app.data_dir = PathBuf::from(&cli.dir);
+    app.server_url = api.base_url().to_string();
+    app.user = cli
+        .user
+        .clone()
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_default();
+    app.task_link_format = cli.task_link_format.clone();
+    app.date_format = cli.date_format.clone();
+    app.narrow_width = cli.narrow_width;
+    app.max_columns = cli.max_columns;
+    app.card_meta = cli.card_meta;
+    app.card_drop_order = cli.card_meta_drop.clone();
+    app.age_days = (cli.age_days[0], cli.age_days[1]);
+    app.keep_row = cli.keep_row;
+    app.hide_empty_columns = cli.hide_empty_columns;
+    app.wrap_lists = cli.wrap_lists;
+    app.confirm = !cli.no_confirm;
+    app.read_only = cli.read_only;
+    // Flags override the home saved with :home
+    let home = home::state_path().and_then(|path| home::load(&path));
+    app.view = cli.home_view.or(home.map(|(view, _)| view)).unwrap_or(View::Board);
+    app.focus = cli.focus.or(home.map(|(_, focus)| focus)).unwrap_or(Focus::Content);
+    app.start_column = cli.column.clone();
+    app.resource_link_format = cli.resource_link_format.clone();
+    app.prompt_command = cli.prompt_command.clone();
+    app.column_weight_overrides = cli
+        .column_weights
+        .iter()
+        .filter_map(|e| e.split_once('='))
+        .map(|(name, weight)| (name.trim().to_lowercase(), weight.trim().to_string()))
+        .collect();
+    app.hidden_columns = cli
+        .hide_columns
+        .iter()
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    // History is kept per server; offline snapshots browse without it
+    let recent_path = recent::state_path().filter(|_| snapshot.is_none());
+    if let Some(path) = &recent_path {
+        app.recent = recent::load(path, &app.server_url);
+    }
+    let seen_path = seen::state_path().filter(|_| snapshot.is_none());
+    if let Some(path) = &seen_path {
+        app.last_viewed_mtime = seen::load(path, &app.server_url);
+    }
 
-    // Start background poller
+    // Start background poller, unless browsing an offline snapshot
     let (tx, mut rx) = mpsc::unbounded_channel::<PollMessage>();
-    spawn_poller(api.clone(), tx);
+    let mut poller = None;
+    match (snapshot, discover_error) {
+        (Some(snapshot), _) => {
+            snapshot.apply(&mut app);
+            app.apply_start_column();
+            app.connection = ConnectionState::Offline;
+        }
+        (None, Some(e)) => app.connect_error = Some(format!("{e:#}")),
+        (None, None) => poller = Some(spawn_poller(api.clone(), tx.clone(), app.loaded_window.clone())),
+    }
+
+    let log = match &cli.log_file {
+        Some(path) => EventLog::open(path).await?,
+        None => EventLog::default(),
+    };
+    log.log("start", json!({"url": app.server_url, "offline": app.offline_since.is_some()}));
+
+    let tick_rate = Duration::from_millis(cli.tick_rate.max(1));
+    let mut last_tick = Instant::now();
+    let mut title = String::new();
 
     loop {
-        terminal.draw(|f| ui::render(f, &app))?;
-
-        // Multiplex terminal events and poll messages
-        tokio::select! {
-            // Check for terminal events (with short timeout to stay responsive)
-            _ = tokio::time::sleep(Duration::from_millis(50)) => {
-                while event::poll(Duration::ZERO)? {
-                    if let Event::Key(key) = event::read()? {
-                        handle_key(&mut app, &api, key).await;
+        if app.dirty {
+            if !cli.no_title && app.window_title() != title {
+                title = app.window_title();
+                execute!(terminal.backend_mut(), terminal::SetTitle(&title))?;
+            }
+            let links = if cli.no_hyperlinks {
+                Vec::new()
+            } else {
+                hyperlink::overlay_links(&app)
+            };
+            let frame = terminal.draw(|f| ui::render(f, &app))?;
+            if !links.is_empty() {
+                let buffer = frame.buffer.clone();
+                hyperlink::emit(terminal.backend_mut(), &buffer, &links)?;
+            }
+            app.dirty = false;
+        }
+
+        // Block on terminal input until the next tick so keys are handled immediately
+        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        if tokio::task::block_in_place(|| event::poll(timeout))? {
+            match event::read()? {
+                Event::Key(key) => {
+                    if log.is_enabled() {
+                        log.log(
+                            "key",
+                            json!({
+                                "code": format!("{:?}", key.code),
+                                "modifiers": format!("{:?}", key.modifiers),
+                                "view": format!("{:?}", app.view),
+                                "overlay": app.overlay.is_some(),
+                            }),
+                        );
+                    }
+                    let view = app.view;
+                    handle_key(&mut app, &api, key).await;
+                    if std::mem::take(&mut app.retry_connect) {
+                        log.log("retry_connect", json!({}));
+                        if let Some(poller) = poller.take() {
+                            poller.abort();
+                        }
+                        match server_url(cli) {
+                            Ok(url) => {
+                                api = api_client(cli, &url);
+                                app.server_url = url;
+                                app.connect_error = None;
+                                app.connection = ConnectionState::Connecting;
+                                if let Some(path) = &recent_path {
+                                    app.recent = recent::load(path, &app.server_url);
+                                }
+                                if let Some(path) = &seen_path {
+                                    app.last_viewed_mtime = seen::load(path, &app.server_url);
+                                }
+                                poller = Some(spawn_poller(api.clone(), tx.clone(), app.loaded_window.clone()));
+                            }
+                            Err(e) => app.connect_error = Some(format!("{e:#}")),
+                        }
+                    }
+                    if let Some((command, body)) = app.prompt_run.take() {
+                        log.log("prompt_command", json!({"command": command}));
+                        spawn_prompt_command(command, body, tx.clone());
+                    }
+                    // Leaving Activity (or quitting from it) marks its entries as seen
+                    if view == View::Activity && (app.view != view || app.should_quit) {
+                        app.mark_activity_viewed();
                     }
                 }
+                Event::Mouse(mouse) => handle_mouse(&mut app, mouse),
+                Event::Resize(..) => app.mark_dirty(),
+                _ => {}
             }
-            // Process poll messages
-            msg = rx.recv() => {
-                if let Some(msg) = msg {
-                    handle_poll_message(&mut app, msg);
-                }
+        }
+
+        // Process poll messages that arrived in the meantime
+        while let Ok(msg) = rx.try_recv() {
+            let connection = app.connection;
+            if log.is_enabled() {
+                log.log("poll", msg.log_fields());
+            }
+            handle_poll_message(&mut app, msg);
+            if app.connection != connection {
+                log.log(
+                    "connection",
+                    json!({
+                        "from": format!("{connection:?}"),
+                        "to": format!("{:?}", app.connection),
+                    }),
+                );
+            }
+        }
+
+        if last_tick.elapsed() >= tick_rate {
+            last_tick = Instant::now();
+            app.expire_status();
+            // Keep the "last poll" age in the debug overlay and the
+            // connecting spinner ticking
+            if matches!(app.overlay, Some(Overlay::Debug)) || app.awaiting_first_data() {
+                app.mark_dirty();
             }
         }
 
         if app.should_quit {
+            log.log("quit", json!({"forced": app.force_quit}));
+            if !app.force_quit && log.pending() > 0 {
+                app.set_status("Saving…");
+                terminal.draw(|f| ui::render(f, &app))?;
+                log.flush(QUIT_FLUSH_TIMEOUT).await;
+            }
+            // Quitting the connecting screen must not overwrite a snapshot with nothing
+            if let (Some(path), None, Some(_)) = (&cli.snapshot, &app.offline_since, &app.board) {
+                snapshot::save(&app, path)?;
+            }
+            if let Some(path) = &recent_path {
+                // History is a convenience; failing to save it shouldn't fail the exit
+                let _ = recent::save(path, &app.server_url, &app.recent);
+            }
+            if let (Some(path), Some(mtime)) = (&seen_path, app.last_viewed_mtime) {
+                let _ = seen::save(path, &app.server_url, mtime);
+            }
             return Ok(());
         }
     }
@@ -122,54 +530,168 @@ fn handle_poll_message(app: &mut App, msg: PollMessage) {
             documents,
             activity,
         } => {
+            // A reconnect replaces everything; keep the same items selected
+            let anchor = app.selection_anchor();
             app.version = Some(version);
-            app.board = Some(board);
-            app.config = Some(config);
-            app.prompts = prompts;
-            app.documents = documents;
+            app.set_board(board);
+            app.apply_config(config);
+            app.set_resources(ResourceType::Prompt, prompts);
+            app.set_resources(ResourceType::Document, documents);
             app.activity = activity;
             app.connection = ConnectionState::Connected;
+            app.connect_error = None;
             app.loading = false;
-            app.ensure_board_row_vec();
             app.clamp_indices();
+            app.restore_selection(anchor);
+            app.apply_start_column();
+            app.mark_dirty();
         }
         PollMessage::HashesChanged(hashes) => {
             app.poll_hashes = Some(hashes);
             app.last_poll = Some(std::time::Instant::now());
         }
         PollMessage::BoardUpdated(board) => {
-            app.board = Some(board);
-            app.ensure_board_row_vec();
-            app.clamp_indices();
+            app.set_board(board);
+            notify_watched(app);
+            app.refresh_open_overlay();
+            app.mark_dirty();
+        }
+        PollMessage::ConfigUpdated(config) => {
+            app.apply_config(config);
+            app.mark_dirty();
         }
         PollMessage::PromptsUpdated(prompts) => {
-            app.prompts = prompts;
+            app.set_resources(ResourceType::Prompt, prompts);
             app.clamp_indices();
+            app.refresh_open_overlay();
+            app.mark_dirty();
         }
         PollMessage::DocumentsUpdated(documents) => {
-            app.documents = documents;
+            app.set_resources(ResourceType::Document, documents);
             app.clamp_indices();
+            app.refresh_open_overlay();
+            app.mark_dirty();
         }
         PollMessage::ActivityUpdated(activity) => {
             app.activity = activity;
             app.clamp_indices();
+            app.mark_dirty();
+        }
+        PollMessage::TaskUpdated(task) => {
+            app.apply_task_update(task);
+            notify_watched(app);
+            app.refresh_open_overlay();
+            app.mark_dirty();
+        }
+        PollMessage::Reconnecting(count) => {
+            app.sse_reconnects = count;
+            app.mark_dirty();
         }
         PollMessage::ConnectionLost => {
             app.connection = ConnectionState::Disconnected;
+            app.mark_dirty();
         }
         PollMessage::ConnectionRestored => {
             app.connection = ConnectionState::Connected;
+            app.mark_dirty();
+        }
+        PollMessage::Error(error) => {
+            // Connection state follows via ConnectionLost; the text is for
+            // the connecting screen
+            app.connect_error = Some(error);
+            if app.awaiting_first_data() {
+                app.mark_dirty();
+            }
         }
-        PollMessage::Error(_) => {
-            // Errors are reflected via ConnectionLost
+        PollMessage::PromptOutput { command, result } => {
+            app.prompt_running = false;
+            match result {
+                // Don't throw away a form or picker opened in the meantime
+                Ok(output) if matches!(app.overlay, None | Some(Overlay::ResourceDetail { .. })) => {
+                    app.status_message = None;
+                    app.overlay = Some(Overlay::CommandOutput {
+                        title: command,
+                        output,
+                        scroll: 0,
+                    });
+                    app.mark_dirty();
+                }
+                Ok(_) => app.set_status("Prompt command finished while another overlay was open; output dropped"),
+                Err(e) => app.set_status(format!("Prompt command failed: {e}")),
+            }
         }
     }
 }
 
+/// Report changes to watched tasks in the status bar and ring the terminal bell.
+fn notify_watched(app: &mut App) {
+    let notes = app.check_watched();
+    if notes.is_empty() {
+        return;
+    }
+    app.set_status(notes.join(" · "));
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+}
+
 async fn handle_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
-    // Global: Ctrl+C always quits
+    // Nearly every key moves a cursor or opens something; redraw unconditionally
+    app.mark_dirty();
+
+    // Global: Ctrl+C always quits, without waiting for pending writes
     if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
         app.should_quit = true;
+        app.force_quit = true;
+        return;
+    }
+
+    // The connecting screen only retries or quits
+    if app.awaiting_first_data() {
+        match key.code {
+            KeyCode::Char('r') => app.retry_connect = true,
+            KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
+            _ => {}
+        }
+        return;
+    }
+
+    // Input prompt captures all keys while open
+    if app.input.is_some() {
+        handle_input_key(app, api, key).await;
+        return;
+    }
+
+    // F12 toggles the debug overlay from anywhere
+    if key.code == KeyCode::F(12) {
+        toggle_debug_overlay(app);
+        return;
+    }
+
+    // Ctrl+P opens quick-open from anywhere, replacing any open overlay
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('p') {
+        open_quick_open(app);
+        return;
+    }
+
+    // Ctrl+R lists recently opened items the same way
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('r') {
+        open_recent(app);
+        return;
+    }
+
+    // On the board Ctrl+e / Ctrl+y scroll the column without moving the
+    // selection, as in vim
+    if key.modifiers.contains(KeyModifiers::CONTROL) && board_has_focus(app) {
+        match key.code {
+            KeyCode::Char('e') => return scroll_board_column(app, app.board_col, 1),
+            KeyCode::Char('y') => return scroll_board_column(app, app.board_col, -1),
+            _ => {}
+        }
+    }
+
+    // Elsewhere Ctrl+Y copies the last API request as curl, for server bug reports
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('y') {
+        copy_last_request(app, api);
         return;
     }
 
@@ -179,6 +701,31 @@ async fn handle_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
         return;
     }
 
+    // Pending column jump consumes the next key (digits would otherwise switch views)
+    if let Some(jump) = app.column_jump {
+        let target = match key.code {
+            KeyCode::Char(c) => app.column_jump_target(c),
+            _ => None,
+        };
+        app.column_jump = None;
+        match (jump, target) {
+            (ColumnJump::MoveMarked, Some(col)) => {
+                let label = app
+                    .board
+                    .as_ref()
+                    .and_then(|b| b.columns.get(col))
+                    .map(|c| c.display_label().to_string())
+                    .unwrap_or_default();
+                let count = app.marked.len();
+                let message = format!("Move {count} marked {} to {label}?", tasks(count));
+                confirm_or_run(app, api, message, ConfirmAction::MoveMarked(col)).await;
+            }
+            (_, Some(col)) => app.select_column(col),
+            (_, None) => {}
+        }
+        return;
+    }
+
     // Global keys that work regardless of focus
     match key.code {
         KeyCode::Char('q') => {
@@ -193,8 +740,32 @@ async fn handle_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
             app.overlay = Some(Overlay::Help { scroll: 0 });
             return;
         }
+        KeyCode::Char('~') => {
+            toggle_debug_overlay(app);
+            return;
+        }
+        KeyCode::Char(':') => {
+            app.input = Some(InputPrompt {
+                label: "Command".to_string(),
+                input: TextInput::default(),
+                action: InputAction::Command,
+            });
+            return;
+        }
+        KeyCode::Char('y') => {
+            copy_link(app);
+            return;
+        }
         KeyCode::Char('r') => {
-            refresh_current_view(app, api).await;
+            if !offline_blocked(app) {
+                refresh_current_view(app, api).await;
+            }
+            return;
+        }
+        KeyCode::Char('R') => {
+            if !offline_blocked(app) {
+                reload_config(app, api).await;
+            }
             return;
         }
         _ => {}
@@ -237,71 +808,278 @@ async fn handle_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
 }
 
 async fn handle_board_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
-    let ncols = app.column_count();
-    if ncols == 0 {
+    if key.code == KeyCode::Char('X') && (!app.hidden_columns.is_empty() || app.hide_empty_columns) {
+        app.hidden_columns.clear();
+        app.hide_empty_columns = false;
+        app.clamp_indices();
+        app.set_status("Showing all columns");
+        return;
+    }
+    if app.column_count() == 0 {
         return;
     }
 
     match key.code {
         KeyCode::Char('h') | KeyCode::Left => {
-            if app.board_col > 0 {
-                app.board_col -= 1;
-            }
+            app.step_column(-1, screen_rect().width);
         }
         KeyCode::Char('l') | KeyCode::Right => {
-            if app.board_col + 1 < ncols {
-                app.board_col += 1;
+            let width = screen_rect().width;
+            let moved = app.step_column(1, width);
+            // Past the last column on screen, pick one of the collapsed ones
+            let overflow = app.overflow_columns(width);
+            if !moved && !overflow.is_empty() {
+                app.overlay = Some(Overlay::ColumnPicker {
+                    columns: overflow,
+                    selected: 0,
+                });
             }
         }
-        KeyCode::Char('j') | KeyCode::Down => {
-            let tasks_len = app.current_column_tasks().len();
-            if tasks_len > 0 {
-                let row = app.current_board_row();
-                if row + 1 < tasks_len {
-                    app.set_board_row(row + 1);
-                }
+        KeyCode::Char('E') => {
+            let hidden = app.toggle_empty_columns();
+            app.set_status(if hidden {
+                "Hiding empty columns"
+            } else {
+                "Showing empty columns"
+            });
+        }
+        KeyCode::Char('=') => {
+            app.keep_row = !app.keep_row;
+            app.set_status(if app.keep_row {
+                "Moving between columns keeps the row"
+            } else {
+                "Moving between columns restores each column's row"
+            });
+        }
+        KeyCode::Char('H') => {
+            app.age_colors = !app.age_colors;
+            let (fresh, stale) = app.age_days;
+            app.set_status(if app.age_colors {
+                format!("Age colors: fresh up to {fresh}d, stale from {stale}d")
+            } else {
+                "Age colors off".to_string()
+            });
+        }
+        KeyCode::Char('%') => {
+            let rows = app.progress_rows();
+            if rows.is_empty() {
+                app.set_status("No task has checkboxes");
+            } else {
+                app.overlay = Some(Overlay::Progress { rows, selected: 0 });
+            }
+        }
+        KeyCode::Char('!') => {
+            let jumped = app.jump_to_over_wip();
+            if !jumped {
+                app.set_status("No column is over its WIP limit");
             }
         }
+        KeyCode::Char('x') => {
+            let hidden = app.hide_current_column();
+            if !hidden {
+                app.set_status("Cannot hide the last visible column");
+            }
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.step_row(1);
+        }
         KeyCode::Char('k') | KeyCode::Up => {
-            let row = app.current_board_row();
-            if row > 0 {
-                app.set_board_row(row - 1);
-            } else {
+            // Moving up from the first task leaves the board for the tab bar
+            let moved = app.step_row(-1);
+            if !moved {
                 app.focus = Focus::TabBar;
             }
         }
+        KeyCode::Char('@') => {
+            match app.cycle_assignee_filter() {
+                Some(name) => app.set_status(format!("Showing tasks assigned to @{name}")),
+                None => app.set_status("Showing all assignees"),
+            }
+        }
+        KeyCode::Char('t') => {
+            match app.cycle_status_filter() {
+                Some(status) => app.set_status(format!("Showing {status} tasks")),
+                None => app.set_status("Showing all statuses"),
+            }
+        }
+        KeyCode::Backspace if app.has_filter() => {
+            app.clear_filters();
+            app.set_status("Showing all tasks");
+        }
+        KeyCode::Char('J' | 'K' | 'n' | 's' | 'a' | 'S' | 'D' | 'M' | '+') if offline_blocked(app) => {}
+        KeyCode::Char('J' | 'K' | 'n' | 'a' | 'S' | 'D' | 'M' | '+') if read_only_blocked(app) => {}
+        KeyCode::Esc if !app.marked.is_empty() => {
+            app.marked.clear();
+        }
+        KeyCode::Esc if app.board_search.is_some() => {
+            app.set_board_search(None);
+        }
+        KeyCode::Char('/') => {
+            app.input = Some(InputPrompt {
+                label: "Find on board".to_string(),
+                input: TextInput::new(app.board_search.clone().unwrap_or_default()),
+                action: InputAction::BoardSearch,
+            });
+        }
+        KeyCode::Char(' ') => {
+            app.toggle_marked();
+        }
+        KeyCode::Char('w') => {
+            if let Some((watching, title)) = app.toggle_watch() {
+                if watching {
+                    app.set_status(format!("Watching \"{title}\""));
+                } else {
+                    app.set_status(format!("Stopped watching \"{title}\""));
+                }
+            }
+        }
+        KeyCode::Char('M') | KeyCode::Char('+') if app.marked.is_empty() => {
+            app.set_status("Mark tasks with Space first");
+        }
+        KeyCode::Char('M') => {
+            app.column_jump = Some(ColumnJump::MoveMarked);
+            app.set_status(format!("Move {} marked to column 1-9", app.marked.len()));
+        }
+        KeyCode::Char('+') => {
+            app.input = Some(InputPrompt {
+                label: format!("Add scope to {} marked", app.marked.len()),
+                input: TextInput::default(),
+                action: InputAction::BulkAddScope,
+            });
+        }
+        KeyCode::Char('J') | KeyCode::Char('K') if app.board_sort != BoardSort::Manual => {
+            app.set_status("Switch to manual sort (s) to reorder tasks");
+        }
+        KeyCode::Char('J') | KeyCode::Char('K') => {
+            let delta = if key.code == KeyCode::Char('J') { 1 } else { -1 };
+            // Optimistic: the next board poll reconciles with the server's order
+            if let Some((column, filename, index)) = app.move_selected_task(delta) {
+                if let Err(e) = api.reorder_task(&column, &filename, index).await {
+                    app.set_status(format!("Reorder failed: {e}"));
+                }
+            }
+        }
+        KeyCode::Char('s') => {
+            let selected = app
+                .selected_task()
+                .map(|t| (t.column.clone(), t.filename.clone()));
+            app.board_sort = match app.board_sort {
+                BoardSort::Manual => BoardSort::Priority,
+                BoardSort::Priority => BoardSort::Manual,
+            };
+            // Manual order is the server's, so re-fetch rather than un-sort
+            match api.board().await {
+                Ok(board) => app.set_board(board),
+                Err(e) => {
+                    app.set_status(format!("Failed to load board: {e}"));
+                    return;
+                }
+            }
+            if let Some((column, filename)) = selected {
+                app.select_task(&column, &filename);
+            }
+            app.set_status(format!("Sort: {}", app.board_sort.label()));
+        }
+        KeyCode::Char('n') => {
+            if let Some(col) = app.board.as_ref().and_then(|b| b.columns.get(app.board_col)) {
+                app.overlay = Some(Overlay::NewTask {
+                    column: col.name.clone(),
+                    fields: vec![TextInput::default(); NEW_TASK_FIELDS.len()],
+                    focused: 0,
+                    error: None,
+                });
+            }
+        }
+        KeyCode::Char('a') => {
+            if let Some(task) = app.selected_task() {
+                let (column, filename) = (task.column.clone(), task.filename.clone());
+                let known = app.known_assignees();
+                let options = assignee_options(&known, "");
+                // Start on the current assignee
+                let selected = options
+                    .iter()
+                    .position(|name| *name == task.meta.assignee)
+                    .unwrap_or(0);
+                app.overlay = Some(Overlay::AssigneePicker {
+                    column,
+                    filename,
+                    query: TextInput::default(),
+                    known,
+                    options,
+                    selected,
+                });
+            }
+        }
+        KeyCode::Char('D') => {
+            if let Some(task) = app.selected_task() {
+                let message = format!(
+                    "Delete \"{}\"? This removes {} from the server.",
+                    task.display_title(),
+                    task.filename
+                );
+                let action = ConfirmAction::DeleteTask {
+                    column: task.column.clone(),
+                    filename: task.filename.clone(),
+                };
+                confirm_or_run(app, api, message, action).await;
+            }
+        }
+        KeyCode::Char('S') => {
+            if let Some(task) = app.selected_task() {
+                let (column, filename) = (task.column.clone(), task.filename.clone());
+                let chosen: Vec<String> = task.meta.scopes.as_vec().iter().map(|s| s.to_string()).collect();
+                let known = app.known_scopes();
+                let options = scope_options(&known, &chosen, "");
+                app.overlay = Some(Overlay::ScopeEditor {
+                    column,
+                    filename,
+                    query: TextInput::default(),
+                    known,
+                    chosen,
+                    options,
+                    selected: 0,
+                });
+            }
+        }
+        KeyCode::Char('m') => {
+            app.column_jump = Some(ColumnJump::Number);
+        }
+        KeyCode::Char('f') => {
+            app.column_jump = Some(ColumnJump::Hint);
+        }
         KeyCode::Char('g') => {
-            app.set_board_row(0);
+            app.select_edge_row(false);
         }
-        KeyCode::Char('G') => {
-            let tasks_len = app.current_column_tasks().len();
-            if tasks_len > 0 {
-                app.set_board_row(tasks_len - 1);
+        KeyCode::Char('}' | '{') => {
+            let has_scopes = app
+                .selected_task()
+                .is_some_and(|t| !t.meta.scopes.as_vec().is_empty());
+            if !has_scopes {
+                app.set_status("Task has no scopes");
+            } else if app.step_shared_scope(key.code == KeyCode::Char('}')) == 0 {
+                app.set_status("No other task shares its scopes");
             }
         }
-        KeyCode::Enter | KeyCode::Char(' ') => {
+        KeyCode::Char('i') => copy_task_id(app),
+        KeyCode::Char('I') => {
+            let found = app.select_first_incomplete();
+            if !found {
+                app.set_status("All done — no incomplete tasks");
+            }
+        }
+        KeyCode::Char('G') => {
+            app.select_edge_row(true);
+        }
+        KeyCode::Enter | KeyCode::Char('z') => {
             if let Some(task) = app.selected_task().cloned() {
-                // Fetch full task detail + comments
+                app.focus_mode = key.code == KeyCode::Char('z');
                 let task_id = task
                     .meta
                     .id
                     .as_ref()
                     .map(|v| v.to_string())
                     .unwrap_or_default();
-                let full_task = api
-                    .get_task(&task.column, &task.filename)
-                    .await
-                    .unwrap_or(task.clone());
-                let comments = if !task_id.is_empty() {
-                    api.get_comments(&task_id).await.unwrap_or_default()
-                } else {
-                    vec![]
-                };
-                app.overlay = Some(Overlay::TaskDetail {
-                    task: full_task,
-                    comments,
-                    scroll: 0,
-                });
+                open_task(app, api, &task.column, &task.filename, &task_id).await;
             }
         }
         _ => {}
@@ -309,9 +1087,9 @@ async fn handle_board_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
 }
 
 async fn handle_list_key(app: &mut App, api: &ApiClient, key: KeyEvent, rtype: ResourceType) {
-    let (len, index) = match rtype {
-        ResourceType::Prompt => (app.prompts.len(), &mut app.prompt_index),
-        ResourceType::Document => (app.documents.len(), &mut app.document_index),
+    let (len, index, has_more) = match rtype {
+        ResourceType::Prompt => (app.prompts.len(), &mut app.prompt_index, app.prompts_has_more),
+        ResourceType::Document => (app.documents.len(), &mut app.document_index, app.documents_has_more),
     };
 
     if len == 0 {
@@ -324,16 +1102,11 @@ async fn handle_list_key(app: &mut App, api: &ApiClient, key: KeyEvent, rtype: R
 
     match key.code {
         KeyCode::Char('j') | KeyCode::Down => {
-            if *index + 1 < len {
-                *index += 1;
-            }
+            // Unloaded pages come before the wrap back to the top
+            step_list(index, len, 1, app.wrap_lists && !has_more);
         }
-        KeyCode::Char('k') | KeyCode::Up => {
-            if *index > 0 {
-                *index -= 1;
-            } else {
-                app.focus = Focus::TabBar;
-            }
+        KeyCode::Char('k') | KeyCode::Up if !step_list(index, len, -1, app.wrap_lists) => {
+            app.focus = Focus::TabBar;
         }
         KeyCode::Char('g') => {
             *index = 0;
@@ -342,42 +1115,69 @@ async fn handle_list_key(app: &mut App, api: &ApiClient, key: KeyEvent, rtype: R
             *index = len - 1;
         }
         KeyCode::Enter | KeyCode::Char(' ') => {
-            let (resources, idx) = match rtype {
-                ResourceType::Prompt => (&app.prompts, app.prompt_index),
-                ResourceType::Document => (&app.documents, app.document_index),
-            };
-            if let Some(res) = resources.get(idx).cloned() {
-                let dir_name = res.dir_name.clone();
-                // Fetch full resource + revisions
-                let full_res = match rtype {
-                    ResourceType::Prompt => api.get_prompt(&dir_name).await.unwrap_or(res),
-                    ResourceType::Document => api.get_document(&dir_name).await.unwrap_or(res),
-                };
-                let revisions = match rtype {
-                    ResourceType::Prompt => {
-                        api.list_prompt_revisions(&dir_name).await.unwrap_or_default()
-                    }
-                    ResourceType::Document => {
-                        api.list_document_revisions(&dir_name)
-                            .await
-                            .unwrap_or_default()
-                    }
-                };
-                app.overlay = Some(Overlay::ResourceDetail {
-                    resource: full_res,
-                    revisions,
-                    current_rev: None,
-                    scroll: 0,
-                    resource_type: rtype,
-                });
+            if let Some(dir_name) = app.selected_resource(rtype).map(|r| r.dir_name.clone()) {
+                open_resource(app, api, rtype, &dir_name).await;
             }
         }
+        KeyCode::Char('s') => {
+            let sort = app.cycle_resource_sort(rtype);
+            app.set_status(format!("Sort: {}", sort.label()));
+        }
         _ => {}
     }
+
+    load_more_if_needed(app, api, rtype).await;
+}
+
+/// Move a list selection by `delta` (±1). Past either end `wrap` continues
+/// from the other end; otherwise the selection stays and false is returned.
+fn step_list(index: &mut usize, len: usize, delta: i32, wrap: bool) -> bool {
+    let next = *index as i64 + delta as i64;
+    if (0..len as i64).contains(&next) {
+        *index = next as usize;
+    } else if wrap && len > 0 {
+        *index = next.rem_euclid(len as i64) as usize;
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Fetch the next page of a list once the selection nears the loaded end.
+async fn load_more_if_needed(app: &mut App, api: &ApiClient, rtype: ResourceType) {
+    const LOAD_AHEAD: usize = 10;
+    let (len, index, has_more) = match rtype {
+        ResourceType::Prompt => (app.prompts.len(), app.prompt_index, app.prompts_has_more),
+        ResourceType::Document => (app.documents.len(), app.document_index, app.documents_has_more),
+    };
+    if !has_more || index + LOAD_AHEAD < len {
+        return;
+    }
+    let page = match rtype {
+        ResourceType::Prompt => api.list_prompts(len, PAGE_SIZE).await,
+        ResourceType::Document => api.list_documents(len, PAGE_SIZE).await,
+    };
+    match page {
+        Ok(page) => app.append_resources(rtype, page),
+        Err(e) => app.set_status(format!("Failed to load more: {e}")),
+    }
 }
 
 async fn handle_activity_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
-    let len = app.activity.len();
+    let entry_type = match key.code {
+        KeyCode::Char('t') => Some("task"),
+        KeyCode::Char('p') => Some("prompt"),
+        KeyCode::Char('d') => Some("document"),
+        _ => None,
+    };
+    if let Some(entry_type) = entry_type {
+        let shown = app.toggle_activity_type(entry_type);
+        let state = if shown { "Showing" } else { "Hiding" };
+        app.set_status(format!("{state} {entry_type} activity"));
+        return;
+    }
+
+    let len = app.filtered_activity().len();
     if len == 0 {
         if matches!(key.code, KeyCode::Char('k') | KeyCode::Up) {
             app.focus = Focus::TabBar;
@@ -387,16 +1187,10 @@ async fn handle_activity_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
 
     match key.code {
         KeyCode::Char('j') | KeyCode::Down => {
-            if app.activity_index + 1 < len {
-                app.activity_index += 1;
-            }
+            step_list(&mut app.activity_index, len, 1, app.wrap_lists);
         }
-        KeyCode::Char('k') | KeyCode::Up => {
-            if app.activity_index > 0 {
-                app.activity_index -= 1;
-            } else {
-                app.focus = Focus::TabBar;
-            }
+        KeyCode::Char('k') | KeyCode::Up if !step_list(&mut app.activity_index, len, -1, app.wrap_lists) => {
+            app.focus = Focus::TabBar;
         }
         KeyCode::Char('g') => {
             app.activity_index = 0;
@@ -405,7 +1199,7 @@ async fn handle_activity_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
             app.activity_index = len - 1;
         }
         KeyCode::Enter | KeyCode::Char(' ') => {
-            if let Some(entry) = app.activity.get(app.activity_index).cloned() {
+            if let Some(entry) = app.selected_activity().cloned() {
                 open_activity_entry(app, api, &entry).await;
             }
         }
@@ -414,72 +1208,917 @@ async fn handle_activity_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
 }
 
 async fn open_activity_entry(app: &mut App, api: &ApiClient, entry: &model::ActivityEntry) {
+    let id = entry
+        .id
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_default();
     match entry.entry_type.as_str() {
-        "task" => {
-            if let (Some(col), Some(filename)) = (&entry.column, &entry.filename) {
-                let task_id = entry
-                    .id
-                    .as_ref()
-                    .map(|v| v.to_string())
-                    .unwrap_or_default();
-                if let Ok(task) = api.get_task(col, filename).await {
-                    let comments = if !task_id.is_empty() {
-                        api.get_comments(&task_id).await.unwrap_or_default()
-                    } else {
-                        vec![]
+        "task" => match activity_task_location(app, entry) {
+            Some((col, filename)) => open_task(app, api, &col, &filename, &id).await,
+            None => app.set_status(format!("Cannot find task {}", entry_label(entry))),
+        },
+        "prompt" => {
+            if let Some(dir_name) = &entry.dir_name {
+                open_resource(app, api, ResourceType::Prompt, dir_name).await;
+            }
+        }
+        "document" => {
+            if let Some(dir_name) = &entry.dir_name {
+                open_resource(app, api, ResourceType::Document, dir_name).await;
+            }
+        }
+        "comment" => {
+            let Some((col, filename)) = activity_task_location(app, entry) else {
+                app.set_status(format!("Cannot find task {}", entry_label(entry)));
+                return;
+            };
+            open_task(app, api, &col, &filename, &id).await;
+            let target = entry.comment.as_deref().and_then(|comment| {
+                ui::task_detail::comment_scroll_target(app, comment, screen_rect())
+            });
+            if let Some(target) = target {
+                set_overlay_scroll(app, target);
+            }
+        }
+        other => app.set_status(format!("Cannot open {other} entries")),
+    }
+}
+
+/// Where the task behind a task or comment entry lives. Some servers leave
+/// out the column, or send only the task id; both are resolved against the
+/// loaded board.
+fn activity_task_location(app: &App, entry: &model::ActivityEntry) -> Option<(String, String)> {
+    match (&entry.column, &entry.filename) {
+        (Some(col), Some(filename)) => Some((col.clone(), filename.clone())),
+        (None, Some(filename)) => app
+            .task_column(filename)
+            .map(|col| (col.to_string(), filename.clone())),
+        _ => app.locate_task_by_id(entry.id.as_ref()?),
+    }
+}
+
+/// `"Title"` for status messages, falling back to `#id`.
+fn entry_label(entry: &model::ActivityEntry) -> String {
+    if !entry.title.is_empty() {
+        return format!("{:?}", entry.title);
+    }
+    match ui::board::short_id(&entry.id) {
+        id if id.is_empty() => "without an id".to_string(),
+        id => format!("#{id}"),
+    }
+}
+
+async fn open_task(app: &mut App, api: &ApiClient, column: &str, filename: &str, task_id: &str) {
+    if app.offline_since.is_some() {
+        // Snapshots carry task bodies but not comments
+        match app.find_task(column, filename).cloned() {
+            Some(task) => {
+                remember_task(app, &task);
+                app.overlay = Some(Overlay::TaskDetail {
+                    task,
+                    comments: vec![],
+                    scroll: 0,
+                    search: None,
+                    visual: None,
+                    comments_collapsed: false,
+                    gone: false,
+                    sections: BTreeSet::new(),
+                    links: None,
+                });
+                app.restore_scroll();
+            }
+            None => app.set_status("Task is not in the snapshot"),
+        }
+        return;
+    }
+    let task = match api.get_task(column, filename).await {
+        Ok(task) => task,
+        Err(e) => {
+            report_fetch_error(app, "Task", &e);
+            return;
+        }
+    };
+    let comments = if !task_id.is_empty() {
+        api.get_comments(task_id).await.unwrap_or_else(|e| {
+            report_fetch_error(app, "Comments", &e);
+            vec![]
+        })
+    } else {
+        vec![]
+    };
+    remember_task(app, &task);
+    app.overlay = Some(Overlay::TaskDetail {
+        task,
+        comments,
+        scroll: 0,
+        search: None,
+        visual: None,
+        comments_collapsed: false,
+        gone: false,
+        sections: BTreeSet::new(),
+        links: None,
+    });
+    app.restore_scroll();
+}
+
+/// Record an opened task in the recently viewed history.
+fn remember_task(app: &mut App, task: &model::Task) {
+    let item = QuickOpenItem {
+        title: task.display_title().to_string(),
+        target: QuickOpenTarget::Task {
+            column: task.column.clone(),
+            filename: task.filename.clone(),
+            id: task.meta.id.as_ref().map(|v| v.to_string()).unwrap_or_default(),
+        },
+    };
+    recent::push(&mut app.recent, item);
+}
+
+async fn open_resource(app: &mut App, api: &ApiClient, rtype: ResourceType, dir_name: &str) {
+    let (resource, revisions) = if app.offline_since.is_some() {
+        // Snapshots carry the current revision only
+        match app.find_resource(rtype, dir_name).cloned() {
+            Some(resource) => (Ok(resource), Ok(vec![])),
+            None => {
+                app.set_status("Item is not in the snapshot");
+                return;
+            }
+        }
+    } else {
+        match rtype {
+            ResourceType::Prompt => (
+                api.get_prompt(dir_name).await,
+                api.list_prompt_revisions(dir_name).await,
+            ),
+            ResourceType::Document => (
+                api.get_document(dir_name).await,
+                api.list_document_revisions(dir_name).await,
+            ),
+        }
+    };
+    let resource = match resource {
+        Ok(resource) => resource,
+        Err(e) => {
+            let label = match rtype {
+                ResourceType::Prompt => "Prompt",
+                ResourceType::Document => "Document",
+            };
+            report_fetch_error(app, label, &e);
+            return;
+        }
+    };
+    let revisions = revisions.unwrap_or_else(|e| {
+        report_fetch_error(app, "Revisions", &e);
+        vec![]
+    });
+    let item = QuickOpenItem {
+        title: resource.display_title().to_string(),
+        target: QuickOpenTarget::Resource {
+            resource_type: rtype,
+            dir_name: resource.dir_name.clone(),
+        },
+    };
+    recent::push(&mut app.recent, item);
+    app.overlay = Some(Overlay::ResourceDetail {
+        resource,
+        revisions,
+        current_rev: None,
+        scroll: 0,
+        resource_type: rtype,
+        toc: None,
+        search: None,
+        visual: None,
+        gone: false,
+        sections: BTreeSet::new(),
+        links: None,
+    });
+    app.restore_scroll();
+}
+
+/// Re-fetch the open task and its comments, or the open resource and its
+/// revisions, keeping the scroll position and the rest of the app as is.
+async fn refresh_overlay(app: &mut App, api: &ApiClient) {
+    match &app.overlay {
+        Some(Overlay::TaskDetail { task, .. }) => {
+            let (column, filename) = (task.column.clone(), task.filename.clone());
+            let fresh = match api.get_task(&column, &filename).await {
+                Ok(fresh) => fresh,
+                Err(e) => {
+                    if api::is_not_found(&e) {
+                        if let Some(Overlay::TaskDetail { gone, .. }) = &mut app.overlay {
+                            *gone = true;
+                        }
+                    }
+                    report_fetch_error(app, "Task", &e);
+                    return;
+                }
+            };
+            let task_id = fresh.meta.id.as_ref().map(|v| v.to_string()).unwrap_or_default();
+            let fresh_comments = if task_id.is_empty() {
+                Ok(vec![])
+            } else {
+                api.get_comments(&task_id).await
+            };
+            if let Some(Overlay::TaskDetail {
+                task,
+                comments,
+                visual,
+                gone,
+                ..
+            }) = &mut app.overlay
+            {
+                if fresh.body != task.body {
+                    *visual = None;
+                }
+                *task = fresh;
+                *gone = false;
+                if let Ok(fresh_comments) = &fresh_comments {
+                    *comments = fresh_comments.clone();
+                }
+            }
+            match fresh_comments {
+                Ok(_) => app.set_status("Refreshed"),
+                Err(e) => report_fetch_error(app, "Comments", &e),
+            }
+        }
+        Some(Overlay::ResourceDetail {
+            resource,
+            resource_type,
+            ..
+        }) => {
+            let (rtype, dir_name) = (*resource_type, resource.dir_name.clone());
+            let (fresh, fresh_revisions) = match rtype {
+                ResourceType::Prompt => (
+                    api.get_prompt(&dir_name).await,
+                    api.list_prompt_revisions(&dir_name).await,
+                ),
+                ResourceType::Document => (
+                    api.get_document(&dir_name).await,
+                    api.list_document_revisions(&dir_name).await,
+                ),
+            };
+            let fresh = match fresh {
+                Ok(fresh) => fresh,
+                Err(e) => {
+                    if api::is_not_found(&e) {
+                        if let Some(Overlay::ResourceDetail { gone, .. }) = &mut app.overlay {
+                            *gone = true;
+                        }
+                    }
+                    let label = match rtype {
+                        ResourceType::Prompt => "Prompt",
+                        ResourceType::Document => "Document",
                     };
-                    app.overlay = Some(Overlay::TaskDetail {
-                        task,
-                        comments,
-                        scroll: 0,
-                    });
+                    report_fetch_error(app, label, &e);
+                    return;
+                }
+            };
+            if let Some(Overlay::ResourceDetail {
+                resource,
+                revisions,
+                current_rev,
+                visual,
+                gone,
+                ..
+            }) = &mut app.overlay
+            {
+                if current_rev.is_none() && fresh.body != resource.body {
+                    *visual = None;
+                }
+                *resource = fresh;
+                *gone = false;
+                if let Ok(fresh_revisions) = &fresh_revisions {
+                    *revisions = fresh_revisions.clone();
+                    // Revisions are oldest first, so an index stays on its revision
+                    if current_rev.is_some_and(|idx| idx >= revisions.len()) {
+                        *current_rev = None;
+                    }
                 }
             }
+            match fresh_revisions {
+                Ok(_) => app.set_status("Refreshed"),
+                Err(e) => report_fetch_error(app, "Revisions", &e),
+            }
         }
-        "prompt" => {
-            if let Some(dir_name) = &entry.dir_name {
-                if let Ok(resource) = api.get_prompt(dir_name).await {
-                    let revisions = api
-                        .list_prompt_revisions(dir_name)
-                        .await
-                        .unwrap_or_default();
-                    app.overlay = Some(Overlay::ResourceDetail {
-                        resource,
-                        revisions,
-                        current_rev: None,
-                        scroll: 0,
-                        resource_type: ResourceType::Prompt,
+        _ => {}
+    }
+}
+
+/// In offline snapshot mode, refuse an action that needs the server.
+fn offline_blocked(app: &mut App) -> bool {
+    if app.offline_since.is_some() {
+        app.set_status("Not available offline (snapshot is read-only)");
+        return true;
+    }
+    false
+}
+
+/// With `--read-only`, refuse a key that would write to the server.
+fn read_only_blocked(app: &mut App) -> bool {
+    if app.read_only {
+        app.set_status("Read-only mode");
+        return true;
+    }
+    false
+}
+
+/// Tell the user why an item could not be opened.
+fn report_fetch_error(app: &mut App, what: &str, err: &anyhow::Error) {
+    if api::is_not_found(err) {
+        app.set_status(format!("{what} no longer exists"));
+    } else {
+        app.set_status(format!("Failed to load {}: {err}", what.to_lowercase()));
+    }
+}
+
+/// Copy a web UI link for the open overlay's item, or the selected one.
+fn copy_link(app: &mut App) {
+    let link = match &app.overlay {
+        Some(Overlay::TaskDetail { task, .. }) => {
+            Some(links::task_link(&app.task_link_format, &app.server_url, task))
+        }
+        Some(Overlay::ResourceDetail {
+            resource,
+            resource_type,
+            ..
+        }) => Some(links::resource_link(
+            &app.resource_link_format,
+            &app.server_url,
+            *resource_type,
+            resource,
+        )),
+        Some(_) => None,
+        None => match app.view {
+            View::Board => app
+                .selected_task()
+                .map(|t| links::task_link(&app.task_link_format, &app.server_url, t)),
+            View::Prompts | View::Documents => {
+                let rtype = if app.view == View::Prompts {
+                    ResourceType::Prompt
+                } else {
+                    ResourceType::Document
+                };
+                app.selected_resource(rtype).map(|r| {
+                    links::resource_link(&app.resource_link_format, &app.server_url, rtype, r)
+                })
+            }
+            View::Activity => None,
+        },
+    };
+
+    let Some(link) = link else {
+        app.set_status("Nothing to copy here");
+        return;
+    };
+    match clipboard::copy(&link) {
+        Ok(()) => app.set_status(format!("Copied {link}")),
+        Err(e) => app.set_status(format!("Copy failed: {e}")),
+    }
+}
+
+/// Copy the most recent API request as a curl command.
+fn copy_last_request(app: &mut App, api: &ApiClient) {
+    let Some(request) = api.last_request() else {
+        app.set_status("No API request made yet");
+        return;
+    };
+    match clipboard::copy(&request.to_curl()) {
+        Ok(()) => app.set_status(format!("Copied curl for {} {}", request.method, request.url)),
+        Err(e) => app.set_status(format!("Copy failed: {e}")),
+    }
+}
+
+/// Copy the full id of the open task, or the selected one on the board.
+fn copy_task_id(app: &mut App) {
+    let task = match &app.overlay {
+        Some(Overlay::TaskDetail { task, .. }) => Some(task),
+        Some(_) => None,
+        None => app.selected_task(),
+    };
+    let id = task
+        .and_then(|t| t.meta.id.as_ref())
+        .map(export::json_scalar)
+        .unwrap_or_default();
+    if id.is_empty() {
+        app.set_status("This task has no id");
+        return;
+    }
+    match clipboard::copy(&id) {
+        Ok(()) => app.set_status(format!("Copied id {id}")),
+        Err(e) => app.set_status(format!("Copy failed: {e}")),
+    }
+}
+
+/// Copy the open task's body as GitHub-flavored markdown, optionally under
+/// its title as a heading.
+fn copy_checklist(app: &mut App, with_title: bool) {
+    let Some(Overlay::TaskDetail { task, .. }) = &app.overlay else {
+        return;
+    };
+    let markdown = export::task_to_checklist(task, with_title);
+    match clipboard::copy(&markdown) {
+        Ok(()) if with_title => app.set_status("Copied task as markdown with title"),
+        Ok(()) => app.set_status("Copied task as markdown"),
+        Err(e) => app.set_status(format!("Copy failed: {e}")),
+    }
+}
+
+/// Body of the open prompt, at the revision being viewed.
+fn open_prompt_body(app: &App) -> Option<String> {
+    match &app.overlay {
+        Some(Overlay::ResourceDetail {
+            resource_type: ResourceType::Prompt,
+            ..
+        }) => app.overlay_body().map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Copy the open prompt's body, naming the placeholders left to fill in.
+fn copy_prompt(app: &mut App) {
+    let Some(body) = open_prompt_body(app) else {
+        return;
+    };
+    let names = prompt::placeholders(&body);
+    match clipboard::copy(&body) {
+        Ok(()) if names.is_empty() => app.set_status("Copied prompt"),
+        Ok(()) => app.set_status(format!(
+            "Copied prompt; fill in {}",
+            names.iter().map(|n| format!("{{{{{n}}}}}")).collect::<Vec<_>>().join(", ")
+        )),
+        Err(e) => app.set_status(format!("Copy failed: {e}")),
+    }
+}
+
+/// Queue the open prompt for --prompt-command; the main loop starts it and
+/// its output comes back as a `PollMessage::PromptOutput`.
+fn run_prompt_command(app: &mut App) {
+    let Some(body) = open_prompt_body(app) else {
+        return;
+    };
+    let Some(command) = app.prompt_command.clone() else {
+        app.set_status("Set --prompt-command to run prompts");
+        return;
+    };
+    if app.prompt_running {
+        app.set_status("Prompt command still running…");
+        return;
+    }
+    app.prompt_running = true;
+    app.set_status(format!("Running {command}…"));
+    app.prompt_run = Some((command, body));
+}
+
+/// Run a prompt command off the UI loop, killing it after
+/// PROMPT_COMMAND_TIMEOUT, and send back what it printed.
+fn spawn_prompt_command(command: String, body: String, tx: mpsc::UnboundedSender<PollMessage>) {
+    tokio::spawn(async move {
+        let result = match tokio::time::timeout(PROMPT_COMMAND_TIMEOUT, prompt::run_command(&command, &body)).await {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(e)) => Err(format!("{e:#}")),
+            Err(_) => Err(format!("timed out after {}s", PROMPT_COMMAND_TIMEOUT.as_secs())),
+        };
+        let _ = tx.send(PollMessage::PromptOutput { command, result });
+    });
+}
+
+fn toggle_debug_overlay(app: &mut App) {
+    app.overlay = match app.overlay {
+        Some(Overlay::Debug) => None,
+        _ => Some(Overlay::Debug),
+    };
+}
+
+fn open_quick_open(app: &mut App) {
+    let items = quick_open::build_index(app);
+    let matches = (0..items.len()).collect();
+    app.overlay = Some(Overlay::QuickOpen {
+        title: "Quick open",
+        query: TextInput::default(),
+        items,
+        matches,
+        selected: 0,
+    });
+}
+
+fn open_recent(app: &mut App) {
+    if app.recent.is_empty() {
+        app.set_status("Nothing opened yet");
+        return;
+    }
+    let items = app.recent.clone();
+    let matches = (0..items.len()).collect();
+    app.overlay = Some(Overlay::QuickOpen {
+        title: "Recent",
+        query: TextInput::default(),
+        items,
+        matches,
+        selected: 0,
+    });
+}
+
+async fn handle_quick_open_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
+    let Some(Overlay::QuickOpen {
+        query,
+        items,
+        matches,
+        selected,
+        ..
+    }) = &mut app.overlay
+    else {
+        return;
+    };
+
+    match key.code {
+        KeyCode::Down | KeyCode::Tab => {
+            if *selected + 1 < matches.len() {
+                *selected += 1;
+            }
+            return;
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            *selected = selected.saturating_sub(1);
+            return;
+        }
+        _ => {}
+    }
+
+    match query.handle_key(key) {
+        InputOutcome::Submit => {
+            let Some(target) = matches.get(*selected).map(|&i| items[i].target.clone()) else {
+                return;
+            };
+            app.overlay = None;
+            match target {
+                QuickOpenTarget::Task {
+                    column,
+                    filename,
+                    id,
+                } => {
+                    // Recent entries may predate a move; follow the task by filename
+                    let column = app.task_column(&filename).map_or(column, str::to_string);
+                    app.view = View::Board;
+                    app.select_task(&column, &filename);
+                    open_task(app, api, &column, &filename, &id).await;
+                }
+                QuickOpenTarget::Resource {
+                    resource_type,
+                    dir_name,
+                } => open_resource(app, api, resource_type, &dir_name).await,
+            }
+        }
+        InputOutcome::Cancel => app.overlay = None,
+        InputOutcome::Edited => {
+            *matches = quick_open::rank(items, &query.value);
+            // Then items that only mention the query in their body
+            for hit in app.search_index.query(&query.value) {
+                if hit.field != HitField::Body {
+                    continue;
+                }
+                let index = items.iter().position(|item| item.target == hit.target);
+                if let Some(index) = index.filter(|i| !matches.contains(i)) {
+                    matches.push(index);
+                }
+            }
+            *selected = 0;
+        }
+        InputOutcome::Ignored => {}
+    }
+}
+
+async fn handle_assignee_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
+    let Some(Overlay::AssigneePicker {
+        column,
+        filename,
+        query,
+        known,
+        options,
+        selected,
+    }) = &mut app.overlay
+    else {
+        return;
+    };
+
+    match key.code {
+        KeyCode::Down | KeyCode::Tab => {
+            if *selected + 1 < options.len() {
+                *selected += 1;
+            }
+            return;
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            *selected = selected.saturating_sub(1);
+            return;
+        }
+        _ => {}
+    }
+
+    match query.handle_key(key) {
+        InputOutcome::Submit => {
+            let assignee = options.get(*selected).cloned().unwrap_or_default();
+            let (column, filename) = (column.clone(), filename.clone());
+            app.overlay = None;
+            app.set_task_assignee(&column, &filename, &assignee);
+            match api.set_assignee(&column, &filename, &assignee).await {
+                Ok(task) => {
+                    app.apply_task_update(task);
+                    app.set_status(if assignee.is_empty() {
+                        "Unassigned".to_string()
+                    } else {
+                        format!("Assigned to @{assignee}")
                     });
                 }
+                Err(e) => {
+                    app.set_status(format!("Assign failed: {e}"));
+                    refresh_current_view(app, api).await;
+                }
             }
         }
-        "document" => {
-            if let Some(dir_name) = &entry.dir_name {
-                if let Ok(resource) = api.get_document(dir_name).await {
-                    let revisions = api
-                        .list_document_revisions(dir_name)
-                        .await
-                        .unwrap_or_default();
-                    app.overlay = Some(Overlay::ResourceDetail {
-                        resource,
-                        revisions,
-                        current_rev: None,
-                        scroll: 0,
-                        resource_type: ResourceType::Document,
+        InputOutcome::Cancel => app.overlay = None,
+        InputOutcome::Edited => {
+            *options = assignee_options(known, &query.value);
+            *selected = 0;
+        }
+        InputOutcome::Ignored => {}
+    }
+}
+
+/// Ask before running `action`, unless confirmations are off.
+async fn confirm_or_run(app: &mut App, api: &ApiClient, message: String, action: ConfirmAction) {
+    if app.confirm {
+        app.overlay = Some(Overlay::Confirm { message, action });
+    } else {
+        run_confirmed(app, api, action).await;
+    }
+}
+
+async fn run_confirmed(app: &mut App, api: &ApiClient, action: ConfirmAction) {
+    match action {
+        ConfirmAction::MoveMarked(column) => bulk_move(app, api, column).await,
+        ConfirmAction::DeleteTask { column, filename } => {
+            delete_task(app, api, &column, &filename).await
+        }
+    }
+}
+
+/// Delete a task, taking it off the board first and putting it back if the
+/// server refuses.
+async fn delete_task(app: &mut App, api: &ApiClient, column: &str, filename: &str) {
+    let Some((row, task)) = app.remove_task(column, filename) else {
+        return;
+    };
+    match api.delete_task(column, filename).await {
+        Ok(()) => app.set_status(format!("Deleted \"{}\"", task.display_title())),
+        Err(e) => {
+            app.restore_task(row, task);
+            app.set_status(format!("Delete failed: {e}"));
+        }
+    }
+}
+
+async fn handle_confirm_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
+    let Some(Overlay::Confirm { action, .. }) = &app.overlay else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            let action = action.clone();
+            app.overlay = None;
+            run_confirmed(app, api, action).await;
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+            app.overlay = None;
+            app.set_status("Cancelled");
+        }
+        _ => {}
+    }
+}
+
+fn handle_column_picker_key(app: &mut App, key: KeyEvent) {
+    let Some(Overlay::ColumnPicker { columns, selected }) = &mut app.overlay else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => {
+            *selected = (*selected + 1).min(columns.len().saturating_sub(1));
+        }
+        KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => {
+            *selected = selected.saturating_sub(1);
+        }
+        KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => {
+            let col = columns.get(*selected).copied();
+            app.overlay = None;
+            match col {
+                Some(col) => app.select_column(col),
+                None => app.clamp_indices(),
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') | KeyCode::Left => {
+            app.overlay = None;
+        }
+        _ => {}
+    }
+}
+
+async fn handle_progress_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
+    let Some(Overlay::Progress { rows, selected }) = &mut app.overlay else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => {
+            *selected = (*selected + 1).min(rows.len().saturating_sub(1));
+        }
+        KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => {
+            *selected = selected.saturating_sub(1);
+        }
+        KeyCode::Char('g') | KeyCode::Home => *selected = 0,
+        KeyCode::Char('G') | KeyCode::End => *selected = rows.len().saturating_sub(1),
+        KeyCode::Enter => {
+            let Some(row) = rows.get(*selected).cloned() else {
+                return;
+            };
+            app.overlay = None;
+            let id = app
+                .find_task(&row.column, &row.filename)
+                .and_then(|t| t.meta.id.as_ref())
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            app.select_task(&row.column, &row.filename);
+            open_task(app, api, &row.column, &row.filename, &id).await;
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.overlay = None;
+        }
+        _ => {}
+    }
+}
+
+async fn handle_scope_editor_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
+    let Some(Overlay::ScopeEditor {
+        column,
+        filename,
+        query,
+        known,
+        chosen,
+        options,
+        selected,
+    }) = &mut app.overlay
+    else {
+        return;
+    };
+
+    match key.code {
+        KeyCode::Down | KeyCode::Tab => {
+            if *selected + 1 < options.len() {
+                *selected += 1;
+            }
+            return;
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            *selected = selected.saturating_sub(1);
+            return;
+        }
+        // Scopes never contain spaces, so Space is free to toggle
+        KeyCode::Char(' ') => {
+            if let Some(scope) = options.get(*selected) {
+                toggle_scope(chosen, scope);
+                if !query.value.is_empty() {
+                    *query = TextInput::default();
+                    *options = scope_options(known, chosen, "");
+                    *selected = 0;
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    match query.handle_key(key) {
+        InputOutcome::Submit => {
+            // A typed scope that wasn't toggled yet is added on save
+            if !query.value.trim().is_empty() {
+                if let Some(scope) = options.get(*selected) {
+                    if !chosen.iter().any(|s| s.eq_ignore_ascii_case(scope)) {
+                        chosen.push(scope.clone());
+                    }
+                }
+            }
+            let (column, filename, scopes) = (column.clone(), filename.clone(), chosen.clone());
+            app.overlay = None;
+            match api.set_scopes(&column, &filename, &scopes).await {
+                Ok(task) => {
+                    app.apply_task_update(task);
+                    app.set_status(if scopes.is_empty() {
+                        "Cleared scopes".to_string()
+                    } else {
+                        format!("Scopes: {}", scopes.join(", "))
                     });
                 }
+                Err(e) => app.set_status(format!("Saving scopes failed: {e}")),
             }
         }
+        InputOutcome::Cancel => app.overlay = None,
+        InputOutcome::Edited => {
+            *options = scope_options(known, chosen, &query.value);
+            *selected = 0;
+        }
+        InputOutcome::Ignored => {}
+    }
+}
+
+async fn handle_overlay_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
+    // Forms capture text input instead of the scrolling keys below
+    match app.overlay {
+        Some(Overlay::NewTask { .. }) => return handle_new_task_key(app, api, key).await,
+        Some(Overlay::QuickOpen { .. }) => return handle_quick_open_key(app, api, key).await,
+        Some(Overlay::AssigneePicker { .. }) => return handle_assignee_key(app, api, key).await,
+        Some(Overlay::ScopeEditor { .. }) => return handle_scope_editor_key(app, api, key).await,
+        Some(Overlay::ColumnPicker { .. }) => return handle_column_picker_key(app, key),
+        Some(Overlay::Progress { .. }) => return handle_progress_key(app, api, key).await,
+        Some(Overlay::Confirm { .. }) => return handle_confirm_key(app, api, key).await,
         _ => {}
     }
-}
+    if matches!(app.overlay, Some(Overlay::ResourceDetail { toc: Some(_), .. }))
+        && handle_toc_key(app, key)
+    {
+        return;
+    }
+    if overlay_visual(app).is_some() && handle_visual_key(app, key) {
+        return;
+    }
+    if handle_link_picker_key(app, key) {
+        return;
+    }
 
-async fn handle_overlay_key(app: &mut App, _api: &ApiClient, key: KeyEvent) {
     match key.code {
-        KeyCode::Esc => {
+        KeyCode::Esc if app.overlay_search().is_some() => {
+            app.set_overlay_search(None);
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.remember_scroll();
             app.overlay = None;
+            app.focus_mode = false;
         }
-        KeyCode::Char('q') => {
+        KeyCode::Char('L')
+            if matches!(
+                app.overlay,
+                Some(Overlay::TaskDetail { .. }) | Some(Overlay::ResourceDetail { .. })
+            ) =>
+        {
+            app.line_numbers = !app.line_numbers;
+        }
+        KeyCode::Char('m')
+            if matches!(
+                app.overlay,
+                Some(Overlay::TaskDetail { .. }) | Some(Overlay::ResourceDetail { .. })
+            ) =>
+        {
+            app.metadata_table = !app.metadata_table;
+        }
+        KeyCode::Enter
+            if matches!(
+                app.overlay,
+                Some(Overlay::TaskDetail { .. }) | Some(Overlay::ResourceDetail { .. })
+            ) =>
+        {
+            toggle_section(app);
+        }
+        KeyCode::Char('R')
+            if matches!(
+                app.overlay,
+                Some(Overlay::TaskDetail { .. }) | Some(Overlay::ResourceDetail { .. })
+            ) =>
+        {
+            toggle_raw_markdown(app);
+        }
+        KeyCode::Char('r')
+            if matches!(
+                app.overlay,
+                Some(Overlay::TaskDetail { .. }) | Some(Overlay::ResourceDetail { .. })
+            ) && !offline_blocked(app) =>
+        {
+            refresh_overlay(app, api).await;
+        }
+        KeyCode::Char('z') if matches!(app.overlay, Some(Overlay::TaskDetail { .. })) => {
+            app.focus_mode = !app.focus_mode;
+        }
+        KeyCode::Char('C') => {
+            if let Some(Overlay::TaskDetail {
+                comments,
+                comments_collapsed,
+                ..
+            }) = &mut app.overlay
+            {
+                if !comments.is_empty() {
+                    *comments_collapsed = !*comments_collapsed;
+                }
+            }
+        }
+        KeyCode::Char('V') => {
+            start_visual(app);
+        }
+        KeyCode::Char('~') if matches!(app.overlay, Some(Overlay::Debug)) => {
             app.overlay = None;
         }
         KeyCode::Char('j') | KeyCode::Down => {
@@ -509,8 +2148,684 @@ async fn handle_overlay_key(app: &mut App, _api: &ApiClient, key: KeyEvent) {
         KeyCode::Char(']') => {
             navigate_revision(app, 1);
         }
+        KeyCode::Char('o') => {
+            open_overlay_image(app);
+        }
+        KeyCode::Char('O')
+            if matches!(
+                app.overlay,
+                Some(Overlay::TaskDetail { .. }) | Some(Overlay::ResourceDetail { .. })
+            ) =>
+        {
+            if hyperlink::picker_links(app).is_empty() {
+                app.set_status("No links in this item");
+            } else {
+                set_link_picker(app, Some(0));
+            }
+        }
+        KeyCode::Char('y') => {
+            copy_link(app);
+        }
+        KeyCode::Char('c') => copy_checklist(app, false),
+        KeyCode::Char('p') => copy_prompt(app),
+        KeyCode::Char('P') => run_prompt_command(app),
+        KeyCode::Char('i') => copy_task_id(app),
+        KeyCode::Char('#') => copy_checklist(app, true),
+        KeyCode::Char('/') => {
+            if matches!(
+                app.overlay,
+                Some(Overlay::TaskDetail { .. }) | Some(Overlay::ResourceDetail { .. })
+            ) {
+                app.input = Some(InputPrompt {
+                    label: "Find".to_string(),
+                    input: TextInput::new(app.overlay_search().unwrap_or_default()),
+                    action: InputAction::OverlaySearch,
+                });
+            }
+        }
+        KeyCode::Char('n') if app.overlay_search().is_some() => {
+            jump_to_match(app, MatchJump::Next);
+        }
+        KeyCode::Char('N') if app.overlay_search().is_some() => {
+            jump_to_match(app, MatchJump::Prev);
+        }
+        KeyCode::Char('T') => {
+            if let Some(Overlay::ResourceDetail { toc, .. }) = &mut app.overlay {
+                *toc = Some(0);
+            }
+        }
+        KeyCode::Char('e') => {
+            if let Some(Overlay::TaskDetail { task, .. }) = &app.overlay {
+                app.input = Some(InputPrompt {
+                    label: "Export task to".to_string(),
+                    input: TextInput::new(task.filename.clone()),
+                    action: InputAction::ExportTask,
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+fn overlay_visual(app: &App) -> Option<(usize, usize)> {
+    match &app.overlay {
+        Some(Overlay::TaskDetail { visual, .. }) | Some(Overlay::ResourceDetail { visual, .. }) => {
+            *visual
+        }
+        _ => None,
+    }
+}
+
+fn set_overlay_visual(app: &mut App, selection: Option<(usize, usize)>) {
+    if let Some(Overlay::TaskDetail { visual, .. }) | Some(Overlay::ResourceDetail { visual, .. }) =
+        &mut app.overlay
+    {
+        *visual = selection;
+    }
+}
+
+/// Enter in a detail overlay: expand or collapse the first `<details>`
+/// section whose header is on screen.
+fn toggle_section(app: &mut App) {
+    let Some(content) = ui::overlay_content(app, screen_rect()) else {
+        return;
+    };
+    let Some(body) = app.overlay_body() else {
+        return;
+    };
+    let rendered = app
+        .markdown_cache
+        .render(body, app.raw_markdown, app.overlay_sections());
+    let scroll = overlay_scroll(app);
+    let top = content.lines.line_at_row(scroll, content.width);
+    let bottom = content.lines.line_at_row(scroll + content.height as usize, content.width);
+    let index = rendered.sections.iter().find_map(|&(index, source)| {
+        let line = content.map.line_of(source)?;
+        (top..bottom).contains(&line).then_some(index)
+    });
+    let Some(index) = index else {
+        app.set_status("No collapsible section on screen");
+        return;
+    };
+    if let Some(Overlay::TaskDetail { sections, .. }) | Some(Overlay::ResourceDetail { sections, .. }) =
+        &mut app.overlay
+    {
+        if !sections.remove(&index) {
+            sections.insert(index);
+        }
+    }
+}
+
+/// `R` in a detail overlay: switch between rendered and raw markdown, keeping
+/// the same fraction of the content scrolled past.
+fn toggle_raw_markdown(app: &mut App) {
+    let total_rows = |app: &App| {
+        ui::overlay_content(app, screen_rect())
+            .map(|c| c.lines.row_of(c.lines.len(), c.width))
+            .unwrap_or(0)
+    };
+    let before = total_rows(app);
+    let scroll = overlay_scroll(app);
+    app.raw_markdown = !app.raw_markdown;
+    if let Some(scroll) = (scroll * total_rows(app)).checked_div(before) {
+        set_overlay_scroll(app, scroll);
+    }
+    app.set_status(if app.raw_markdown {
+        "Showing raw markdown"
+    } else {
+        "Showing rendered markdown"
+    });
+}
+
+fn board_has_focus(app: &App) -> bool {
+    app.view == View::Board && app.overlay.is_none() && app.input.is_none() && app.focus == Focus::Content
+}
+
+/// Scroll board column `col` by `delta` cards, leaving the selection alone.
+fn scroll_board_column(app: &mut App, col: usize, delta: isize) {
+    let area = ui::content_area(screen_rect());
+    if let Some(start) = ui::board::scrolled_start(app, col, area, delta) {
+        app.set_board_scroll(col, start);
+    }
+}
+
+/// `--mouse`: the wheel scrolls overlays, and on the board the column under
+/// the pointer.
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    let delta = match mouse.kind {
+        MouseEventKind::ScrollDown => 1,
+        MouseEventKind::ScrollUp => -1,
+        _ => return,
+    };
+    app.mark_dirty();
+    if app.overlay.is_some() {
+        scroll_overlay(app, delta * 3);
+    } else if app.view == View::Board && app.input.is_none() {
+        let area = ui::content_area(screen_rect());
+        if let Some(col) = ui::board::column_at(app, area, mouse.column, mouse.row) {
+            scroll_board_column(app, col, delta as isize);
+        }
+    }
+}
+
+fn screen_rect() -> ratatui::layout::Rect {
+    terminal::size()
+        .map(|(w, h)| ratatui::layout::Rect::new(0, 0, w, h))
+        .unwrap_or_default()
+}
+
+/// `V` in a detail overlay: start a line selection on the first body line on screen.
+fn start_visual(app: &mut App) {
+    let Some(content) = ui::overlay_content(app, screen_rect()) else {
+        return;
+    };
+    let line = content.lines.line_at_row(overlay_scroll(app), content.width);
+    match content.map.source_at_or_after(line) {
+        Some(source) => set_overlay_visual(app, Some((source, source))),
+        None => app.set_status("Nothing to select"),
+    }
+}
+
+/// Keys while a line selection is active. Returns false for keys it leaves
+/// to the regular overlay handling.
+fn handle_visual_key(app: &mut App, key: KeyEvent) -> bool {
+    let Some((anchor, cursor)) = overlay_visual(app) else {
+        return false;
+    };
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => move_visual_cursor(app, anchor, cursor, 1),
+        KeyCode::Char('k') | KeyCode::Up => move_visual_cursor(app, anchor, cursor, -1),
+        KeyCode::Char('y') => {
+            yank_lines(app, anchor.min(cursor), anchor.max(cursor));
+            set_overlay_visual(app, None);
+        }
+        KeyCode::Char('V') | KeyCode::Esc => set_overlay_visual(app, None),
+        _ => return false,
+    }
+    true
+}
+
+/// Extend the selection by `delta` rendered body lines, scrolling to keep
+/// the cursor line on screen.
+fn move_visual_cursor(app: &mut App, anchor: usize, cursor: usize, delta: isize) {
+    let Some(content) = ui::overlay_content(app, screen_rect()) else {
+        return;
+    };
+    let cursor = content.map.step(cursor, delta);
+    set_overlay_visual(app, Some((anchor, cursor)));
+
+    let Some(line) = content.map.line_of(cursor) else {
+        return;
+    };
+    let row = content.lines.row_of(line, content.width);
+    let scroll = overlay_scroll(app);
+    let height = content.height.max(1) as usize;
+    if row < scroll {
+        set_overlay_scroll(app, row);
+    } else if row >= scroll + height {
+        set_overlay_scroll(app, row + 1 - height);
+    }
+}
+
+/// Copy source lines `lo..=hi` of the overlay body to the clipboard.
+fn yank_lines(app: &mut App, lo: usize, hi: usize) {
+    let Some(body) = app.overlay_body() else {
+        return;
+    };
+    let selected: Vec<&str> = body.lines().skip(lo).take(hi + 1 - lo).collect();
+    let count = selected.len();
+    match clipboard::copy(&selected.join("\n")) {
+        Ok(()) if count == 1 => app.set_status("Copied 1 line"),
+        Ok(()) => app.set_status(format!("Copied {count} lines")),
+        Err(e) => app.set_status(format!("Copy failed: {e}")),
+    }
+}
+
+/// Keys for the contents panel of the resource overlay. Returns false for keys
+/// it leaves to the regular overlay handling (page scrolling, revisions, ...).
+fn handle_toc_key(app: &mut App, key: KeyEvent) -> bool {
+    let count = ui::resources::detail_heading_count(app);
+    let Some(Overlay::ResourceDetail { toc: Some(selected), .. }) = &mut app.overlay else {
+        return false;
+    };
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            if *selected + 1 < count {
+                *selected += 1;
+            }
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            *selected = selected.saturating_sub(1);
+        }
+        KeyCode::Enter => {
+            let heading = *selected;
+            let screen = screen_rect();
+            if let Some(target) = ui::resources::heading_scroll_target(app, heading, screen) {
+                set_overlay_scroll(app, target);
+            }
+        }
+        KeyCode::Char('T') | KeyCode::Esc => {
+            if let Some(Overlay::ResourceDetail { toc, .. }) = &mut app.overlay {
+                *toc = None;
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+async fn handle_new_task_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
+    let Some(Overlay::NewTask {
+        fields,
+        focused,
+        error,
+        ..
+    }) = &mut app.overlay
+    else {
+        return;
+    };
+
+    match key.code {
+        KeyCode::Tab | KeyCode::Down => {
+            *focused = (*focused + 1) % fields.len();
+            return;
+        }
+        KeyCode::BackTab | KeyCode::Up => {
+            *focused = (*focused + fields.len() - 1) % fields.len();
+            return;
+        }
         _ => {}
     }
+
+    match fields[*focused].handle_key(key) {
+        InputOutcome::Cancel => {
+            app.overlay = None;
+        }
+        InputOutcome::Submit => {
+            if fields[0].value.trim().is_empty() {
+                *error = Some("Title is required".to_string());
+                *focused = 0;
+                return;
+            }
+            submit_new_task(app, api).await;
+        }
+        InputOutcome::Edited => {
+            *error = None;
+        }
+        InputOutcome::Ignored => {}
+    }
+}
+
+async fn submit_new_task(app: &mut App, api: &ApiClient) {
+    let Some(Overlay::NewTask { column, fields, .. }) = &app.overlay else {
+        return;
+    };
+    let title = fields[0].value.trim().to_string();
+    let assignee = fields[1].value.trim().to_string();
+    let scopes: Vec<String> = fields[2]
+        .value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match api.create_task(column, &title, &assignee, &scopes, "", &app.user).await {
+        Ok(task) => {
+            app.overlay = None;
+            if let Ok(board) = api.board().await {
+                app.set_board(board);
+            }
+            app.select_task(&task.column, &task.filename);
+            app.set_status(format!("Created {}", task.filename));
+        }
+        Err(e) => {
+            if let Some(Overlay::NewTask { error, .. }) = &mut app.overlay {
+                *error = Some(format!("Create failed: {e}"));
+            }
+        }
+    }
+}
+
+async fn handle_input_key(app: &mut App, api: &ApiClient, key: KeyEvent) {
+    let Some(prompt) = &mut app.input else {
+        return;
+    };
+    match prompt.input.handle_key(key) {
+        InputOutcome::Submit => {
+            let prompt = app.input.take().expect("input prompt is open");
+            submit_input(app, api, prompt.action, prompt.input.value.trim()).await;
+        }
+        InputOutcome::Cancel => {
+            app.input = None;
+        }
+        InputOutcome::Edited | InputOutcome::Ignored => {}
+    }
+}
+
+async fn submit_input(app: &mut App, api: &ApiClient, action: InputAction, value: &str) {
+    match action {
+        InputAction::ExportTask => {
+            let Some(Overlay::TaskDetail { task, comments, .. }) = &app.overlay else {
+                return;
+            };
+            if value.is_empty() {
+                app.set_status("Export cancelled: no path given");
+                return;
+            }
+            let markdown = export::task_to_markdown(task, comments);
+            match std::fs::write(value, markdown) {
+                Ok(()) => app.set_status(format!("Exported to {value}")),
+                Err(e) => app.set_status(format!("Export failed: {e}")),
+            }
+        }
+        InputAction::OverlaySearch => {
+            if value.is_empty() {
+                app.set_overlay_search(None);
+                return;
+            }
+            app.set_overlay_search(Some(value.to_string()));
+            jump_to_match(app, MatchJump::First);
+        }
+        InputAction::BoardSearch => {
+            match app.set_board_search(Some(value.to_string())) {
+                0 if !value.is_empty() => app.set_status(format!("No tasks match \"{value}\"")),
+                _ => app.select_first_board_match(),
+            }
+        }
+        InputAction::BulkAddScope => {
+            if !value.is_empty() {
+                bulk_add_scope(app, api, value).await;
+            }
+        }
+        InputAction::Command => match command::parse(value) {
+            Ok(cmd) => execute_command(app, api, cmd).await,
+            Err(e) => app.set_status(format!("{e}")),
+        },
+    }
+}
+
+/// Run a parsed `:` command through the same code paths as its keys.
+async fn execute_command(app: &mut App, api: &ApiClient, cmd: Command) {
+    match cmd {
+        Command::Quit => app.should_quit = true,
+        Command::View(view) => {
+            app.view = view;
+            app.focus = Focus::Content;
+        }
+        Command::Help => app.overlay = Some(Overlay::Help { scroll: 0 }),
+        Command::Home => {
+            let saved = home::state_path()
+                .context("No state directory (set XDG_STATE_HOME or HOME)")
+                .and_then(|path| home::save(&path, app.view, app.focus));
+            match saved {
+                Ok(()) => app.set_status(format!("Home: {} on start", app.view.label())),
+                Err(e) => app.set_status(format!("Failed to save home: {e:#}")),
+            }
+        }
+        Command::Refresh => {
+            if !offline_blocked(app) {
+                refresh_current_view(app, api).await;
+            }
+        }
+        Command::Filter(filter) => {
+            app.view = View::Board;
+            app.focus = Focus::Content;
+            let status = match filter {
+                Filter::Assignee(name) => {
+                    let status = format!("Showing tasks assigned to @{name}");
+                    app.active_assignee = Some(name);
+                    status
+                }
+                Filter::Scope(scope) => {
+                    let status = format!("Showing tasks in [{scope}]");
+                    app.active_scope = Some(scope);
+                    status
+                }
+                Filter::Status(name) => {
+                    let status = format!("Showing {name} tasks");
+                    app.active_status = Some(name);
+                    status
+                }
+                Filter::Clear => {
+                    app.clear_filters();
+                    "Showing all tasks".to_string()
+                }
+            };
+            app.clamp_indices();
+            app.set_status(status);
+        }
+        Command::Goto { column, filename } => {
+            let Some(col) = app.column_by_name(&column) else {
+                app.set_status(format!("No column named \"{column}\""));
+                return;
+            };
+            app.view = View::Board;
+            app.focus = Focus::Content;
+            let Some(filename) = filename else {
+                app.select_column(col);
+                return;
+            };
+            // Filenames may be shortened to any unique prefix, like "001"
+            let found = app.board.as_ref().and_then(|b| {
+                let c = &b.columns[col];
+                let prefixed: Vec<&str> = c
+                    .tasks
+                    .iter()
+                    .map(|t| t.filename.as_str())
+                    .filter(|f| f.starts_with(filename.as_str()))
+                    .collect();
+                let exact = prefixed.iter().find(|&&f| f == filename || f == format!("{filename}.md"));
+                let file = exact.or(prefixed.first().filter(|_| prefixed.len() == 1))?;
+                Some((c.name.clone(), file.to_string()))
+            });
+            match found {
+                Some((name, file)) => {
+                    app.select_task(&name, &file);
+                }
+                None => app.set_status(format!("No single task {filename} in {column}")),
+            }
+        }
+    }
+}
+
+/// Move every marked task to `column`, then clear the marks.
+async fn bulk_move(app: &mut App, api: &ApiClient, column: usize) {
+    let Some(to) = app
+        .board
+        .as_ref()
+        .and_then(|b| b.columns.get(column))
+        .map(|c| (c.name.clone(), c.display_label().to_string()))
+    else {
+        return;
+    };
+    let (to_column, label) = to;
+    let mut moved = 0;
+    let mut failed = 0;
+    for (from_column, filename) in std::mem::take(&mut app.marked) {
+        if from_column == to_column {
+            continue;
+        }
+        match api.move_task(&filename, &from_column, &to_column).await {
+            Ok(()) => moved += 1,
+            Err(_) => failed += 1,
+        }
+    }
+    refresh_current_view(app, api).await;
+    app.select_column(column);
+    app.set_status(bulk_status(format!("Moved {moved} {} to {label}", tasks(moved)), failed));
+}
+
+/// Add `scope` to every marked task that lacks it, then clear the marks.
+async fn bulk_add_scope(app: &mut App, api: &ApiClient, scope: &str) {
+    let mut updated = 0;
+    let mut failed = 0;
+    for (column, filename) in std::mem::take(&mut app.marked) {
+        let Some(task) = app.find_task(&column, &filename) else {
+            continue;
+        };
+        let mut scopes: Vec<String> = task.meta.scopes.as_vec().iter().map(|s| s.to_string()).collect();
+        if scopes.iter().any(|s| s.eq_ignore_ascii_case(scope)) {
+            continue;
+        }
+        scopes.push(scope.to_string());
+        match api.set_scopes(&column, &filename, &scopes).await {
+            Ok(task) => {
+                app.apply_task_update(task);
+                updated += 1;
+            }
+            Err(_) => failed += 1,
+        }
+    }
+    app.set_status(bulk_status(format!("Added [{scope}] to {updated} {}", tasks(updated)), failed));
+}
+
+fn bulk_status(done: String, failed: usize) -> String {
+    match failed {
+        0 => done,
+        n => format!("{done}, {n} failed"),
+    }
+}
+
+fn tasks(n: usize) -> &'static str {
+    if n == 1 {
+        "task"
+    } else {
+        "tasks"
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MatchJump {
+    /// First match at or below the current scroll position
+    First,
+    Next,
+    Prev,
+}
+
+/// Scroll the overlay to a match of its find query, wrapping around the ends.
+fn jump_to_match(app: &mut App, jump: MatchJump) {
+    let Some(query) = app.overlay_search().map(str::to_string) else {
+        return;
+    };
+    let screen = screen_rect();
+    let Some(ui::OverlayContent { lines, width, .. }) = ui::overlay_content(app, screen) else {
+        return;
+    };
+    let mut found: Vec<_> = lines.iter().cloned().collect();
+    let hits = ui::markdown::highlight_matches(&mut found, &query);
+    if hits.is_empty() {
+        app.set_status(format!("No matches for \"{query}\""));
+        return;
+    }
+
+    let line_rows = lines.rows(width);
+    let rows: Vec<usize> = hits
+        .iter()
+        .map(|&line| line_rows[..line].iter().sum())
+        .collect();
+    let current = overlay_scroll(app);
+    let idx = match jump {
+        MatchJump::First => rows.iter().position(|&r| r >= current).unwrap_or(0),
+        MatchJump::Next => rows.iter().position(|&r| r > current).unwrap_or(0),
+        MatchJump::Prev => rows
+            .iter()
+            .rposition(|&r| r < current)
+            .unwrap_or(rows.len() - 1),
+    };
+    set_overlay_scroll(app, rows[idx]);
+    app.set_status(format!("Match {}/{}", idx + 1, rows.len()));
+}
+
+/// Open the first image referenced in the overlay body with the system viewer.
+fn open_overlay_image(app: &mut App) {
+    let (Some(body), Some(base_dir)) = (app.overlay_body(), overlay_base_dir(app)) else {
+        return;
+    };
+    let Some(image) = ui::markdown::find_images(body).into_iter().next() else {
+        app.set_status("No images in this item");
+        return;
+    };
+    open_overlay_target(app, &image.target, &base_dir);
+}
+
+/// The directory relative link targets in the open item resolve against.
+fn overlay_base_dir(app: &App) -> Option<PathBuf> {
+    match &app.overlay {
+        Some(Overlay::TaskDetail { task, .. }) => {
+            Some(app.data_dir.join("tasks").join(&task.column))
+        }
+        Some(Overlay::ResourceDetail {
+            resource,
+            resource_type,
+            ..
+        }) => Some(
+            app.data_dir
+                .join(resource_type.collection())
+                .join(&resource.dir_name),
+        ),
+        _ => None,
+    }
+}
+
+fn open_overlay_target(app: &mut App, target: &str, base_dir: &Path) {
+    let target = open::resolve_target(target, base_dir);
+    match open::open_external(&target) {
+        Ok(()) => app.set_status(format!("Opened {target}")),
+        Err(e) => app.set_status(format!("Cannot open {target}: {e}")),
+    }
+}
+
+fn set_link_picker(app: &mut App, selection: Option<usize>) {
+    if let Some(Overlay::TaskDetail { links, .. }) | Some(Overlay::ResourceDetail { links, .. }) =
+        &mut app.overlay
+    {
+        *links = selection;
+    }
+}
+
+/// Keys for the `O` link picker: j/k move, Enter or a digit opens, y copies
+/// the target. Returns whether the key was handled.
+fn handle_link_picker_key(app: &mut App, key: KeyEvent) -> bool {
+    let links = hyperlink::picker_links(app);
+    let (Some(Overlay::TaskDetail { links: Some(selected), .. })
+    | Some(Overlay::ResourceDetail { links: Some(selected), .. })) = &mut app.overlay
+    else {
+        return false;
+    };
+    let chosen = match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            if *selected + 1 < links.len() {
+                *selected += 1;
+            }
+            return true;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            *selected = selected.saturating_sub(1);
+            return true;
+        }
+        KeyCode::Char('O') | KeyCode::Esc => {
+            set_link_picker(app, None);
+            return true;
+        }
+        KeyCode::Char(c @ '1'..='9') => (c as usize - '1' as usize).min(links.len().saturating_sub(1)),
+        KeyCode::Enter | KeyCode::Char('y') => *selected,
+        _ => return false,
+    };
+    let Some(link) = links.get(chosen) else {
+        return true;
+    };
+    if key.code == KeyCode::Char('y') {
+        match clipboard::copy(&link.url) {
+            Ok(()) => app.set_status(format!("Copied {}", link.url)),
+            Err(e) => app.set_status(format!("Copy failed: {e}")),
+        }
+        return true;
+    }
+    set_link_picker(app, None);
+    if let Some(base_dir) = overlay_base_dir(app) {
+        open_overlay_target(app, &link.url, &base_dir);
+    }
+    true
 }
 
 fn scroll_overlay(app: &mut App, delta: i32) {
@@ -521,10 +2836,28 @@ fn scroll_overlay(app: &mut App, delta: i32) {
         Some(Overlay::ResourceDetail { scroll, .. }) => {
             *scroll = (*scroll as i32 + delta).max(0) as usize;
         }
-        Some(Overlay::Help { scroll }) => {
+        Some(Overlay::Help { scroll }) | Some(Overlay::CommandOutput { scroll, .. }) => {
             *scroll = (*scroll as i32 + delta).max(0) as usize;
         }
-        None => {}
+        Some(Overlay::Debug)
+        | Some(Overlay::NewTask { .. })
+        | Some(Overlay::QuickOpen { .. })
+        | Some(Overlay::AssigneePicker { .. })
+        | Some(Overlay::ScopeEditor { .. })
+        | Some(Overlay::ColumnPicker { .. })
+        | Some(Overlay::Progress { .. })
+        | Some(Overlay::Confirm { .. })
+        | None => {}
+    }
+}
+
+fn overlay_scroll(app: &App) -> usize {
+    match &app.overlay {
+        Some(Overlay::TaskDetail { scroll, .. })
+        | Some(Overlay::ResourceDetail { scroll, .. })
+        | Some(Overlay::Help { scroll })
+        | Some(Overlay::CommandOutput { scroll, .. }) => *scroll,
+        _ => 0,
     }
 }
 
@@ -532,8 +2865,16 @@ fn set_overlay_scroll(app: &mut App, value: usize) {
     match &mut app.overlay {
         Some(Overlay::TaskDetail { scroll, .. }) => *scroll = value,
         Some(Overlay::ResourceDetail { scroll, .. }) => *scroll = value,
-        Some(Overlay::Help { scroll }) => *scroll = value,
-        None => {}
+        Some(Overlay::Help { scroll }) | Some(Overlay::CommandOutput { scroll, .. }) => *scroll = value,
+        Some(Overlay::Debug)
+        | Some(Overlay::NewTask { .. })
+        | Some(Overlay::QuickOpen { .. })
+        | Some(Overlay::AssigneePicker { .. })
+        | Some(Overlay::ScopeEditor { .. })
+        | Some(Overlay::ColumnPicker { .. })
+        | Some(Overlay::Progress { .. })
+        | Some(Overlay::Confirm { .. })
+        | None => {}
     }
 }
 
@@ -542,6 +2883,7 @@ fn navigate_revision(app: &mut App, delta: i32) {
         revisions,
         current_rev,
         scroll,
+        visual,
         ..
     }) = &mut app.overlay
     {
@@ -569,27 +2911,51 @@ fn navigate_revision(app: &mut App, delta: i32) {
         };
         *current_rev = new_rev;
         *scroll = 0;
+        *visual = None;
+    }
+}
+
+/// Re-fetch the server config and board, re-deriving scope colors and
+/// column weights from them.
+async fn reload_config(app: &mut App, api: &ApiClient) {
+    let config = match api.config().await {
+        Ok(config) => config,
+        Err(e) => {
+            app.set_status(format!("Failed to load config: {e}"));
+            return;
+        }
+    };
+    let (columns, scopes) = (config.columns.len(), config.scopes.len());
+    app.apply_config(config);
+    if let Ok(board) = api.board().await {
+        app.set_board(board);
+        app.refresh_open_overlay();
     }
+    app.set_status(format!(
+        "Reloaded config: {columns} {}, {scopes} {}",
+        if columns == 1 { "column" } else { "columns" },
+        if scopes == 1 { "scope" } else { "scopes" },
+    ));
 }
 
 async fn refresh_current_view(app: &mut App, api: &ApiClient) {
     match app.view {
         View::Board => {
             if let Ok(board) = api.board().await {
-                app.board = Some(board);
-                app.ensure_board_row_vec();
-                app.clamp_indices();
+                app.set_board(board);
             }
         }
         View::Prompts => {
-            if let Ok(prompts) = api.list_prompts().await {
-                app.prompts = prompts;
+            let limit = app.loaded_window.get(ResourceType::Prompt);
+            if let Ok(prompts) = api.list_prompts(0, limit).await {
+                app.set_resources(ResourceType::Prompt, prompts);
                 app.clamp_indices();
             }
         }
         View::Documents => {
-            if let Ok(docs) = api.list_documents().await {
-                app.documents = docs;
+            let limit = app.loaded_window.get(ResourceType::Document);
+            if let Ok(docs) = api.list_documents(0, limit).await {
+                app.set_resources(ResourceType::Document, docs);
                 app.clamp_indices();
             }
         }
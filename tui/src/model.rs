@@ -1,15 +1,15 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // /api/version
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionInfo {
     pub version: String,
     pub project: String,
 }
 
 // /api/config
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub columns: Vec<ColumnDef>,
     #[serde(default)]
@@ -18,22 +18,82 @@ pub struct Config {
     pub scopes: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl Config {
+    /// Per-scope colors from `settings.scope_colors`, as (scope, color) pairs.
+    pub fn scope_colors(&self) -> Vec<(String, String)> {
+        self.color_setting("scope_colors")
+    }
+
+    /// Per-type colors for the Activity view from `settings.activity_colors`,
+    /// written like `scope_colors`.
+    pub fn activity_colors(&self) -> Vec<(String, String)> {
+        self.color_setting("activity_colors")
+    }
+
+    /// Task status pill colors from `settings.status_colors`, written like
+    /// `scope_colors`.
+    pub fn status_colors(&self) -> Vec<(String, String)> {
+        self.color_setting("status_colors")
+    }
+
+    fn color_setting(&self, key: &str) -> Vec<(String, String)> {
+        let colors: ScopeColors = self
+            .settings
+            .get(key)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        colors.pairs()
+    }
+}
+
+/// `settings.scope_colors` is either a mapping (`{auth: "#ef4444"}`) or, since
+/// the server's YAML parser only supports flat lists, `[auth=#ef4444, ...]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ScopeColors {
+    Map(HashMap<String, String>),
+    List(Vec<String>),
+    #[default]
+    Empty,
+}
+
+impl ScopeColors {
+    pub fn pairs(&self) -> Vec<(String, String)> {
+        match self {
+            ScopeColors::Map(m) => m.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            ScopeColors::List(items) => items
+                .iter()
+                .filter_map(|item| item.split_once(['=', ':']))
+                .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                .collect(),
+            ScopeColors::Empty => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnDef {
     pub name: String,
     #[serde(default)]
     pub label: String,
     #[serde(default)]
     pub color: String,
+    /// Relative board width (default 1). Kept as written so bad values can
+    /// be rejected when the layout is built; see `App::column_weights`.
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub weight: Option<String>,
+    /// Work-in-progress limit: more tasks than this is flagged on the board
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub wip_limit: Option<String>,
 }
 
 // /api/board
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Board {
     pub columns: Vec<Column>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Column {
     pub name: String,
     #[serde(default)]
@@ -44,8 +104,19 @@ pub struct Column {
     pub tasks: Vec<Task>,
 }
 
+impl Column {
+    /// Label for display, falling back to the directory name.
+    pub fn display_label(&self) -> &str {
+        if self.label.is_empty() {
+            &self.name
+        } else {
+            &self.label
+        }
+    }
+}
+
 // /api/task/{col}/{file}
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub filename: String,
     #[serde(default)]
@@ -56,7 +127,18 @@ pub struct Task {
     pub body: String,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+impl Task {
+    /// Title for display, falling back to the filename.
+    pub fn display_title(&self) -> &str {
+        if self.meta.title.is_empty() {
+            &self.filename
+        } else {
+            &self.meta.title
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TaskMeta {
     #[serde(default)]
     pub id: Option<serde_json::Value>,
@@ -74,10 +156,79 @@ pub struct TaskMeta {
     pub branch: String,
     #[serde(default)]
     pub completed: String,
+    /// `high`/`medium`/`low` or a number (1 = most urgent); see `priority_level`.
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub priority: Option<String>,
+    /// Workflow state independent of the column (`blocked`, `review`, ...).
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub status: Option<String>,
+    /// Frontmatter keys the fields above don't model (estimate, team, ...).
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl TaskMeta {
+    /// The status, unless it is blank.
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref().map(str::trim).filter(|s| !s.is_empty())
+    }
+
+    /// Priority normalized to 3 (high), 2 (medium) or 1 (low). Numeric
+    /// priorities follow the P1-P5 convention: 1-2 high, 3 medium, 4-5 low.
+    pub fn priority_level(&self) -> Option<u8> {
+        let raw = self.priority.as_deref()?.trim().to_lowercase();
+        let raw = raw.strip_prefix('p').unwrap_or(&raw);
+        if let Ok(n) = raw.parse::<u8>() {
+            return match n {
+                1 | 2 => Some(3),
+                3 => Some(2),
+                4 | 5 => Some(1),
+                _ => None,
+            };
+        }
+        match raw {
+            "high" | "hi" | "h" | "urgent" | "critical" => Some(3),
+            "medium" | "med" | "m" | "normal" => Some(2),
+            "low" | "lo" | "l" | "minor" => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Unmodeled scalar fields as (key, value) pairs sorted by key. Null,
+    /// empty, list and object values are skipped.
+    pub fn extra_fields(&self) -> Vec<(&str, String)> {
+        let mut fields: Vec<(&str, String)> = self
+            .extra
+            .iter()
+            .filter_map(|(key, value)| {
+                let text = match value {
+                    serde_json::Value::String(s) if !s.is_empty() => s.clone(),
+                    serde_json::Value::Number(n) => n.to_string(),
+                    serde_json::Value::Bool(b) => b.to_string(),
+                    _ => return None,
+                };
+                Some((key.as_str(), text))
+            })
+            .collect();
+        fields.sort();
+        fields
+    }
+}
+
+/// Accept a YAML scalar parsed as either a string or a number.
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(s)) if !s.is_empty() => Some(s),
+        Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
 }
 
 /// Scopes can be either a list of strings or a single string from YAML parsing.
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ScopesOrString {
     List(Vec<String>),
@@ -97,7 +248,7 @@ impl ScopesOrString {
 }
 
 // /api/comments/{id}
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
     pub filename: String,
     #[serde(default)]
@@ -106,7 +257,7 @@ pub struct Comment {
     pub body: String,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CommentMeta {
     #[serde(default)]
     pub author: String,
@@ -115,7 +266,7 @@ pub struct CommentMeta {
 }
 
 // /api/prompts, /api/documents
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Resource {
     pub dir_name: String,
     #[serde(default)]
@@ -124,7 +275,18 @@ pub struct Resource {
     pub body: String,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+impl Resource {
+    /// Title for display, falling back to the directory name.
+    pub fn display_title(&self) -> &str {
+        if self.meta.title.is_empty() {
+            &self.dir_name
+        } else {
+            &self.meta.title
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResourceMeta {
     #[serde(default)]
     pub id: Option<serde_json::Value>,
@@ -136,12 +298,15 @@ pub struct ResourceMeta {
     pub updated: String,
     #[serde(default)]
     pub revision: Option<i64>,
+    /// Number of stored revisions; only sent by the list endpoints
+    #[serde(default)]
+    pub revision_count: Option<u32>,
     #[serde(default)]
     pub scopes: ScopesOrString,
 }
 
 // /api/{type}/{dir}/revisions
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Revision {
     pub filename: String,
     #[serde(default)]
@@ -150,7 +315,7 @@ pub struct Revision {
     pub body: String,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RevisionMeta {
     #[serde(default)]
     pub revision: Option<i64>,
@@ -159,7 +324,7 @@ pub struct RevisionMeta {
 }
 
 // /api/activity
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActivityEntry {
     #[serde(rename = "type")]
     pub entry_type: String,
@@ -177,12 +342,80 @@ pub struct ActivityEntry {
     pub mtime: f64,
     #[serde(default)]
     pub revision: Option<i64>,
+    /// Comment filename for `comment` entries, which otherwise carry their
+    /// task's `id`, `column` and `filename`
+    #[serde(default)]
+    pub comment: Option<String>,
 }
 
 // /api/poll
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PollHashes {
     pub board: String,
     pub prompts: String,
     pub documents: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_textual_and_numeric_priorities() {
+        let level = |json: &str| {
+            serde_json::from_str::<TaskMeta>(json)
+                .unwrap()
+                .priority_level()
+        };
+        assert_eq!(level(r#"{"priority": "High"}"#), Some(3));
+        assert_eq!(level(r#"{"priority": "med"}"#), Some(2));
+        assert_eq!(level(r#"{"priority": 1}"#), Some(3));
+        assert_eq!(level(r#"{"priority": "P3"}"#), Some(2));
+        assert_eq!(level(r#"{"priority": 5}"#), Some(1));
+        assert_eq!(level(r#"{"priority": "someday"}"#), None);
+        assert_eq!(level(r#"{"priority": null}"#), None);
+        assert_eq!(level(r#"{}"#), None);
+    }
+
+    #[test]
+    fn reads_activity_colors_and_unknown_entry_types() {
+        let config: Config = serde_json::from_str(
+            r#"{"columns": [], "settings": {"activity_colors": ["comment=#ff8800", "release: red"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.activity_colors(),
+            vec![
+                ("comment".to_string(), "#ff8800".to_string()),
+                ("release".to_string(), "red".to_string()),
+            ]
+        );
+
+        let entry: ActivityEntry = serde_json::from_str(
+            r#"{"type": "comment", "title": "Re: login", "id": 7, "column": "todo",
+                "filename": "001.md", "comment": "20240601-093000.md", "mtime": 1.0}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.comment.as_deref(), Some("20240601-093000.md"));
+        let entry: ActivityEntry = serde_json::from_str(r#"{"type": "release", "title": "v2"}"#).unwrap();
+        assert_eq!(entry.entry_type, "release");
+    }
+
+    #[test]
+    fn keeps_unmodeled_scalar_frontmatter() {
+        let meta: TaskMeta = serde_json::from_str(
+            r#"{"title": "T", "team": "core", "estimate": 3, "epic": "",
+                "blocked": false, "links": ["a"], "owner": null}"#,
+        )
+        .unwrap();
+        assert_eq!(meta.title, "T");
+        assert_eq!(
+            meta.extra_fields(),
+            vec![
+                ("blocked", "false".to_string()),
+                ("estimate", "3".to_string()),
+                ("team", "core".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+#[cfg(target_os = "macos")]
+const OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const OPENER: &str = "xdg-open";
+
+/// Open a URL or file path with the platform's default handler. The child
+/// is dropped without waiting; tokio reaps it once it exits.
+pub fn open_external(target: &str) -> io::Result<()> {
+    // Targets come from markdown; neither opener takes `--`, so don't let
+    // one pass itself off as an option
+    if target.starts_with('-') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("refusing to open {target:?}"),
+        ));
+    }
+    Command::new(OPENER)
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Resolve a link target from a markdown body. URLs and absolute paths are
+/// returned as-is; relative paths are joined onto `base_dir`.
+pub fn resolve_target(target: &str, base_dir: &Path) -> String {
+    if target.contains("://") || Path::new(target).is_absolute() {
+        target.to_string()
+    } else {
+        base_dir.join(target).to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_that_look_like_options_are_refused() {
+        let err = open_external("-x").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(resolve_target("-x", Path::new("/docs")), "/docs/-x");
+    }
+}
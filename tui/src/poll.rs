@@ -1,10 +1,48 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use futures::StreamExt;
 use tokio::sync::mpsc;
 
-use crate::api::ApiClient;
-use crate::model::PollHashes;
+use crate::api::{ApiClient, ResourcePage};
+use crate::app::ResourceType;
+use crate::model::{PollHashes, Task};
+
+/// Page size for prompt/document list requests.
+pub const PAGE_SIZE: usize = 50;
+
+/// How many prompts/documents the UI has loaded. Shared with the poller so
+/// change-driven refreshes re-fetch the same window instead of the first page.
+#[derive(Debug)]
+pub struct LoadedWindow {
+    prompts: AtomicUsize,
+    documents: AtomicUsize,
+}
+
+impl LoadedWindow {
+    pub fn new() -> Self {
+        Self {
+            prompts: AtomicUsize::new(PAGE_SIZE),
+            documents: AtomicUsize::new(PAGE_SIZE),
+        }
+    }
+
+    pub fn get(&self, rtype: ResourceType) -> usize {
+        self.slot(rtype).load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, rtype: ResourceType, len: usize) {
+        self.slot(rtype).store(len.max(PAGE_SIZE), Ordering::Relaxed);
+    }
+
+    fn slot(&self, rtype: ResourceType) -> &AtomicUsize {
+        match rtype {
+            ResourceType::Prompt => &self.prompts,
+            ResourceType::Document => &self.documents,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum PollMessage {
@@ -12,25 +50,100 @@ pub enum PollMessage {
         version: crate::model::VersionInfo,
         board: crate::model::Board,
         config: crate::model::Config,
-        prompts: Vec<crate::model::Resource>,
-        documents: Vec<crate::model::Resource>,
+        prompts: ResourcePage,
+        documents: ResourcePage,
         activity: Vec<crate::model::ActivityEntry>,
     },
     HashesChanged(PollHashes),
     BoardUpdated(crate::model::Board),
-    PromptsUpdated(Vec<crate::model::Resource>),
-    DocumentsUpdated(Vec<crate::model::Resource>),
+    /// Re-fetched alongside the board, since column edits change both
+    ConfigUpdated(crate::model::Config),
+    PromptsUpdated(ResourcePage),
+    DocumentsUpdated(ResourcePage),
     ActivityUpdated(Vec<crate::model::ActivityEntry>),
+    /// A single task changed (from a granular `task_updated` event)
+    TaskUpdated(Task),
+    /// The SSE stream dropped; carries the total number of reconnect attempts
+    Reconnecting(u32),
     ConnectionLost,
     ConnectionRestored,
-    #[allow(dead_code)]
     Error(String),
+    /// Not from the poller: what a `--prompt-command` run printed, or why it
+    /// failed, sent by the task running it
+    PromptOutput {
+        command: String,
+        result: Result<String, String>,
+    },
 }
 
-pub fn spawn_poller(api: ApiClient, tx: mpsc::UnboundedSender<PollMessage>) {
+impl PollMessage {
+    /// Compact description for `--log-file`; payloads are summarized, not copied.
+    pub fn log_fields(&self) -> serde_json::Value {
+        use serde_json::json;
+        let task_count = |board: &crate::model::Board| -> usize {
+            board.columns.iter().map(|c| c.tasks.len()).sum()
+        };
+        match self {
+            PollMessage::InitialData {
+                board,
+                prompts,
+                documents,
+                activity,
+                ..
+            } => json!({
+                "message": "initial_data",
+                "tasks": task_count(board),
+                "prompts": prompts.items.len(),
+                "documents": documents.items.len(),
+                "activity": activity.len(),
+            }),
+            PollMessage::HashesChanged(hashes) => json!({
+                "message": "hashes_changed",
+                "board": hashes.board,
+                "prompts": hashes.prompts,
+                "documents": hashes.documents,
+            }),
+            PollMessage::BoardUpdated(board) => {
+                json!({"message": "board_updated", "tasks": task_count(board)})
+            }
+            PollMessage::ConfigUpdated(config) => {
+                json!({"message": "config_updated", "columns": config.columns.len()})
+            }
+            PollMessage::PromptsUpdated(page) => {
+                json!({"message": "prompts_updated", "items": page.items.len()})
+            }
+            PollMessage::DocumentsUpdated(page) => {
+                json!({"message": "documents_updated", "items": page.items.len()})
+            }
+            PollMessage::ActivityUpdated(entries) => {
+                json!({"message": "activity_updated", "entries": entries.len()})
+            }
+            PollMessage::TaskUpdated(task) => json!({
+                "message": "task_updated",
+                "column": task.column,
+                "filename": task.filename,
+            }),
+            PollMessage::Reconnecting(count) => json!({"message": "reconnecting", "attempt": count}),
+            PollMessage::ConnectionLost => json!({"message": "connection_lost"}),
+            PollMessage::ConnectionRestored => json!({"message": "connection_restored"}),
+            PollMessage::Error(error) => json!({"message": "error", "error": error}),
+            PollMessage::PromptOutput { command, result } => json!({
+                "message": "prompt_output",
+                "command": command,
+                "ok": result.is_ok(),
+            }),
+        }
+    }
+}
+
+pub fn spawn_poller(
+    api: ApiClient,
+    tx: mpsc::UnboundedSender<PollMessage>,
+    window: Arc<LoadedWindow>,
+) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         // Initial data fetch
-        match fetch_all(&api).await {
+        match fetch_all(&api, &window).await {
             Ok(msg) => {
                 let _ = tx.send(msg);
             }
@@ -41,14 +154,17 @@ pub fn spawn_poller(api: ApiClient, tx: mpsc::UnboundedSender<PollMessage>) {
         }
 
         let mut was_connected = true;
+        let mut reconnects: u32 = 0;
+        let mut resume = SseResume::default();
 
         loop {
-            match connect_sse(&api, &tx, &mut was_connected).await {
+            match connect_sse(&api, &tx, &window, &mut was_connected, &mut resume).await {
                 Ok(()) => {
                     // Stream ended cleanly (server closed connection)
                 }
-                Err(_) => {
+                Err(e) => {
                     // Connection failed or broke
+                    let _ = tx.send(PollMessage::Error(format!("{e:#}")));
                 }
             }
 
@@ -57,23 +173,67 @@ pub fn spawn_poller(api: ApiClient, tx: mpsc::UnboundedSender<PollMessage>) {
                 let _ = tx.send(PollMessage::ConnectionLost);
             }
 
-            // Back off before reconnecting
-            tokio::time::sleep(Duration::from_secs(2)).await;
+            // Back off before reconnecting, as long as the server asked for
+            tokio::time::sleep(resume.retry).await;
+            reconnects += 1;
+            let _ = tx.send(PollMessage::Reconnecting(reconnects));
         }
-    });
+    })
+}
+
+/// Reconnect state carried across SSE connections.
+#[derive(Debug)]
+struct SseResume {
+    /// Last `id:` seen, sent back as `Last-Event-ID` so the server can replay
+    last_event_id: Option<String>,
+    /// Reconnect delay; the server can override it with `retry:`
+    retry: Duration,
+}
+
+impl Default for SseResume {
+    fn default() -> Self {
+        Self {
+            last_event_id: None,
+            retry: Duration::from_secs(2),
+        }
+    }
+}
+
+impl SseResume {
+    /// Record the `id:` and `retry:` fields of a message. Per the SSE spec an
+    /// empty `id:` resets the last id and a non-numeric `retry:` is ignored.
+    fn update(&mut self, message: &str) {
+        for line in message.lines() {
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "id" if !value.contains('\0') => {
+                    self.last_event_id = (!value.is_empty()).then(|| value.to_string());
+                }
+                "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                    if let Ok(ms) = value.parse() {
+                        self.retry = Duration::from_millis(ms);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
 }
 
 /// Connect to SSE stream and process events until disconnect.
 async fn connect_sse(
     api: &ApiClient,
     tx: &mpsc::UnboundedSender<PollMessage>,
+    window: &LoadedWindow,
     was_connected: &mut bool,
+    resume: &mut SseResume,
 ) -> anyhow::Result<()> {
-    let resp = api
-        .client()
-        .get(api.events_url())
-        .send()
-        .await?;
+    let mut req = api.client().get(api.events_url());
+    if let Some(id) = &resume.last_event_id {
+        req = req.header("Last-Event-ID", id);
+    }
+    let resp = req.send().await?;
 
     if !resp.status().is_success() {
         anyhow::bail!("SSE endpoint returned {}", resp.status());
@@ -83,91 +243,156 @@ async fn connect_sse(
         *was_connected = true;
         let _ = tx.send(PollMessage::ConnectionRestored);
         // Full refresh on reconnect
-        if let Ok(msg) = fetch_all(api).await {
+        if let Ok(msg) = fetch_all(api, window).await {
             let _ = tx.send(msg);
         }
     }
 
     let mut stream = resp.bytes_stream();
-    let mut buf = String::new();
+    let mut buf = SseBuffer::default();
     let mut last_hashes: Option<PollHashes> = None;
 
     while let Some(chunk) = stream.next().await {
         let chunk = chunk?;
-        buf.push_str(&String::from_utf8_lossy(&chunk));
-
-        // SSE messages are terminated by a blank line (\n\n)
-        while let Some(boundary) = buf.find("\n\n") {
-            let message = buf[..boundary].to_string();
-            buf = buf[boundary + 2..].to_string();
-
-            if let Some(hashes) = parse_sse_message(&message) {
-                // On hash change, selectively re-fetch changed data
-                if let Some(prev) = &last_hashes {
-                    let mut changed = false;
-                    if prev.board != hashes.board {
-                        changed = true;
-                        if let Ok(board) = api.board().await {
-                            let _ = tx.send(PollMessage::BoardUpdated(board));
-                        }
+        for message in buf.push(&chunk)? {
+            resume.update(&message);
+            let hashes = match parse_sse_message(&message) {
+                Some(SseEvent::Hashes(hashes)) => hashes,
+                Some(SseEvent::TaskUpdated(task)) => {
+                    let _ = tx.send(PollMessage::TaskUpdated(*task));
+                    continue;
+                }
+                // Heartbeat comment, unknown event or unparseable payload
+                None => continue,
+            };
+
+            // On hash change, selectively re-fetch changed data
+            if let Some(prev) = &last_hashes {
+                let mut changed = false;
+                if prev.board != hashes.board {
+                    changed = true;
+                    if let Ok(board) = api.board().await {
+                        let _ = tx.send(PollMessage::BoardUpdated(board));
+                    }
+                    if let Ok(config) = api.config().await {
+                        let _ = tx.send(PollMessage::ConfigUpdated(config));
                     }
-                    if prev.prompts != hashes.prompts {
-                        changed = true;
-                        if let Ok(prompts) = api.list_prompts().await {
-                            let _ = tx.send(PollMessage::PromptsUpdated(prompts));
-                        }
+                }
+                if prev.prompts != hashes.prompts {
+                    changed = true;
+                    let limit = window.get(ResourceType::Prompt);
+                    if let Ok(prompts) = api.list_prompts(0, limit).await {
+                        let _ = tx.send(PollMessage::PromptsUpdated(prompts));
                     }
-                    if prev.documents != hashes.documents {
-                        changed = true;
-                        if let Ok(docs) = api.list_documents().await {
-                            let _ = tx.send(PollMessage::DocumentsUpdated(docs));
-                        }
+                }
+                if prev.documents != hashes.documents {
+                    changed = true;
+                    let limit = window.get(ResourceType::Document);
+                    if let Ok(docs) = api.list_documents(0, limit).await {
+                        let _ = tx.send(PollMessage::DocumentsUpdated(docs));
                     }
-                    if changed {
-                        if let Ok(activity) = api.activity().await {
-                            let _ = tx.send(PollMessage::ActivityUpdated(activity));
-                        }
-                        let _ = tx.send(PollMessage::HashesChanged(hashes.clone()));
+                }
+                if changed {
+                    if let Ok(activity) = api.activity().await {
+                        let _ = tx.send(PollMessage::ActivityUpdated(activity));
                     }
+                    let _ = tx.send(PollMessage::HashesChanged(hashes.clone()));
                 }
-                last_hashes = Some(hashes);
+            } else {
+                // First hashes on this connection (`init`)
+                let _ = tx.send(PollMessage::HashesChanged(hashes.clone()));
             }
-            // else: heartbeat comment or unparseable — ignore
+            last_hashes = Some(hashes);
         }
     }
 
     Ok(())
 }
 
-/// Parse an SSE message block. Returns hashes for both `init` and `changed` events.
-fn parse_sse_message(message: &str) -> Option<PollHashes> {
+/// Largest SSE message accepted before the connection is dropped; guards
+/// against a stream that never sends a blank line.
+const MAX_SSE_MESSAGE: usize = 1024 * 1024;
+
+/// Raw bytes of the SSE stream not yet split into messages. Decoding waits
+/// for a full message so multibyte characters split across chunks survive.
+#[derive(Debug, Default)]
+struct SseBuffer {
+    bytes: Vec<u8>,
+}
+
+impl SseBuffer {
+    /// Append a chunk and return every message it completed. Messages end at
+    /// a blank line, `\n\n` or `\r\n\r\n`.
+    fn push(&mut self, chunk: &[u8]) -> anyhow::Result<Vec<String>> {
+        self.bytes.extend_from_slice(chunk);
+        let mut messages = Vec::new();
+        while let Some((end, sep_len)) = find_boundary(&self.bytes) {
+            let message: Vec<u8> = self.bytes.drain(..end + sep_len).take(end).collect();
+            messages.push(String::from_utf8_lossy(&message).into_owned());
+        }
+        if self.bytes.len() > MAX_SSE_MESSAGE {
+            self.bytes.clear();
+            anyhow::bail!("SSE message exceeds {MAX_SSE_MESSAGE} bytes");
+        }
+        Ok(messages)
+    }
+}
+
+/// Position and length of the first message separator in `bytes`.
+fn find_boundary(bytes: &[u8]) -> Option<(usize, usize)> {
+    let find = |sep: &[u8]| bytes.windows(sep.len()).position(|w| w == sep);
+    match (find(b"\n\n"), find(b"\r\n\r\n")) {
+        (Some(lf), Some(crlf)) if crlf < lf => Some((crlf, 4)),
+        (Some(lf), _) => Some((lf, 2)),
+        (None, Some(crlf)) => Some((crlf, 4)),
+        (None, None) => None,
+    }
+}
+
+/// A parsed server-sent event.
+#[derive(Debug)]
+enum SseEvent {
+    /// `init` / `changed`: state hashes, diffed to decide what to re-fetch
+    Hashes(PollHashes),
+    /// `task_updated`: the full task, applied to the board without a re-fetch
+    TaskUpdated(Box<Task>),
+}
+
+/// Parse an SSE message block into a known event. A payload split over
+/// several `data:` lines is joined with newlines, as the SSE spec says.
+fn parse_sse_message(message: &str) -> Option<SseEvent> {
     let mut event_type = None;
-    let mut data = None;
+    let mut data: Vec<&str> = Vec::new();
 
     for line in message.lines() {
-        if let Some(rest) = line.strip_prefix("event: ") {
-            event_type = Some(rest.trim());
-        } else if let Some(rest) = line.strip_prefix("data: ") {
-            data = Some(rest.trim());
+        // Lines starting with ":" are comments (heartbeat) and have no field name
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event_type = Some(value.trim()),
+            "data" => data.push(value),
+            _ => {}
         }
-        // Lines starting with ":" are comments (heartbeat) — skip
     }
+    if data.is_empty() {
+        return None;
+    }
+    let json_str = data.join("\n");
 
-    match (event_type, data) {
-        (Some("init" | "changed"), Some(json_str)) => {
-            serde_json::from_str::<PollHashes>(json_str).ok()
-        }
+    match event_type {
+        Some("init" | "changed") => serde_json::from_str(&json_str).ok().map(SseEvent::Hashes),
+        Some("task_updated") => serde_json::from_str(&json_str).ok().map(SseEvent::TaskUpdated),
         _ => None,
     }
 }
 
-async fn fetch_all(api: &ApiClient) -> anyhow::Result<PollMessage> {
+async fn fetch_all(api: &ApiClient, window: &LoadedWindow) -> anyhow::Result<PollMessage> {
     let (version, board, config, prompts, documents, activity) = tokio::try_join!(
         api.version(),
         api.board(),
         api.config(),
-        api.list_prompts(),
-        api.list_documents(),
+        api.list_prompts(0, window.get(ResourceType::Prompt)),
+        api.list_documents(0, window.get(ResourceType::Document)),
         api.activity(),
     )?;
     Ok(PollMessage::InitialData {
@@ -179,3 +404,95 @@ async fn fetch_all(api: &ApiClient) -> anyhow::Result<PollMessage> {
         activity,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hash_events() {
+        let msg = "event: changed\ndata: {\"board\": \"a\", \"prompts\": \"b\", \"documents\": \"c\"}";
+        match parse_sse_message(msg) {
+            Some(SseEvent::Hashes(h)) => assert_eq!(h.board, "a"),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(parse_sse_message(": heartbeat").is_none());
+    }
+
+    #[test]
+    fn joins_data_lines_and_ignores_comment_only_messages() {
+        let hashes = |msg: &str| match parse_sse_message(msg) {
+            Some(SseEvent::Hashes(h)) => Some((h.board, h.prompts, h.documents)),
+            _ => None,
+        };
+        let expected = Some(("a".to_string(), "b".to_string(), "c".to_string()));
+        assert_eq!(hashes("event: init\ndata:{\"board\": \"a\", \"prompts\": \"b\", \"documents\": \"c\"}"), expected);
+        let split = "event: changed\n: keep-alive\ndata: {\"board\": \"a\",\ndata:  \"prompts\": \"b\",\ndata: \"documents\": \"c\"}";
+        assert_eq!(hashes(split), expected);
+
+        assert!(parse_sse_message(": heartbeat\n: still here").is_none());
+        assert!(parse_sse_message("event: changed").is_none());
+    }
+
+    #[test]
+    fn parses_task_updated_event() {
+        let msg = "event: task_updated\ndata: {\"filename\": \"001-x.md\", \"column\": \"todo\", \"meta\": {\"title\": \"X\"}}";
+        match parse_sse_message(msg) {
+            Some(SseEvent::TaskUpdated(task)) => {
+                assert_eq!(task.column, "todo");
+                assert_eq!(task.meta.title, "X");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(parse_sse_message("event: task_updated\ndata: {}").is_none());
+    }
+
+    #[test]
+    fn tracks_event_id_and_retry() {
+        let mut resume = SseResume::default();
+        resume.update("id: 42\nretry: 5000\nevent: changed\ndata: {}");
+        assert_eq!(resume.last_event_id.as_deref(), Some("42"));
+        assert_eq!(resume.retry, Duration::from_millis(5000));
+
+        // Heartbeats keep the id; invalid retry values are ignored
+        resume.update(": heartbeat\nretry: soon");
+        assert_eq!(resume.last_event_id.as_deref(), Some("42"));
+        assert_eq!(resume.retry, Duration::from_millis(5000));
+
+        resume.update("id\nretry:250");
+        assert_eq!(resume.last_event_id, None);
+        assert_eq!(resume.retry, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn buffers_multibyte_characters_split_across_chunks() {
+        let message = "event: changed\ndata: {\"title\": \"Příliš\"}\n\n".as_bytes();
+        let split = message.iter().position(|&b| b == 0xC5).unwrap() + 1;
+        let mut buf = SseBuffer::default();
+        assert!(buf.push(&message[..split]).unwrap().is_empty());
+        assert_eq!(
+            buf.push(&message[split..]).unwrap(),
+            vec!["event: changed\ndata: {\"title\": \"Příliš\"}"]
+        );
+    }
+
+    #[test]
+    fn splits_on_lf_and_crlf_separators() {
+        let mut buf = SseBuffer::default();
+        let messages = buf
+            .push(b"event: a\r\ndata: 1\r\n\r\n: heartbeat\n\nevent: b\r\n")
+            .unwrap();
+        assert_eq!(messages, vec!["event: a\r\ndata: 1", ": heartbeat"]);
+        assert_eq!(buf.push(b"data: 2\r\n\r\n").unwrap(), vec!["event: b\r\ndata: 2"]);
+        assert!(buf.bytes.is_empty());
+    }
+
+    #[test]
+    fn rejects_oversized_messages() {
+        let mut buf = SseBuffer::default();
+        assert!(buf.push(&vec![b'x'; MAX_SSE_MESSAGE]).unwrap().is_empty());
+        assert!(buf.push(b"x").is_err());
+        // The buffer is reset, so the next message parses normally
+        assert_eq!(buf.push(b"data: 1\n\n").unwrap(), vec!["data: 1"]);
+    }
+}
@@ -0,0 +1,73 @@
+//! Prompt helpers: `{{placeholder}}` detection and `--prompt-command`, which
+//! pipes a prompt body to a command and shows what it prints.
+
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Names of the `{{...}}` placeholders in `body`, trimmed, each once, in
+/// order of first use. Empty braces and ones spanning lines are not names.
+pub fn placeholders(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        let name = rest[..end].trim();
+        if !name.is_empty() && !name.contains(['\n', '{', '}']) && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        if !rest[..end].contains("{{") {
+            rest = &rest[end + 2..];
+        }
+    }
+    names
+}
+
+/// Run `command` through the shell with `input` on stdin and return its
+/// stdout. A failing command's stderr becomes the error.
+pub async fn run_command(command: &str, input: &str) -> Result<String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Cannot run {command:?}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Commands that don't read their input close the pipe early
+        let _ = stdin.write_all(input.as_bytes()).await;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{command:?} {}: {}", output.status, stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_each_placeholder_once() {
+        let body = "Review {{ file }} for {{topic}}.\nAgain: {{file}}, {{}} and {{a\nb}} {{{x}}}";
+        assert_eq!(placeholders(body), ["file", "topic"]);
+        assert_eq!(placeholders("{{ unclosed"), Vec::<String>::new());
+        assert_eq!(placeholders("{{ {{inner}} }}"), ["inner"]);
+    }
+
+    #[tokio::test]
+    async fn pipes_input_to_the_command() {
+        assert_eq!(run_command("tr a-z A-Z", "hello").await.unwrap(), "HELLO");
+        let err = run_command("echo oops >&2; exit 3", "").await.unwrap_err();
+        assert!(format!("{err:#}").contains("oops"));
+    }
+}
@@ -0,0 +1,158 @@
+//! Cross-view "go to" index: tasks, prompts and documents matched by title.
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{App, ResourceType};
+use crate::model::Task;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuickOpenTarget {
+    Task {
+        column: String,
+        filename: String,
+        id: String,
+    },
+    Resource {
+        resource_type: ResourceType,
+        dir_name: String,
+    },
+}
+
+impl QuickOpenTarget {
+    pub fn for_task(column: &str, task: &Task) -> Self {
+        QuickOpenTarget::Task {
+            column: column.to_string(),
+            filename: task.filename.clone(),
+            id: task
+                .meta
+                .id
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Short type tag shown next to each result.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            QuickOpenTarget::Task { .. } => "task",
+            QuickOpenTarget::Resource {
+                resource_type: ResourceType::Prompt,
+                ..
+            } => "prompt",
+            QuickOpenTarget::Resource {
+                resource_type: ResourceType::Document,
+                ..
+            } => "doc",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickOpenItem {
+    pub title: String,
+    pub target: QuickOpenTarget,
+}
+
+/// Snapshot every task, prompt and document currently loaded in the app.
+pub fn build_index(app: &App) -> Vec<QuickOpenItem> {
+    let mut items = Vec::new();
+    if let Some(board) = &app.board {
+        for col in &board.columns {
+            for task in &col.tasks {
+                items.push(QuickOpenItem {
+                    title: task.display_title().to_string(),
+                    target: QuickOpenTarget::for_task(&col.name, task),
+                });
+            }
+        }
+    }
+    for (resource_type, list) in [
+        (ResourceType::Prompt, &app.prompts),
+        (ResourceType::Document, &app.documents),
+    ] {
+        for res in list {
+            items.push(QuickOpenItem {
+                title: res.display_title().to_string(),
+                target: QuickOpenTarget::Resource {
+                    resource_type,
+                    dir_name: res.dir_name.clone(),
+                },
+            });
+        }
+    }
+    items
+}
+
+/// Indices into `items` matching `query`, best first. An empty query keeps index order.
+pub fn rank(items: &[QuickOpenItem], query: &str) -> Vec<usize> {
+    let mut scored: Vec<(usize, i64)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| fuzzy_score(query, &item.title).map(|s| (i, s)))
+        .collect();
+    // Stable sort keeps index order among equal scores
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+/// Case-insensitive subsequence match. Consecutive matches and matches at word
+/// starts score higher; gaps cost a little. Returns None if `query` does not match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+
+    let mut score = 0i64;
+    let mut qi = 0;
+    let mut last_match: Option<usize> = None;
+    for (i, c) in chars.iter().enumerate() {
+        if qi == query.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(query[qi].to_lowercase()) {
+            continue;
+        }
+        score += 1;
+        let word_start = i == 0 || !chars[i - 1].is_alphanumeric();
+        if word_start {
+            score += 8;
+        }
+        match last_match {
+            Some(prev) if prev + 1 == i => score += 5,
+            Some(prev) => score -= (i - prev - 1).min(5) as i64,
+            None => score -= i.min(5) as i64,
+        }
+        last_match = Some(i);
+        qi += 1;
+    }
+
+    (qi == query.len()).then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_all_query_chars_in_order() {
+        assert!(fuzzy_score("flb", "Fix login bug").is_some());
+        assert!(fuzzy_score("FLB", "fix login bug").is_some());
+        assert!(fuzzy_score("blf", "Fix login bug").is_none());
+        assert!(fuzzy_score("xyz", "Fix login bug").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn prefers_contiguous_and_word_start_matches() {
+        let contiguous = fuzzy_score("login", "Fix login bug").unwrap();
+        let scattered = fuzzy_score("login", "Large ordering in notes").unwrap();
+        assert!(contiguous > scattered);
+
+        let word_start = fuzzy_score("api", "API spec").unwrap();
+        let mid_word = fuzzy_score("api", "Rapid iteration").unwrap();
+        assert!(word_start > mid_word);
+    }
+}
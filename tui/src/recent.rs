@@ -0,0 +1,66 @@
+//! Recently opened tasks, prompts and documents, kept across sessions.
+//!
+//! History is stored per server URL in `recent.json` in the state directory
+//! (see state.rs).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::quick_open::QuickOpenItem;
+use crate::state;
+
+/// How many items the history keeps.
+pub const MAX_RECENT: usize = 20;
+
+pub fn state_path() -> Option<PathBuf> {
+    state::path("recent.json")
+}
+
+/// Move `item` to the front of `recent`, dropping older entries for the same
+/// target and anything past `MAX_RECENT`.
+pub fn push(recent: &mut Vec<QuickOpenItem>, item: QuickOpenItem) {
+    recent.retain(|r| r.target != item.target);
+    recent.insert(0, item);
+    recent.truncate(MAX_RECENT);
+}
+
+/// History for `server`. A missing or unreadable file is an empty history.
+pub fn load(path: &Path, server: &str) -> Vec<QuickOpenItem> {
+    state::load_for(path, server).unwrap_or_default()
+}
+
+/// Replace the history for `server`, keeping other servers' entries.
+pub fn save(path: &Path, server: &str, items: &[QuickOpenItem]) -> Result<()> {
+    state::save_for(path, server, items.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ResourceType;
+    use crate::quick_open::QuickOpenTarget;
+
+    fn doc(name: &str) -> QuickOpenItem {
+        QuickOpenItem {
+            title: name.to_uppercase(),
+            target: QuickOpenTarget::Resource {
+                resource_type: ResourceType::Document,
+                dir_name: name.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn push_dedups_and_caps() {
+        let mut recent = Vec::new();
+        for i in 0..MAX_RECENT + 5 {
+            push(&mut recent, doc(&format!("d{i}")));
+        }
+        assert_eq!(recent.len(), MAX_RECENT);
+        push(&mut recent, doc("d10"));
+        assert_eq!(recent[0].target, doc("d10").target);
+        assert_eq!(recent.iter().filter(|r| r.title == "D10").count(), 1);
+        assert_eq!(recent.len(), MAX_RECENT);
+    }
+}
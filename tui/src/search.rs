@@ -0,0 +1,213 @@
+//! Text index over the loaded tasks, prompts and documents, shared by the
+//! board search and quick-open.
+//!
+//! Text is lowercased once per data update instead of on every keystroke.
+//! Tasks, prompts and documents are indexed separately, so a poll that
+//! changes one list only re-indexes that list.
+
+use std::collections::BTreeMap;
+
+use crate::app::ResourceType;
+use crate::model::{Board, Resource};
+use crate::quick_open::QuickOpenTarget;
+
+/// Where a query matched, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HitField {
+    /// A word of the title starts with the query
+    TitleWord,
+    /// The title, filename, assignee or scopes contain the query
+    Name,
+    Body,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub target: QuickOpenTarget,
+    pub title: String,
+    pub field: HitField,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    tasks: Segment,
+    prompts: Segment,
+    documents: Segment,
+}
+
+impl SearchIndex {
+    /// Re-index every task on `board`.
+    pub fn index_tasks(&mut self, board: Option<&Board>) {
+        let entries = board
+            .iter()
+            .flat_map(|b| &b.columns)
+            .flat_map(|c| c.tasks.iter().map(move |t| (c, t)))
+            .map(|(col, task)| {
+                let mut names = vec![task.meta.title.as_str(), &task.filename, &task.meta.assignee];
+                names.extend(task.meta.scopes.as_vec());
+                Entry::new(
+                    QuickOpenTarget::for_task(&col.name, task),
+                    task.display_title(),
+                    &names,
+                    &task.body,
+                )
+            })
+            .collect();
+        self.tasks = Segment::new(entries);
+    }
+
+    /// Re-index the loaded prompts or documents.
+    pub fn index_resources(&mut self, resource_type: ResourceType, list: &[Resource]) {
+        let entries = list
+            .iter()
+            .map(|res| {
+                let target = QuickOpenTarget::Resource {
+                    resource_type,
+                    dir_name: res.dir_name.clone(),
+                };
+                let names = [res.meta.title.as_str(), &res.dir_name];
+                Entry::new(target, res.display_title(), &names, &res.body)
+            })
+            .collect();
+        match resource_type {
+            ResourceType::Prompt => self.prompts = Segment::new(entries),
+            ResourceType::Document => self.documents = Segment::new(entries),
+        }
+    }
+
+    /// Everything containing `query`, case-insensitively: title word
+    /// prefixes first, then other name matches, then body matches. Within
+    /// each group tasks come before prompts and documents, in loaded order.
+    pub fn query(&self, query: &str) -> Vec<SearchHit> {
+        let needle = query.to_lowercase();
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        let mut hits = Vec::new();
+        for segment in [&self.tasks, &self.prompts, &self.documents] {
+            segment.query(&needle, &mut hits);
+        }
+        hits.sort_by_key(|hit| hit.field);
+        hits
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    target: QuickOpenTarget,
+    title: String,
+    /// Lowercased names, one per line so matches can't span two of them
+    names: String,
+    body: String,
+}
+
+impl Entry {
+    fn new(target: QuickOpenTarget, title: &str, names: &[&str], body: &str) -> Self {
+        Self {
+            target,
+            title: title.to_string(),
+            names: names.join("\n").to_lowercase(),
+            body: body.to_lowercase(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Segment {
+    entries: Vec<Entry>,
+    /// Lowercased title words and the entries using them
+    words: BTreeMap<String, Vec<usize>>,
+}
+
+impl Segment {
+    fn new(entries: Vec<Entry>) -> Self {
+        let mut words: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (i, entry) in entries.iter().enumerate() {
+            for word in words_of(&entry.title) {
+                let ids = words.entry(word).or_default();
+                if ids.last() != Some(&i) {
+                    ids.push(i);
+                }
+            }
+        }
+        Self { entries, words }
+    }
+
+    fn query(&self, needle: &str, hits: &mut Vec<SearchHit>) {
+        let mut fields: Vec<Option<HitField>> = vec![None; self.entries.len()];
+        // Only single-word queries can be a word prefix
+        if needle.chars().all(char::is_alphanumeric) {
+            let prefixed = self
+                .words
+                .range(needle.to_string()..)
+                .take_while(|(word, _)| word.starts_with(needle));
+            for (_, ids) in prefixed {
+                for &i in ids {
+                    fields[i] = Some(HitField::TitleWord);
+                }
+            }
+        }
+        for (entry, field) in self.entries.iter().zip(&mut fields) {
+            if field.is_none() {
+                if entry.names.contains(needle) {
+                    *field = Some(HitField::Name);
+                } else if entry.body.contains(needle) {
+                    *field = Some(HitField::Body);
+                }
+            }
+        }
+        hits.extend(self.entries.iter().zip(fields).filter_map(|(entry, field)| {
+            Some(SearchHit {
+                target: entry.target.clone(),
+                title: entry.title.clone(),
+                field: field?,
+            })
+        }));
+    }
+}
+
+fn words_of(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_title_words_then_names_then_bodies() {
+        let board: Board = serde_json::from_value(serde_json::json!({"columns": [
+            {"name": "todo", "tasks": [
+                {"filename": "001-a.md", "meta": {"title": "Catalog cleanup"}, "body": "see the login page"},
+                {"filename": "002-b.md", "meta": {"title": "Fix Login bug", "assignee": "alice"}},
+            ]},
+        ]}))
+        .unwrap();
+        let docs: Vec<Resource> =
+            serde_json::from_value(serde_json::json!([{"dir_name": "login-notes", "body": ""}])).unwrap();
+        let mut index = SearchIndex::default();
+        index.index_tasks(Some(&board));
+        index.index_resources(ResourceType::Document, &docs);
+
+        let found = |index: &SearchIndex, query: &str| -> Vec<(String, HitField)> {
+            index.query(query).into_iter().map(|h| (h.title, h.field)).collect()
+        };
+        assert_eq!(
+            found(&index, "LOG"),
+            [
+                ("Fix Login bug".to_string(), HitField::TitleWord),
+                ("login-notes".to_string(), HitField::TitleWord),
+                ("Catalog cleanup".to_string(), HitField::Name),
+            ]
+        );
+        assert_eq!(found(&index, "login p"), [("Catalog cleanup".to_string(), HitField::Body)]);
+        assert_eq!(found(&index, "alice"), [("Fix Login bug".to_string(), HitField::Name)]);
+        assert!(found(&index, "").is_empty());
+
+        // Re-indexing tasks leaves the documents alone
+        index.index_tasks(None);
+        assert_eq!(found(&index, "log"), [("login-notes".to_string(), HitField::TitleWord)]);
+    }
+}
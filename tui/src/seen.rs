@@ -0,0 +1,24 @@
+//! The Activity view's read marker, kept across sessions.
+//!
+//! The newest activity `mtime` seen is stored per server URL in
+//! `activity_seen.json` in the state directory, next to the recent history.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::state;
+
+pub fn state_path() -> Option<PathBuf> {
+    state::path("activity_seen.json")
+}
+
+/// Marker for `server`. A missing or unreadable file means nothing was seen.
+pub fn load(path: &Path, server: &str) -> Option<f64> {
+    state::load_for(path, server)
+}
+
+/// Replace the marker for `server`, keeping other servers' markers.
+pub fn save(path: &Path, server: &str, mtime: f64) -> Result<()> {
+    state::save_for(path, server, mtime)
+}
@@ -0,0 +1,110 @@
+//! Offline snapshots: the app's loaded data written on quit (`--snapshot`)
+//! and browsed later without a server (`--offline`).
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api::ResourcePage;
+use crate::app::{App, ResourceType};
+use crate::model::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Local time the snapshot was written, shown in the offline banner
+    pub saved_at: String,
+    pub version: Option<VersionInfo>,
+    pub config: Option<Config>,
+    pub board: Option<Board>,
+    #[serde(default)]
+    pub prompts: Vec<Resource>,
+    #[serde(default)]
+    pub documents: Vec<Resource>,
+    #[serde(default)]
+    pub activity: Vec<ActivityEntry>,
+}
+
+impl Snapshot {
+    pub fn from_app(app: &App) -> Self {
+        Self {
+            saved_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            version: app.version.clone(),
+            config: app.config.clone(),
+            board: app.board.clone(),
+            prompts: app.prompts.clone(),
+            documents: app.documents.clone(),
+            activity: app.activity.clone(),
+        }
+    }
+
+    /// Load the snapshot's data into `app` as if it had come from the server.
+    pub fn apply(self, app: &mut App) {
+        app.version = self.version;
+        if let Some(config) = self.config {
+            app.apply_config(config);
+        }
+        if let Some(board) = self.board {
+            app.set_board(board);
+        }
+        for (rtype, items) in [
+            (ResourceType::Prompt, self.prompts),
+            (ResourceType::Document, self.documents),
+        ] {
+            app.set_resources(
+                rtype,
+                ResourcePage {
+                    items,
+                    has_more: false,
+                },
+            );
+        }
+        app.activity = self.activity;
+        app.offline_since = Some(self.saved_at);
+        app.loading = false;
+        app.clamp_indices();
+    }
+}
+
+pub fn save(app: &App, path: &Path) -> Result<()> {
+    let json = serde_json::to_string(&Snapshot::from_app(app))?;
+    std::fs::write(path, json).with_context(|| format!("Cannot write snapshot {path:?}"))
+}
+
+pub fn load(path: &Path) -> Result<Snapshot> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Cannot read snapshot {path:?}"))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid snapshot {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_app_data() {
+        let board: Board = serde_json::from_str(
+            r#"{"columns": [{"name": "todo", "tasks": [
+                {"filename": "001-a.md", "column": "todo",
+                 "meta": {"id": 1, "title": "A", "scopes": "auth", "priority": 2, "epic": "x"},
+                 "body": "- [ ] step"}]}]}"#,
+        )
+        .unwrap();
+        let mut app = App::new();
+        app.set_board(board);
+
+        let json = serde_json::to_string(&Snapshot::from_app(&app)).unwrap();
+        let mut restored = App::new();
+        serde_json::from_str::<Snapshot>(&json)
+            .unwrap()
+            .apply(&mut restored);
+
+        let task = &restored.board.as_ref().unwrap().columns[0].tasks[0];
+        assert_eq!(task.meta.title, "A");
+        assert_eq!(task.meta.scopes.as_vec(), vec!["auth"]);
+        assert_eq!(task.meta.priority_level(), Some(3));
+        assert_eq!(task.meta.extra_fields(), vec![("epic", "x".to_string())]);
+        assert_eq!(task.body, "- [ ] step");
+        assert!(restored.offline_since.is_some());
+    }
+}
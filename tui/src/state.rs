@@ -0,0 +1,74 @@
+//! JSON state files kept across sessions in `$XDG_STATE_HOME/mdboard`
+//! (falling back to `~/.local/state`): the recent history, the Activity read
+//! marker and the home view.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// `$XDG_STATE_HOME/mdboard`, where every state file lives.
+pub fn state_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))?;
+    Some(base.join("mdboard"))
+}
+
+/// `name` inside the state directory.
+pub fn path(name: &str) -> Option<PathBuf> {
+    Some(state_dir()?.join(name))
+}
+
+pub fn read<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Write `value` as pretty JSON, creating the directory first.
+pub fn write<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Cannot create {dir:?}"))?;
+    }
+    let json = serde_json::to_string_pretty(value)?;
+    std::fs::write(path, json).with_context(|| format!("Cannot write {path:?}"))
+}
+
+/// The value stored for `server` in a file keyed by server URL. A missing or
+/// unreadable file has no values.
+pub fn load_for<T: DeserializeOwned>(path: &Path, server: &str) -> Option<T> {
+    read::<HashMap<String, T>>(path).ok()?.remove(server)
+}
+
+/// Replace the value for `server`, keeping other servers' values.
+pub fn save_for<T: Serialize + DeserializeOwned>(path: &Path, server: &str, value: T) -> Result<()> {
+    let mut all: HashMap<String, T> = read(path).unwrap_or_default();
+    all.insert(server.to_string(), value);
+    write(path, &all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_per_server() {
+        let path = std::env::temp_dir()
+            .join(format!("mdboard-state-{}", std::process::id()))
+            .join("values.json");
+        assert_eq!(load_for::<f64>(&path, "http://a"), None);
+        save_for(&path, "http://a", 1.5).unwrap();
+        save_for(&path, "http://b", 2.0).unwrap();
+        save_for(&path, "http://a", 3.0).unwrap();
+        assert_eq!(load_for(&path, "http://a"), Some(3.0));
+        assert_eq!(load_for(&path, "http://b"), Some(2.0));
+        assert_eq!(load_for::<f64>(&path, "http://c"), None);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(load_for::<f64>(&path, "http://a"), None);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
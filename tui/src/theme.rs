@@ -1,17 +1,124 @@
 use ratatui::style::Color;
 
-/// Convert a hex color string like "#3b82f6" to a ratatui Color.
-pub fn hex_to_color(hex: &str) -> Color {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() != 6 {
-        return Color::White;
+/// Parse a config color: `#rrggbb`, `#rgb` (with or without `#`) or a CSS
+/// color name. Returns None for anything else, including the empty string.
+pub fn parse_color(spec: &str) -> Option<Color> {
+    let spec = spec.trim();
+    let hex = spec.strip_prefix('#').unwrap_or(spec);
+    if hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+        match hex.len() {
+            6 => {
+                return Some(Color::Rgb(
+                    channel(&hex[0..2])?,
+                    channel(&hex[2..4])?,
+                    channel(&hex[4..6])?,
+                ));
+            }
+            3 => {
+                // #f0a -> #ff00aa
+                let double = |i: usize| channel(&hex[i..i + 1]).map(|v| v * 17);
+                return Some(Color::Rgb(double(0)?, double(1)?, double(2)?));
+            }
+            _ => {}
+        }
     }
-    let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(255);
-    let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(255);
-    let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(255);
-    Color::Rgb(r, g, b)
+    let name = spec.to_ascii_lowercase();
+    CSS_COLORS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, (r, g, b))| Color::Rgb(r, g, b))
 }
 
+/// Color for the column at `index`: its configured color if it parses,
+/// otherwise a palette color so unconfigured columns stay distinguishable.
+pub fn column_color(spec: &str, index: usize) -> Color {
+    parse_color(spec).unwrap_or(PALETTE[index % PALETTE.len()])
+}
+
+/// Blend from `from` (t = 0) to `to` (t = 1). Non-RGB colors can't be mixed
+/// and switch over halfway.
+pub fn lerp(from: Color, to: Color, t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        _ if t < 0.5 => from,
+        _ => to,
+    }
+}
+
+/// Fallback accents, in the same family as the semantic colors below.
+const PALETTE: [Color; 8] = [
+    Color::Rgb(137, 180, 250), // blue
+    Color::Rgb(166, 227, 161), // green
+    Color::Rgb(249, 226, 175), // yellow
+    Color::Rgb(250, 179, 135), // peach
+    Color::Rgb(203, 166, 247), // mauve
+    Color::Rgb(148, 226, 213), // teal
+    Color::Rgb(243, 139, 168), // red
+    Color::Rgb(245, 194, 231), // pink
+];
+
+/// Common CSS named colors.
+const CSS_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("black", (0, 0, 0)),
+    ("white", (255, 255, 255)),
+    ("gray", (128, 128, 128)),
+    ("grey", (128, 128, 128)),
+    ("silver", (192, 192, 192)),
+    ("darkgray", (169, 169, 169)),
+    ("lightgray", (211, 211, 211)),
+    ("slategray", (112, 128, 144)),
+    ("red", (255, 0, 0)),
+    ("darkred", (139, 0, 0)),
+    ("crimson", (220, 20, 60)),
+    ("firebrick", (178, 34, 34)),
+    ("tomato", (255, 99, 71)),
+    ("coral", (255, 127, 80)),
+    ("salmon", (250, 128, 114)),
+    ("orange", (255, 165, 0)),
+    ("darkorange", (255, 140, 0)),
+    ("gold", (255, 215, 0)),
+    ("yellow", (255, 255, 0)),
+    ("khaki", (240, 230, 140)),
+    ("olive", (128, 128, 0)),
+    ("lime", (0, 255, 0)),
+    ("green", (0, 128, 0)),
+    ("darkgreen", (0, 100, 0)),
+    ("forestgreen", (34, 139, 34)),
+    ("seagreen", (46, 139, 87)),
+    ("limegreen", (50, 205, 50)),
+    ("lightgreen", (144, 238, 144)),
+    ("teal", (0, 128, 128)),
+    ("cyan", (0, 255, 255)),
+    ("aqua", (0, 255, 255)),
+    ("turquoise", (64, 224, 208)),
+    ("skyblue", (135, 206, 235)),
+    ("lightblue", (173, 216, 230)),
+    ("steelblue", (70, 130, 180)),
+    ("dodgerblue", (30, 144, 255)),
+    ("royalblue", (65, 105, 225)),
+    ("blue", (0, 0, 255)),
+    ("navy", (0, 0, 128)),
+    ("indigo", (75, 0, 130)),
+    ("purple", (128, 0, 128)),
+    ("rebeccapurple", (102, 51, 153)),
+    ("violet", (238, 130, 238)),
+    ("orchid", (218, 112, 214)),
+    ("plum", (221, 160, 221)),
+    ("magenta", (255, 0, 255)),
+    ("fuchsia", (255, 0, 255)),
+    ("pink", (255, 192, 203)),
+    ("hotpink", (255, 105, 180)),
+    ("brown", (165, 42, 42)),
+    ("chocolate", (210, 105, 30)),
+    ("tan", (210, 180, 140)),
+    ("maroon", (128, 0, 0)),
+];
+
 // Semantic colors
 pub const HEADER_BG: Color = Color::Rgb(30, 30, 46);
 pub const HEADER_FG: Color = Color::Rgb(205, 214, 244);
@@ -28,3 +135,45 @@ pub const GREEN: Color = Color::Rgb(166, 227, 161);
 pub const YELLOW: Color = Color::Rgb(249, 226, 175);
 pub const RED: Color = Color::Rgb(243, 139, 168);
 pub const SCOPE_FG: Color = Color::Rgb(180, 190, 254);
+pub const LINK_FG: Color = Color::Rgb(116, 199, 236);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_and_named_colors() {
+        assert_eq!(parse_color("#3b82f6"), Some(Color::Rgb(59, 130, 246)));
+        assert_eq!(parse_color("#f00"), Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(parse_color("f00"), Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(parse_color("rebeccapurple"), Some(Color::Rgb(102, 51, 153)));
+        assert_eq!(parse_color("Red"), Some(Color::Rgb(255, 0, 0)));
+    }
+
+    #[test]
+    fn rejects_empty_and_malformed_colors() {
+        assert_eq!(parse_color(""), None);
+        assert_eq!(parse_color("#"), None);
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("#gg0000"), None);
+        assert_eq!(parse_color("notacolor"), None);
+        assert_eq!(parse_color("#ff\u{e9}"), None);
+    }
+
+    #[test]
+    fn lerp_mixes_rgb_channels() {
+        let (black, white) = (Color::Rgb(0, 0, 0), Color::Rgb(255, 255, 255));
+        assert_eq!(lerp(black, white, 0.0), black);
+        assert_eq!(lerp(black, white, 0.5), Color::Rgb(128, 128, 128));
+        assert_eq!(lerp(black, white, 2.0), white);
+        assert_eq!(lerp(Color::Red, Color::Blue, 0.4), Color::Red);
+    }
+
+    #[test]
+    fn unparseable_column_colors_use_the_palette() {
+        assert_eq!(column_color("", 0), PALETTE[0]);
+        assert_eq!(column_color("bogus", 1), PALETTE[1]);
+        assert_ne!(column_color("", 2), Color::White);
+        assert_eq!(column_color("#f00", 3), Color::Rgb(255, 0, 0));
+    }
+}
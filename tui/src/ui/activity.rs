@@ -1,37 +1,56 @@
 use ratatui::Frame;
 use ratatui::layout::Rect;
-use ratatui::style::{Modifier, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Padding};
 
 use crate::app::{App, Focus};
 use crate::model::ActivityEntry;
 use crate::theme;
+use crate::ui::common::render_empty_state;
 
 pub fn render_activity(f: &mut Frame, app: &App, area: Rect) {
     if app.activity.is_empty() {
-        let msg = if app.loading {
-            "Loading..."
+        if app.loading {
+            render_empty_state(f, area, "Loading...", "");
         } else {
-            "No activity"
-        };
-        let p = ratatui::widgets::Paragraph::new(msg)
-            .style(Style::default().fg(theme::TEXT_DIM))
-            .centered();
-        f.render_widget(p, area);
+            render_empty_state(
+                f,
+                area,
+                "No activity",
+                "Changes to tasks, prompts and documents show up here.",
+            );
+        }
         return;
     }
 
-    let items: Vec<ListItem> = app
-        .activity
+    let entries = app.filtered_activity();
+    if entries.is_empty() {
+        render_empty_state(
+            f,
+            area,
+            "All activity filtered out",
+            "Press t / p / d to show tasks, prompts or documents again.",
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = entries
         .iter()
         .enumerate()
-        .map(|(i, entry)| make_activity_item(entry, i == app.activity_index && app.overlay.is_none() && app.focus == Focus::Content))
+        .map(|(i, entry)| {
+            make_activity_item(
+                entry,
+                type_color(app, &entry.entry_type),
+                i == app.activity_index && app.overlay.is_none() && app.focus == Focus::Content,
+                app.is_new_activity(entry),
+            )
+        })
         .collect();
 
     let block = Block::default()
         .title(Line::from(Span::styled(
-            format!(" Activity ({}) ", app.activity.len()),
+            activity_title(app, entries.len()),
             Style::default()
                 .fg(theme::TEXT_PRIMARY)
                 .add_modifier(Modifier::BOLD),
@@ -49,22 +68,59 @@ pub fn render_activity(f: &mut Frame, app: &App, area: Rect) {
     f.render_stateful_widget(list, area, &mut state);
 }
 
-fn make_activity_item(entry: &ActivityEntry, is_selected: bool) -> ListItem<'static> {
-    let indicator = if is_selected { "▌" } else { " " };
+/// ` Activity (12) `, or with filters ` Activity (5 of 12) · hidden: prompt 4, doc 3 `,
+/// followed by `· 3 new ` when entries changed since the last visit.
+fn activity_title(app: &App, shown: usize) -> String {
+    let total = app.activity.len();
+    let new = app.activity.iter().filter(|e| app.is_new_activity(e)).count();
+    let new = if new > 0 { format!("· {new} new ") } else { String::new() };
+    if app.hidden_activity_types.is_empty() {
+        return format!(" Activity ({total}) {new}");
+    }
+    let hidden: Vec<String> = ["task", "prompt", "document"]
+        .into_iter()
+        .filter(|t| app.hidden_activity_types.contains(*t))
+        .map(|t| {
+            let count = app.activity.iter().filter(|e| e.entry_type == t).count();
+            format!("{} {count}", type_label(t))
+        })
+        .collect();
+    format!(" Activity ({shown} of {total}) · hidden: {} {new}", hidden.join(", "))
+}
 
-    let type_color = match entry.entry_type.as_str() {
+fn type_label(entry_type: &str) -> &str {
+    match entry_type {
+        "document" => "doc",
+        other => other,
+    }
+}
+
+/// Color for an entry type: `settings.activity_colors` first, then the
+/// built-in palette. Types without either get a neutral color.
+fn type_color(app: &App, entry_type: &str) -> Color {
+    if let Some(color) = app.activity_colors.get(&entry_type.to_lowercase()) {
+        return *color;
+    }
+    match entry_type {
         "task" => theme::TAB_ACTIVE_FG,
         "prompt" => theme::GREEN,
         "document" => theme::YELLOW,
+        "comment" => theme::SCOPE_FG,
+        "release" => theme::LINK_FG,
         _ => theme::TEXT_SECONDARY,
-    };
+    }
+}
 
-    let type_label = match entry.entry_type.as_str() {
-        "task" => "task",
-        "prompt" => "prompt",
-        "document" => "doc",
-        other => other,
-    };
+fn make_activity_item(
+    entry: &ActivityEntry,
+    type_color: Color,
+    is_selected: bool,
+    is_new: bool,
+) -> ListItem<'static> {
+    let indicator = if is_selected { "▌" } else { " " };
+
+    // Long names of unknown types would push titles out of line
+    let type_label: String = type_label(&entry.entry_type).chars().take(8).collect();
 
     let mut spans = vec![
         Span::styled(
@@ -75,11 +131,15 @@ fn make_activity_item(entry: &ActivityEntry, is_selected: bool) -> ListItem<'sta
             format!(" {type_label:<8}"),
             Style::default().fg(type_color),
         ),
+        Span::styled(
+            if is_new { "NEW " } else { "" },
+            Style::default().fg(theme::GREEN).add_modifier(Modifier::BOLD),
+        ),
         Span::styled(
             entry.title.clone(),
             Style::default()
                 .fg(theme::TEXT_PRIMARY)
-                .add_modifier(if is_selected {
+                .add_modifier(if is_selected || is_new {
                     Modifier::BOLD
                 } else {
                     Modifier::empty()
@@ -1,50 +1,62 @@
+use chrono::NaiveDate;
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Modifier, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Padding, Paragraph};
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, Focus};
+use crate::app::{App, CardField, CardMetaPolicy, Focus};
+use crate::dates;
 use crate::model::Task;
 use crate::theme;
+use crate::ui::common::render_empty_state;
+use crate::ui::markdown::highlight_matches;
+
+/// Width of the "+N more" strip shown with --max-columns.
+const OVERFLOW_WIDTH: u16 = 10;
 
 pub fn render_board(f: &mut Frame, app: &App, area: Rect) {
     let board = match &app.board {
         Some(b) => b,
         None => {
-            let msg = if app.loading {
-                "Loading..."
+            if app.loading {
+                render_empty_state(f, area, "Loading...", "");
             } else {
-                "No board data"
-            };
-            let p = Paragraph::new(msg)
-                .style(Style::default().fg(theme::TEXT_DIM))
-                .centered();
-            f.render_widget(p, area);
+                render_empty_state(
+                    f,
+                    area,
+                    "No board data",
+                    "Check that the mdboard server is running, or pass --url.",
+                );
+            }
             return;
         }
     };
 
     if board.columns.is_empty() {
-        let p = Paragraph::new("No columns configured")
-            .style(Style::default().fg(theme::TEXT_DIM))
-            .centered();
-        f.render_widget(p, area);
+        let hint = format!(
+            "Add columns to {}",
+            app.data_dir.join("tasks").join("config.yaml").display()
+        );
+        render_empty_state(f, area, "No columns configured", &hint);
         return;
     }
 
-    // Split area into equal columns
-    let constraints: Vec<Constraint> = board
-        .columns
-        .iter()
-        .map(|_| Constraint::Ratio(1, board.columns.len() as u32))
-        .collect();
-
-    let col_areas = Layout::horizontal(constraints).split(area);
+    let layout = column_layout(app, area);
+    if layout.columns.is_empty() {
+        render_empty_state(f, area, "All columns hidden", "Press X to show them again");
+        return;
+    }
+    if let Some((hidden, strip)) = layout.overflow {
+        render_overflow(f, hidden, strip);
+    }
 
-    for (i, col) in board.columns.iter().enumerate() {
+    for (i, col_area) in layout.columns {
+        let col = &board.columns[i];
+        let shown: Vec<&Task> = col.tasks.iter().filter(|t| app.matches_filter(t)).collect();
         let is_selected = i == app.board_col && app.overlay.is_none() && app.focus == Focus::Content;
-        let col_color = theme::hex_to_color(&col.color);
+        let col_color = theme::column_color(&col.color, i);
 
         let border_style = if is_selected {
             Style::default().fg(col_color)
@@ -57,7 +69,19 @@ pub fn render_board(f: &mut Frame, app: &App, area: Rect) {
         } else {
             &col.label
         };
-        let title_line = Line::from(vec![
+        let wip_limit = app.wip_limit(i);
+        let over_wip = wip_limit.is_some_and(|limit| col.tasks.len() > limit);
+        let mut title_spans = vec![];
+        if let Some(hint) = app.column_jump_label(i) {
+            title_spans.push(Span::styled(
+                format!(" {hint} "),
+                Style::default()
+                    .fg(theme::HEADER_BG)
+                    .bg(theme::YELLOW)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        title_spans.extend([
             Span::styled(
                 format!(" {label} "),
                 Style::default()
@@ -65,79 +89,337 @@ pub fn render_board(f: &mut Frame, app: &App, area: Rect) {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!("{}", col.tasks.len()),
-                Style::default().fg(theme::TEXT_DIM),
+                match (app.has_filter(), wip_limit) {
+                    (true, _) => format!("{}/{}", shown.len(), col.tasks.len()),
+                    (false, Some(limit)) => format!("{}/{limit}", col.tasks.len()),
+                    (false, None) => format!("{}", col.tasks.len()),
+                },
+                if over_wip {
+                    Style::default().fg(theme::RED).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme::TEXT_DIM)
+                },
             ),
         ]);
+        let title_line = Line::from(title_spans);
 
-        let block = Block::default()
+        let mut block = Block::default()
             .title(title_line)
             .borders(Borders::ALL)
             .border_style(border_style)
             .padding(Padding::horizontal(1));
 
-        let inner = block.inner(col_areas[i]);
-        f.render_widget(block, col_areas[i]);
+        // Checkbox rollup across the column's tasks, in the bottom border
+        let (checked, total) = shown
+            .iter()
+            .map(|t| count_checkboxes(&t.body))
+            .fold((0, 0), |(c, t), (checked, total)| (c + checked, t + total));
+        if total > 0 {
+            let color = if checked == total { theme::GREEN } else { theme::TEXT_DIM };
+            block = block.title_bottom(
+                Line::from(Span::styled(
+                    format!(" {} ", format_progress(checked, total)),
+                    Style::default().fg(color),
+                ))
+                .right_aligned(),
+            );
+        }
+
+        let inner = block.inner(col_area);
+        f.render_widget(block, col_area);
 
         if col.tasks.is_empty() {
-            let empty = Paragraph::new("No tasks")
-                .style(Style::default().fg(theme::TEXT_DIM));
-            f.render_widget(empty, inner);
+            let hint = if is_selected {
+                format!("Press n, or add a .md file to tasks/{}/", col.name)
+            } else {
+                String::new()
+            };
+            render_empty_state(f, inner, "No tasks", &hint);
+            continue;
+        }
+
+        if shown.is_empty() {
+            render_empty_state(f, inner, "No matching tasks", "");
             continue;
         }
 
-        let selected_row = app.board_row.get(i).copied().unwrap_or(0);
+        // The selected row indexes the whole column; find it among the shown cards
+        let selected = selected_position(app, i);
 
         // Render task cards
-        render_task_list(f, &col.tasks, selected_row, is_selected, inner);
+        let manual = app.board_scroll.get(i).copied().flatten();
+        render_task_list(f, app, &shown, selected, is_selected, manual, inner);
+    }
+}
+
+/// Where the board's columns go within the board area.
+struct ColumnLayout {
+    /// On-screen columns by index, left to right
+    columns: Vec<(usize, Rect)>,
+    /// Count of columns past --max-columns and the strip standing in for them
+    overflow: Option<(usize, Rect)>,
+}
+
+fn column_layout(app: &App, area: Rect) -> ColumnLayout {
+    let mut visible = app.on_screen_columns(area.width);
+    if visible.is_empty() {
+        return ColumnLayout {
+            columns: Vec::new(),
+            overflow: None,
+        };
+    }
+    // Narrow terminals get the selected column alone; h/l still step through
+    if app.is_narrow(area.width) {
+        let selected = visible
+            .iter()
+            .copied()
+            .find(|&i| i == app.board_col)
+            .unwrap_or(visible[0]);
+        visible = vec![selected];
+    }
+
+    // Columns past --max-columns collapse into a strip on the right
+    let overflow = app.overflow_columns(area.width);
+    let (area, overflow) = if overflow.is_empty() {
+        (area, None)
+    } else {
+        let [columns, strip] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(OVERFLOW_WIDTH)]).areas(area);
+        (columns, Some((overflow.len(), strip)))
+    };
+
+    // Split area by the configured column weights (equal by default)
+    let weights = app.column_weights(&visible);
+    let total: u32 = weights.iter().sum();
+    let constraints: Vec<Constraint> = weights
+        .iter()
+        .map(|&w| Constraint::Ratio(w, total))
+        .collect();
+    let areas = Layout::horizontal(constraints).split(area);
+    ColumnLayout {
+        columns: visible.into_iter().zip(areas.iter().copied()).collect(),
+        overflow,
+    }
+}
+
+/// Column `col`'s task list area when the board fills `area`, if the
+/// column is on screen.
+fn task_list_area(app: &App, col: usize, area: Rect) -> Option<Rect> {
+    let (_, col_area) = column_layout(app, area)
+        .columns
+        .into_iter()
+        .find(|&(i, _)| i == col)?;
+    // Same borders and padding as the column block drawn in render_board
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1));
+    Some(block.inner(col_area))
+}
+
+/// Board column under the terminal cell `(x, y)` when the board fills `area`.
+pub fn column_at(app: &App, area: Rect, x: u16, y: u16) -> Option<usize> {
+    column_layout(app, area)
+        .columns
+        .into_iter()
+        .find(|(_, r)| r.contains(ratatui::layout::Position::new(x, y)))
+        .map(|(i, _)| i)
+}
+
+/// Scroll column `col` by `delta` cards when the board fills `area`,
+/// starting from what is on screen. Returns the new first card, or None
+/// when the column is off screen or empty.
+pub fn scrolled_start(app: &App, col: usize, area: Rect, delta: isize) -> Option<usize> {
+    let list = task_list_area(app, col, area)?;
+    let board = app.board.as_ref()?;
+    let shown: Vec<&Task> = board.columns[col].tasks.iter().filter(|t| app.matches_filter(t)).collect();
+    if shown.is_empty() || list.height == 0 {
+        return None;
     }
+    let is_active = col == app.board_col && app.overlay.is_none() && app.focus == Focus::Content;
+    let selected = selected_position(app, col);
+    let manual = app.board_scroll.get(col).copied().flatten();
+    let heights = card_heights(app, &shown, selected, is_active, list.width);
+    let current = scroll_window(&heights, selected, list.height, manual).start;
+    // Clamp through scroll_window so the stored start is one that can show
+    let wanted = current.saturating_add_signed(delta);
+    Some(scroll_window(&heights, selected, list.height, Some(wanted)).start)
+}
+
+/// Position of column `col`'s selected task among its shown tasks.
+fn selected_position(app: &App, col: usize) -> usize {
+    let selected_row = app.board_row.get(col).copied().unwrap_or(0);
+    app.shown_rows(col)
+        .iter()
+        .position(|&r| r == selected_row)
+        .unwrap_or(0)
+}
+
+/// The "+N more" indicator for columns beyond --max-columns.
+fn render_overflow(f: &mut Frame, hidden: usize, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_COLOR));
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("+{hidden} more"),
+            Style::default()
+                .fg(theme::TEXT_SECONDARY)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled("l ›", Style::default().fg(theme::TEXT_DIM))),
+    ];
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let top = Rect {
+        y: inner.y + inner.height.saturating_sub(2) / 2,
+        height: inner.height.min(2),
+        ..inner
+    };
+    f.render_widget(Paragraph::new(lines).centered(), top);
 }
 
 fn render_task_list(
     f: &mut Frame,
-    tasks: &[Task],
+    app: &App,
+    tasks: &[&Task],
     selected: usize,
     col_is_active: bool,
+    manual: Option<usize>,
     area: Rect,
 ) {
-    // Each card takes 3 lines (title, meta, separator)
-    let card_height = 3u16;
-    let visible_cards = (area.height / card_height).max(1) as usize;
-
-    // Scroll offset to keep selected visible
-    let offset = if selected >= visible_cards {
-        selected - visible_cards + 1
-    } else {
-        0
+    if area.height == 0 {
+        return;
+    }
+    let heights = card_heights(app, tasks, selected, col_is_active, area.width);
+    let selected = selected.min(tasks.len().saturating_sub(1));
+    let window = scroll_window(&heights, selected, area.height, manual);
+
+    // Counts of cards scrolled out of view, each on its own reserved row
+    let mut indicator = |text: String, y: u16| {
+        let line = Line::from(Span::styled(text, Style::default().fg(theme::TEXT_DIM))).right_aligned();
+        f.render_widget(Paragraph::new(line), Rect::new(area.x, y, area.width, 1));
     };
-
     let mut y = area.y;
-    for (i, task) in tasks.iter().enumerate().skip(offset) {
-        if y + card_height > area.y + area.height {
-            break;
-        }
+    if window.start > 0 {
+        indicator(format!("▲{}", window.start), y);
+        y += 1;
+    }
+    if window.end < tasks.len() {
+        indicator(format!("▼{}", tasks.len() - window.end), area.bottom() - 1);
+    }
 
+    for i in window {
+        let card_height = heights[i];
         let is_selected = i == selected && col_is_active;
-        render_task_card(f, task, is_selected, Rect::new(area.x, y, area.width, card_height));
+        render_task_card(f, app, tasks[i], is_selected, Rect::new(area.x, y, area.width, card_height));
         y += card_height;
     }
 }
 
-fn render_task_card(f: &mut Frame, task: &Task, is_selected: bool, area: Rect) {
+/// Each card takes 3 lines (title, meta, separator); the selected card in
+/// the active column grows by one line when its title needs wrapping.
+fn card_heights(app: &App, tasks: &[&Task], selected: usize, col_is_active: bool, width: u16) -> Vec<u16> {
+    tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let (mark, watch) = card_markers(app, task);
+            card_height(task, i == selected && col_is_active, width, mark.width() + watch.width())
+        })
+        .collect()
+}
+
+/// Cards that fit in `height` rows, from the `manual` scroll position or
+/// else with `selected` visible. A manual start is clamped so the list
+/// doesn't scroll past its last card. A row is taken at the top when cards
+/// are hidden above and at the bottom when cards are hidden below, for the
+/// scroll indicators.
+fn scroll_window(
+    heights: &[u16],
+    selected: usize,
+    height: u16,
+    manual: Option<usize>,
+) -> std::ops::Range<usize> {
+    let (mut above, mut below) = (false, false);
+    loop {
+        let available = height.saturating_sub(above as u16 + below as u16);
+        let mut start = 0;
+        match manual {
+            Some(manual) => {
+                // Stop at the first start from which every remaining card fits
+                while start < manual.min(heights.len().saturating_sub(1))
+                    && heights[start..].iter().sum::<u16>() > available
+                {
+                    start += 1;
+                }
+            }
+            None => {
+                while start < selected && heights[start..=selected].iter().sum::<u16>() > available {
+                    start += 1;
+                }
+            }
+        }
+        let mut end = start;
+        let mut used = 0;
+        while end < heights.len() && used + heights[end] <= available {
+            used += heights[end];
+            end += 1;
+        }
+        // Reserving a row can only hide more cards, so this settles quickly
+        let hidden = (above || start > 0, below || end < heights.len());
+        if hidden == (above, below) {
+            return start..end;
+        }
+        (above, below) = hidden;
+    }
+}
+
+/// `prefix_width` is what the mark and watch markers take from the title.
+fn card_height(task: &Task, expanded: bool, width: u16, prefix_width: usize) -> u16 {
+    let title_width = width.saturating_sub(2 + prefix_width as u16) as usize;
+    if expanded && task.display_title().width() > title_width {
+        4
+    } else {
+        3
+    }
+}
+
+/// Marked cards lead with a check and watched cards with an eye marker,
+/// each taking two columns from the title.
+fn card_markers(app: &App, task: &Task) -> (&'static str, &'static str) {
+    let mark = if app.is_marked(task) { "✓ " } else { "" };
+    let watch = if app.is_watched(task) { "◉ " } else { "" };
+    (mark, watch)
+}
+
+fn render_task_card(f: &mut Frame, app: &App, task: &Task, is_selected: bool, area: Rect) {
     if area.height < 2 {
         return;
     }
 
-    let title = if task.meta.title.is_empty() {
-        &task.filename
+    let title = task.display_title();
+    let (mark, watch) = card_markers(app, task);
+    let prefix_width = mark.width() + watch.width();
+    let title_width = area.width.saturating_sub(2 + prefix_width as u16) as usize;
+    // Cards taller than the standard 3 lines have a wrapped two-line title
+    let title_rows = if area.height >= 4 { 2 } else { 1 };
+
+    // Age mode marks every card, so the selected one keeps a wider bar and a
+    // shaded title
+    let (indicator, indicator_color) = if app.age_colors {
+        let color = age_color(task_age_days(task, chrono::Local::now().naive_local()), app.age_days);
+        (if is_selected { "▌" } else { "▎" }, color)
     } else {
-        &task.meta.title
+        (if is_selected { "▌" } else { " " }, theme::TAB_ACTIVE_FG)
     };
 
-    let indicator = if is_selected { "▌" } else { " " };
-
     // Line 1: indicator + title
-    let title_style = if is_selected {
+    let title_style = if is_selected && app.age_colors {
+        Style::default()
+            .fg(theme::TEXT_PRIMARY)
+            .bg(theme::SURFACE_1)
+            .add_modifier(Modifier::BOLD)
+    } else if is_selected {
         Style::default()
             .fg(theme::TEXT_PRIMARY)
             .add_modifier(Modifier::BOLD)
@@ -145,62 +427,130 @@ fn render_task_card(f: &mut Frame, task: &Task, is_selected: bool, area: Rect) {
         Style::default().fg(theme::TEXT_PRIMARY)
     };
 
-    let title_line = Line::from(vec![
-        Span::styled(indicator, Style::default().fg(theme::TAB_ACTIVE_FG)),
-        Span::styled(truncate(title, area.width.saturating_sub(2) as usize), title_style),
+    let (first, rest) = if title_rows == 2 {
+        let (first, rest) = wrap_once(title, title_width);
+        (first.to_string(), Some(truncate(rest, title_width)))
+    } else {
+        (truncate(title, title_width), None)
+    };
+
+    let mut title_line = Line::from(vec![
+        Span::styled(indicator, Style::default().fg(indicator_color)),
+        Span::styled(mark, Style::default().fg(theme::GREEN).add_modifier(Modifier::BOLD)),
+        Span::styled(watch, Style::default().fg(theme::TAB_ACTIVE_FG)),
+        Span::styled(first, title_style),
     ]);
+    let search = app
+        .board_search
+        .as_deref()
+        .filter(|_| app.is_board_match(task));
+    if let Some(query) = search {
+        highlight_matches(std::slice::from_mut(&mut title_line), query);
+    }
     f.render_widget(
         Paragraph::new(title_line),
         Rect::new(area.x, area.y, area.width, 1),
     );
+    if let Some(rest) = rest {
+        let continuation = Line::from(vec![
+            Span::styled(indicator, Style::default().fg(indicator_color)),
+            Span::raw(" ".repeat(prefix_width)),
+            Span::styled(rest, title_style),
+        ]);
+        f.render_widget(
+            Paragraph::new(continuation),
+            Rect::new(area.x, area.y + 1, area.width, 1),
+        );
+    }
+    let area = Rect::new(area.x, area.y + title_rows - 1, area.width, area.height - (title_rows - 1));
 
-    // Line 2: metadata (assignee, scopes, progress, due)
+    // Line 2: metadata (id, priority, status, assignee, scopes, progress, due)
     if area.height >= 2 {
-        let mut meta_spans = vec![Span::raw(" ")];
+        let mut fields: Vec<(CardField, Vec<Span<'static>>)> = Vec::new();
+
+        let id = short_id(&task.meta.id);
+        if !id.is_empty() {
+            fields.push((
+                CardField::Id,
+                vec![Span::styled(format!("#{id}"), Style::default().fg(theme::TEXT_DIM))],
+            ));
+        }
+
+        if let Some((marker, color)) = priority_marker(task) {
+            fields.push((
+                CardField::Priority,
+                vec![Span::styled(
+                    marker,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                )],
+            ));
+        }
+
+        if let Some(status) = task.meta.status() {
+            fields.push((CardField::Status, vec![status_pill(app, status)]));
+        }
 
-        // Assignee
         if !task.meta.assignee.is_empty() {
-            meta_spans.push(Span::styled(
-                format!("@{}", task.meta.assignee),
-                Style::default().fg(theme::TEXT_SECONDARY),
+            fields.push((
+                CardField::Assignee,
+                vec![Span::styled(
+                    format!("@{}", task.meta.assignee),
+                    Style::default().fg(theme::TEXT_SECONDARY),
+                )],
             ));
-            meta_spans.push(Span::raw(" "));
         }
 
-        // Scopes
         let scopes = task.meta.scopes.as_vec();
-        for scope in scopes.iter().take(2) {
-            meta_spans.push(Span::styled(
-                format!("[{scope}]"),
-                Style::default().fg(theme::SCOPE_FG),
-            ));
-            meta_spans.push(Span::raw(" "));
+        if !scopes.is_empty() {
+            let mut spans = Vec::new();
+            for (i, scope) in scopes.iter().take(2).enumerate() {
+                if i > 0 {
+                    spans.push(Span::raw(" "));
+                }
+                spans.push(Span::styled(
+                    format!("[{scope}]"),
+                    Style::default().fg(app.scope_color(scope)),
+                ));
+            }
+            fields.push((CardField::Scopes, spans));
         }
 
-        // Checkbox progress
         let (checked, total) = count_checkboxes(&task.body);
         if total > 0 {
-            meta_spans.push(Span::styled(
-                format_progress(checked, total),
-                Style::default().fg(if checked == total {
-                    theme::GREEN
-                } else {
-                    theme::YELLOW
-                }),
+            fields.push((
+                CardField::Progress,
+                vec![Span::styled(
+                    format_progress(checked, total),
+                    Style::default().fg(if checked == total {
+                        theme::GREEN
+                    } else {
+                        theme::YELLOW
+                    }),
+                )],
             ));
-            meta_spans.push(Span::raw(" "));
         }
 
-        // Due date
         if !task.meta.due.is_empty() {
-            meta_spans.push(Span::styled(
-                format!("due:{}", task.meta.due),
-                Style::default().fg(theme::TEXT_DIM),
+            fields.push((
+                CardField::Due,
+                vec![Span::styled(
+                    format!("due:{}", app.format_date(&task.meta.due)),
+                    Style::default().fg(due_color(&task.meta.due)),
+                )],
             ));
         }
 
+        let mut meta_line = Line::from(fit_card_meta(
+            fields,
+            area.width as usize,
+            app.card_meta,
+            &app.card_drop_order,
+        ));
+        if let Some(query) = search {
+            highlight_matches(std::slice::from_mut(&mut meta_line), query);
+        }
         f.render_widget(
-            Paragraph::new(Line::from(meta_spans)),
+            Paragraph::new(meta_line),
             Rect::new(area.x, area.y + 1, area.width, 1),
         );
     }
@@ -215,6 +565,93 @@ fn render_task_card(f: &mut Frame, task: &Task, is_selected: bool, area: Rect) {
     }
 }
 
+/// A task `id` short enough for a card: the first 7 characters of a string
+/// id (UUIDs, hashes), numbers as they are, nothing for null or no id.
+pub fn short_id(id: &Option<serde_json::Value>) -> String {
+    match id {
+        Some(serde_json::Value::String(s)) => s.trim().chars().take(7).collect(),
+        Some(serde_json::Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// A task status as a pill in its configured color.
+pub fn status_pill(app: &App, status: &str) -> Span<'static> {
+    Span::styled(
+        format!(" {status} "),
+        Style::default().fg(theme::HEADER_BG).bg(app.status_color(status)),
+    )
+}
+
+/// `!!!`/`!!`/`!` and its color for a task with a recognized priority.
+pub fn priority_marker(task: &Task) -> Option<(&'static str, Color)> {
+    match task.meta.priority_level()? {
+        3 => Some(("!!!", theme::RED)),
+        2 => Some(("!!", theme::YELLOW)),
+        _ => Some(("!", theme::TEXT_DIM)),
+    }
+}
+
+/// How a due date relates to today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueStatus {
+    Overdue,
+    Today,
+    Upcoming,
+    /// Empty or unparseable due string
+    Unknown,
+}
+
+/// Parse the leading `YYYY-MM-DD` of a date or datetime string.
+pub fn parse_date(s: &str) -> Option<NaiveDate> {
+    let date = s.trim().get(..10)?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+pub fn due_status(due: &str, today: NaiveDate) -> DueStatus {
+    match parse_date(due) {
+        Some(date) if date < today => DueStatus::Overdue,
+        Some(date) if date == today => DueStatus::Today,
+        Some(_) => DueStatus::Upcoming,
+        None => DueStatus::Unknown,
+    }
+}
+
+/// Color for a due badge: red when overdue, yellow when due today, dim otherwise.
+pub fn due_color(due: &str) -> Color {
+    match due_status(due, chrono::Local::now().date_naive()) {
+        DueStatus::Overdue => theme::RED,
+        DueStatus::Today => theme::YELLOW,
+        DueStatus::Upcoming | DueStatus::Unknown => theme::TEXT_DIM,
+    }
+}
+
+/// Days since the task's `updated` timestamp, or `created` without one.
+pub fn task_age_days(task: &Task, now: chrono::NaiveDateTime) -> Option<f64> {
+    let updated = task
+        .meta
+        .extra
+        .get("updated")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty());
+    let changed = dates::parse_local(updated.unwrap_or(&task.meta.created))?;
+    Some((now - changed).num_seconds() as f64 / 86_400.0)
+}
+
+/// Green up to `fresh` days, blending through yellow to red at `stale`.
+/// Tasks without a date stay dim.
+pub fn age_color(age_days: Option<f64>, (fresh, stale): (u32, u32)) -> Color {
+    let Some(age) = age_days else {
+        return theme::TEXT_DIM;
+    };
+    let t = (age - fresh as f64) / (stale - fresh).max(1) as f64;
+    if t < 0.5 {
+        theme::lerp(theme::GREEN, theme::YELLOW, t * 2.0)
+    } else {
+        theme::lerp(theme::YELLOW, theme::RED, t * 2.0 - 1.0)
+    }
+}
+
 pub fn count_checkboxes(body: &str) -> (usize, usize) {
     let mut checked = 0;
     let mut total = 0;
@@ -235,11 +672,7 @@ pub fn format_progress(checked: usize, total: usize) -> String {
         return String::new();
     }
     let bar_width = 8;
-    let filled = if total > 0 {
-        (checked * bar_width) / total
-    } else {
-        0
-    };
+    let filled = (checked * bar_width) / total;
     let empty = bar_width - filled;
     format!(
         "[{}{}] {}/{}",
@@ -250,12 +683,267 @@ pub fn format_progress(checked: usize, total: usize) -> String {
     )
 }
 
-fn truncate(s: &str, max_width: usize) -> String {
-    if s.len() <= max_width {
-        s.to_string()
-    } else if max_width > 3 {
-        format!("{}...", &s[..max_width - 3])
+/// Truncate to a display width, marking the cut with `...`.
+pub fn truncate(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width > 3 {
+        format!("{}...", take_width(s, max_width - 3))
     } else {
-        s[..max_width].to_string()
+        take_width(s, max_width).to_string()
+    }
+}
+
+/// Longest prefix of `s` that fits in `max_width` display columns. Prefixes
+/// are measured whole, since emoji with variation selectors or joiners are
+/// narrower or wider than their chars added up.
+fn take_width(s: &str, max_width: usize) -> &str {
+    for (i, c) in s.char_indices() {
+        if s[..i + c.len_utf8()].width() > max_width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// Lay out card metadata fields on one line of `width` columns. Truncating
+/// keeps fields in order and marks the hidden rest with `…`; dropping leaves
+/// out whole fields in `drop_order` until the rest fits. Either way a field
+/// that is too wide on its own is cut.
+fn fit_card_meta(
+    mut fields: Vec<(CardField, Vec<Span<'static>>)>,
+    width: usize,
+    policy: CardMetaPolicy,
+    drop_order: &[CardField],
+) -> Vec<Span<'static>> {
+    // A leading space, then the fields separated by spaces
+    let line_width = |fields: &[(CardField, Vec<Span<'static>>)]| -> usize {
+        1 + fields
+            .iter()
+            .map(|(_, spans)| spans.iter().map(|s| s.width()).sum::<usize>() + 1)
+            .sum::<usize>()
+            .saturating_sub(1)
+    };
+
+    let mut hidden = false;
+    match policy {
+        CardMetaPolicy::Truncate => {
+            while line_width(&fields) > width || (hidden && line_width(&fields) + 2 > width) {
+                if fields.pop().is_none() {
+                    break;
+                }
+                hidden = true;
+            }
+        }
+        CardMetaPolicy::Drop => {
+            for kind in drop_order {
+                if line_width(&fields) <= width {
+                    break;
+                }
+                fields.retain(|(k, _)| k != kind);
+            }
+        }
+    }
+
+    let mut spans = vec![Span::raw(" ")];
+    for (i, (_, field)) in fields.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.extend(field);
+    }
+    if hidden {
+        let marker = if spans.len() > 1 { " …" } else { "…" };
+        spans.push(Span::styled(marker, Style::default().fg(theme::TEXT_DIM)));
+    }
+    truncate_spans(spans, width)
+}
+
+/// Cut `spans` to `max_width` display columns, marking the cut with `...`
+/// in the style of the span it falls in.
+pub fn truncate_spans(spans: Vec<Span<'static>>, max_width: usize) -> Vec<Span<'static>> {
+    let total: usize = spans.iter().map(|s| s.width()).sum();
+    if total <= max_width {
+        return spans;
+    }
+    let mut out = Vec::new();
+    let mut used = 0;
+    let budget = max_width.saturating_sub(3);
+    for span in spans {
+        let width = span.width();
+        if used + width <= budget {
+            used += width;
+            out.push(span);
+            continue;
+        }
+        let head = take_width(&span.content, budget - used).to_string();
+        out.push(Span::styled(head + &".".repeat(max_width.min(3)), span.style));
+        break;
+    }
+    out
+}
+
+/// Split `s` into a first line fitting `width` and the remainder, breaking at
+/// the last space when possible.
+fn wrap_once(s: &str, width: usize) -> (&str, &str) {
+    let head = take_width(s, width);
+    if head.len() == s.len() {
+        return (s, "");
+    }
+    match head.rfind(' ') {
+        Some(space) if space > 0 => (&s[..space], s[space + 1..].trim_start()),
+        _ => (head, &s[head.len()..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_status_relative_to_today() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        assert_eq!(due_status("2024-06-14", today), DueStatus::Overdue);
+        assert_eq!(due_status("2024-06-15", today), DueStatus::Today);
+        assert_eq!(due_status("2024-06-15T09:00:00", today), DueStatus::Today);
+        assert_eq!(due_status("2024-07-01", today), DueStatus::Upcoming);
+        assert_eq!(due_status("", today), DueStatus::Unknown);
+        assert_eq!(due_status("next week", today), DueStatus::Unknown);
+    }
+
+    #[test]
+    fn scroll_window_reserves_rows_for_indicators() {
+        // Everything fits: no indicator rows
+        assert_eq!(scroll_window(&[3, 3, 3], 0, 9, None), 0..3);
+        // Cards hidden below take the last row
+        assert_eq!(scroll_window(&[3, 3, 3, 3], 0, 9, None), 0..2);
+        assert_eq!(scroll_window(&[3, 3, 3, 3], 1, 9, None), 0..2);
+        // Scrolled to the end: only the top row is reserved
+        assert_eq!(scroll_window(&[3, 3, 3, 3], 3, 9, None), 2..4);
+        // Hidden on both sides
+        assert_eq!(scroll_window(&[3, 3, 3, 3, 3], 2, 9, None), 1..3);
+        // A tiny column still shows the selected card's start
+        assert_eq!(scroll_window(&[3, 3], 1, 2, None), 1..1);
+    }
+
+    #[test]
+    fn manual_scroll_ignores_selection_but_stops_at_the_end() {
+        // Peeking ahead leaves the selected first card out of view
+        assert_eq!(scroll_window(&[3, 3, 3, 3, 3], 0, 9, Some(1)), 1..3);
+        // Past the end clamps to the last full window
+        assert_eq!(scroll_window(&[3, 3, 3, 3, 3], 0, 9, Some(9)), 3..5);
+        assert_eq!(scroll_window(&[3, 3, 3], 2, 9, Some(2)), 0..3);
+    }
+
+    #[test]
+    fn truncate_uses_display_width() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("abcdefghij", 8), "abcde...");
+        // Each CJK char is two columns wide
+        assert_eq!(truncate("日本語のタイトル", 9), "日本語...");
+        assert_eq!(truncate("日本語のタイトル", 8), "日本...");
+        assert_eq!(truncate("héllo wörld", 8), "héllo...");
+    }
+
+    #[test]
+    fn card_meta_truncates_or_drops_whole_fields() {
+        let field = |kind, text: &str| (kind, vec![Span::raw(text.to_string())]);
+        let fields = || {
+            vec![
+                field(CardField::Assignee, "@alice"),
+                field(CardField::Scopes, "[auth] [web]"),
+                field(CardField::Due, "due:06-01"),
+            ]
+        };
+        let text = |spans: Vec<Span>| spans.iter().map(|s| s.content.to_string()).collect::<String>();
+        let order = crate::app::DEFAULT_CARD_DROP_ORDER;
+
+        // " @alice [auth] [web] due:06-01" is 30 columns
+        assert_eq!(text(fit_card_meta(fields(), 30, CardMetaPolicy::Truncate, &order)).len(), 30);
+        assert_eq!(
+            text(fit_card_meta(fields(), 25, CardMetaPolicy::Truncate, &order)),
+            " @alice [auth] [web] …"
+        );
+        assert_eq!(
+            text(fit_card_meta(fields(), 25, CardMetaPolicy::Drop, &order)),
+            " @alice [auth] [web]"
+        );
+        assert_eq!(
+            text(fit_card_meta(fields(), 20, CardMetaPolicy::Drop, &[CardField::Scopes])),
+            " @alice due:06-01"
+        );
+        assert_eq!(text(fit_card_meta(fields(), 6, CardMetaPolicy::Truncate, &order)), " …");
+    }
+
+    #[test]
+    fn card_height_leaves_room_for_markers() {
+        let task: Task = serde_json::from_value(serde_json::json!({
+            "filename": "001.md", "column": "todo", "meta": {"title": "ten chars!"}, "body": "",
+        }))
+        .unwrap();
+        assert_eq!(card_height(&task, true, 12, 0), 3);
+        assert_eq!(card_height(&task, true, 12, 2), 4);
+        assert_eq!(card_height(&task, false, 12, 2), 3);
+    }
+
+    #[test]
+    fn card_age_prefers_updated_and_maps_to_colors() {
+        let task: Task = serde_json::from_value(serde_json::json!({
+            "filename": "a.md",
+            "meta": {"created": "2024-05-01", "updated": "2024-05-29T12:00:00"}
+        }))
+        .unwrap();
+        let now = dates::parse_local("2024-06-01 12:00").unwrap();
+        assert_eq!(task_age_days(&task, now), Some(3.0));
+        let undated: Task = serde_json::from_value(serde_json::json!({"filename": "b.md"})).unwrap();
+        assert_eq!(task_age_days(&undated, now), None);
+
+        let color = |age| age_color(age, (7, 31));
+        assert_eq!(color(Some(3.0)), theme::GREEN);
+        assert_eq!(color(Some(19.0)), theme::YELLOW);
+        assert_eq!(color(Some(90.0)), theme::RED);
+        assert_eq!(color(None), theme::TEXT_DIM);
+    }
+
+    #[test]
+    fn short_ids_cut_strings_and_keep_numbers() {
+        let id = |v: serde_json::Value| short_id(&Some(v));
+        assert_eq!(id(serde_json::json!("3f2a9c1e-77b0-4d1a-9f0e-1c2d3e4f5a6b")), "3f2a9c1");
+        assert_eq!(id(serde_json::json!("abc")), "abc");
+        assert_eq!(id(serde_json::json!(1234567890)), "1234567890");
+        assert_eq!(id(serde_json::Value::Null), "");
+        assert_eq!(short_id(&None), "");
+    }
+
+    #[test]
+    fn widths_count_emoji_sequences_as_rendered() {
+        // Heart + VS16 and a ZWJ sequence each render as one wide glyph
+        assert_eq!(truncate("❤️ fix 👩‍💻 ok", 20), "❤️ fix 👩‍💻 ok");
+        assert_eq!(truncate("❤️ fix 👩‍💻 ok", 9), "❤️ fix...");
+        assert_eq!(truncate("👩‍💻👩‍💻👩‍💻", 5), "👩‍💻...");
+        assert_eq!(take_width("ab🎉cd", 3), "ab");
+        assert_eq!(take_width("ab🎉cd", 4), "ab🎉");
+
+        let spans = vec![
+            Span::raw("@ユーザー "),
+            Span::styled("[auth]", Style::default().fg(theme::RED)),
+        ];
+        let cut = truncate_spans(spans.clone(), 14);
+        assert_eq!(cut.iter().map(|s| s.width()).sum::<usize>(), 14);
+        assert_eq!(cut[0].content, "@ユーザー ");
+        assert_eq!(cut[1].content, "[...");
+        assert_eq!(cut[1].style.fg, Some(theme::RED));
+        let cut = truncate_spans(spans.clone(), 6);
+        assert_eq!(cut.len(), 1);
+        assert_eq!(cut[0].content, "@ユ...");
+        assert_eq!(truncate_spans(spans, 16).len(), 2);
+    }
+
+    #[test]
+    fn wrap_once_breaks_at_space() {
+        assert_eq!(wrap_once("fix the login bug", 10), ("fix the", "login bug"));
+        assert_eq!(wrap_once("averyveryverylongword", 6), ("averyv", "eryverylongword"));
+        assert_eq!(wrap_once("fits", 10), ("fits", ""));
     }
 }
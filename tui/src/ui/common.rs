@@ -1,11 +1,12 @@
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Modifier, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap};
 
-use crate::app::{App, Overlay};
+use crate::app::{App, ConnectionState, Overlay};
 use crate::theme;
+use crate::ui::markdown::markdown_to_lines;
 
 /// Create a centered overlay area.
 pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
@@ -24,6 +25,192 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     .split(popup_layout[1])[1]
 }
 
+/// Render a vertically centered empty-state message with an optional hint below it.
+pub fn render_empty_state(f: &mut Frame, area: Rect, title: &str, hint: &str) {
+    let mut lines = vec![Line::from(Span::styled(
+        title.to_string(),
+        Style::default()
+            .fg(theme::TEXT_SECONDARY)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    if !hint.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            hint.to_string(),
+            Style::default().fg(theme::TEXT_DIM),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).centered().wrap(Wrap { trim: true });
+    let height = (paragraph.line_count(area.width) as u16).min(area.height);
+    let area = Rect::new(area.x, area.y + (area.height - height) / 2, area.width, height);
+    f.render_widget(paragraph, area);
+}
+
+/// Create a centered area with a fixed height in rows.
+pub fn centered_rect_fixed(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let height = height.min(area.height);
+    let row = Rect::new(area.x, area.y + (area.height - height) / 2, area.width, height);
+    centered_rect(percent_x, 100, row)
+}
+
+pub fn render_input_prompt(f: &mut Frame, app: &App) {
+    let Some(prompt) = &app.input else {
+        return;
+    };
+    let area = centered_rect_fixed(60, 3, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" {} ", prompt.label),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::horizontal(1));
+
+    let inner = block.inner(area);
+    let paragraph = Paragraph::new(Span::styled(
+        prompt.input.value.clone(),
+        Style::default().fg(theme::TEXT_PRIMARY),
+    ))
+    .block(block);
+    f.render_widget(paragraph, area);
+
+    let cursor_x = inner.x + (prompt.input.cursor_column() as u16).min(inner.width.saturating_sub(1));
+    f.set_cursor_position((cursor_x, inner.y));
+}
+
+/// The confirmation overlay: `message` and the y/n choice.
+pub fn render_confirm(f: &mut Frame, message: &str) {
+    let area = centered_rect_fixed(50, 5, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            " Confirm ",
+            Style::default().fg(theme::YELLOW).add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Line::from(Span::styled(
+            " y confirm · n cancel ",
+            Style::default().fg(theme::TEXT_DIM),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::YELLOW))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::new(1, 1, 1, 0));
+    let paragraph = Paragraph::new(Span::styled(
+        message.to_string(),
+        Style::default().fg(theme::TEXT_PRIMARY),
+    ))
+    .wrap(Wrap { trim: true })
+    .block(block);
+    f.render_widget(paragraph, area);
+}
+
+/// Full-screen placeholder shown until the server sends its first data:
+/// where we are connecting to, a spinner and the last error.
+pub fn render_connecting(f: &mut Frame, app: &App) {
+    const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let spinner = SPINNER[(millis / 100) as usize % SPINNER.len()];
+
+    let target = if app.server_url.is_empty() {
+        "(no server found)"
+    } else {
+        app.server_url.as_str()
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "mdboard",
+            Style::default()
+                .fg(theme::TAB_ACTIVE_FG)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(format!("{spinner} Connecting to "), Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(target.to_string(), Style::default().fg(theme::TEXT_PRIMARY)),
+        ]),
+    ];
+    if let Some(error) = &app.connect_error {
+        lines.push(Line::from(""));
+        lines.extend(error.lines().map(|line| {
+            Line::from(Span::styled(line.to_string(), Style::default().fg(theme::RED)))
+        }));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "r retry · q quit",
+        Style::default().fg(theme::TEXT_DIM),
+    )));
+
+    let area = centered_rect(70, 100, f.area());
+    let paragraph = Paragraph::new(lines).centered().wrap(Wrap { trim: true });
+    let height = (paragraph.line_count(area.width) as u16).min(area.height);
+    let area = Rect::new(area.x, area.y + (area.height - height) / 2, area.width, height);
+    f.render_widget(paragraph, area);
+}
+
+pub fn render_debug(f: &mut Frame, app: &App) {
+    let area = centered_rect_fixed(60, 12, f.area());
+    f.render_widget(Clear, area);
+
+    let connection = match app.connection {
+        ConnectionState::Connected => ("connected", theme::GREEN),
+        ConnectionState::Disconnected => ("disconnected", theme::RED),
+        ConnectionState::Connecting => ("connecting", theme::YELLOW),
+        ConnectionState::Offline => ("offline snapshot", theme::YELLOW),
+    };
+    let last_poll = match app.last_poll {
+        Some(at) => format!("{:.1}s ago", at.elapsed().as_secs_f64()),
+        None => "never".to_string(),
+    };
+
+    let mut lines = vec![
+        make_debug_line("Server", app.server_url.clone(), theme::TEXT_PRIMARY),
+        make_debug_line("Connection", connection.0.to_string(), connection.1),
+        make_debug_line("Last poll", last_poll, theme::TEXT_PRIMARY),
+        make_debug_line("SSE reconnects", app.sse_reconnects.to_string(), theme::TEXT_PRIMARY),
+        Line::from(""),
+    ];
+    match &app.poll_hashes {
+        Some(hashes) => {
+            lines.push(make_debug_line("Board hash", hashes.board.clone(), theme::TEXT_DIM));
+            lines.push(make_debug_line("Prompts hash", hashes.prompts.clone(), theme::TEXT_DIM));
+            lines.push(make_debug_line("Documents hash", hashes.documents.clone(), theme::TEXT_DIM));
+        }
+        None => lines.push(make_debug_line("Hashes", "none yet".to_string(), theme::TEXT_DIM)),
+    }
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            " Debug ",
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::new(2, 2, 1, 0));
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn make_debug_line(label: &str, value: String, color: Color) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{label:<16}"), Style::default().fg(theme::TEXT_SECONDARY)),
+        Span::styled(value, Style::default().fg(color)),
+    ])
+}
+
 pub fn render_help(f: &mut Frame, app: &App) {
     let area = centered_rect(60, 70, f.area());
     f.render_widget(Clear, area);
@@ -46,7 +233,14 @@ pub fn render_help(f: &mut Frame, app: &App) {
         make_help_line("1-4", "Switch view"),
         make_help_line("Tab / Shift+Tab", "Cycle views"),
         make_help_line("r", "Force refresh"),
+        make_help_line("R", "Reload server config (columns, scopes, colors)"),
+        make_help_line("Ctrl+P", "Quick open task/prompt/doc"),
+        make_help_line("Ctrl+R", "Recently opened items"),
+        make_help_line(":", "Command line (q, view, filter, goto, refresh, home)"),
         make_help_line("?", "Toggle this help"),
+        make_help_line("F12 / ~", "Connection debug info"),
+        make_help_line("Ctrl+Y", "Copy last API request as curl (from F12 on the board)"),
+        make_help_line("y", "Copy link to selected item"),
         Line::from(""),
         Line::from(Span::styled("Navigation", Style::default().fg(theme::TAB_ACTIVE_FG).add_modifier(Modifier::BOLD))),
         make_help_line("↑ at top of list", "Focus tab bar"),
@@ -55,14 +249,43 @@ pub fn render_help(f: &mut Frame, app: &App) {
         Line::from(""),
         Line::from(Span::styled("Board View", Style::default().fg(theme::TAB_ACTIVE_FG).add_modifier(Modifier::BOLD))),
         make_help_line("h/l / ←/→", "Move between columns"),
+        make_help_line("=", "Toggle keeping the row across columns"),
         make_help_line("j/k / ↓/↑", "Move between tasks"),
-        make_help_line("Space / Enter", "Open task detail"),
+        make_help_line("Enter", "Open task detail"),
+        make_help_line("z", "Open task full-screen (focus mode)"),
+        make_help_line("Space", "Mark task (Esc clears marks)"),
+        make_help_line("M <1-9>", "Move marked tasks to column"),
+        make_help_line("+", "Add scope to marked tasks"),
+        make_help_line("w", "Watch/unwatch task (notify on changes)"),
         make_help_line("g / G", "Jump to top/bottom"),
+        make_help_line("Ctrl+e / Ctrl+y", "Scroll column without moving the selection"),
+        make_help_line("I", "Jump to first incomplete task"),
+        make_help_line("} / {", "Next / previous task sharing a scope"),
+        make_help_line("i", "Copy the selected task's full id"),
+        make_help_line("m <1-9>", "Jump to column by number"),
+        make_help_line("f <letter>", "Jump to column by hint"),
+        make_help_line("J / K", "Move task down / up"),
+        make_help_line("x / X", "Hide column / show all"),
+        make_help_line("E", "Hide / show columns without tasks"),
+        make_help_line("!", "Jump to a column over its WIP limit"),
+        make_help_line("%", "Checklist progress of every task"),
+        make_help_line("H", "Color cards by age"),
+        make_help_line("l (last column)", "Pick a column beyond --max-columns"),
+        make_help_line("s", "Toggle manual / priority sort"),
+        make_help_line("n", "New task in column"),
+        make_help_line("a", "Assign task"),
+        make_help_line("S", "Edit task scopes (Space toggles)"),
+        make_help_line("D", "Delete task (asks first)"),
+        make_help_line("@ / Backspace", "Filter by assignee (cycle) / clear"),
+        make_help_line("t", "Filter by status (cycle)"),
+        make_help_line("/", "Find tasks on the board (Esc clears)"),
         Line::from(""),
         Line::from(Span::styled("List Views (Prompts/Documents/Activity)", Style::default().fg(theme::TAB_ACTIVE_FG).add_modifier(Modifier::BOLD))),
-        make_help_line("j/k / ↓/↑", "Move between items"),
+        make_help_line("j/k / ↓/↑", "Move between items (--wrap-lists wraps at the ends)"),
         make_help_line("Space / Enter", "Open detail"),
         make_help_line("g / G", "Jump to top/bottom"),
+        make_help_line("s", "Cycle sort: updated, created, title, revision (prompts/docs)"),
+        make_help_line("t / p / d", "Show/hide tasks, prompts, docs (activity)"),
         Line::from(""),
         Line::from(Span::styled("Overlays", Style::default().fg(theme::TAB_ACTIVE_FG).add_modifier(Modifier::BOLD))),
         make_help_line("Esc", "Close overlay"),
@@ -71,6 +294,23 @@ pub fn render_help(f: &mut Frame, app: &App) {
         make_help_line("Ctrl+u", "Page up"),
         make_help_line("g / G", "Jump to top/bottom"),
         make_help_line("[ / ]", "Browse revisions (prompts/docs)"),
+        make_help_line("/", "Find in item (n / N next / previous)"),
+        make_help_line("o", "Open first image"),
+        make_help_line("O", "Pick a link to open or copy"),
+        make_help_line("T", "Toggle contents panel (prompts/docs)"),
+        make_help_line("e", "Export task to a file"),
+        make_help_line("c / #", "Copy task body as GFM (# adds the title)"),
+        make_help_line("i", "Copy the full task id (tasks)"),
+        make_help_line("z", "Toggle focus mode (tasks)"),
+        make_help_line("C", "Collapse/expand comments (tasks)"),
+        make_help_line("L", "Toggle line numbers"),
+        make_help_line("R", "Toggle raw markdown (shows HTML comments and tags)"),
+        make_help_line("r", "Refresh this item (comments / revisions too)"),
+        make_help_line("Enter", "Expand/collapse section on screen"),
+        make_help_line("m", "Toggle inline / table metadata"),
+        make_help_line("V", "Select lines (j/k extend, y copy)"),
+        make_help_line("p", "Copy prompt body"),
+        make_help_line("P", "Run prompt through --prompt-command"),
     ];
 
     let block = Block::default()
@@ -93,6 +333,37 @@ pub fn render_help(f: &mut Frame, app: &App) {
     f.render_widget(paragraph, area);
 }
 
+/// Output of `--prompt-command`, rendered as markdown since that is what
+/// language models tend to answer in.
+pub fn render_command_output(f: &mut Frame, command: &str, output: &str, scroll: usize) {
+    let area = centered_rect(80, 85, f.area());
+    f.render_widget(Clear, area);
+
+    let lines = if output.trim().is_empty() {
+        vec![Line::from(Span::styled("(no output)", Style::default().fg(theme::TEXT_DIM)))]
+    } else {
+        markdown_to_lines(output)
+    };
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" {command} "),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::new(2, 2, 1, 1));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll as u16, 0));
+
+    f.render_widget(paragraph, area);
+}
+
 fn make_help_line(key: &str, desc: &str) -> Line<'static> {
     Line::from(vec![
         Span::styled(
@@ -0,0 +1,396 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph};
+
+use crate::app::{App, NEW_TASK_FIELDS, Overlay};
+use crate::hyperlink;
+use crate::theme;
+use crate::ui::board::format_progress;
+use crate::ui::common::centered_rect_fixed;
+
+pub fn render_new_task(f: &mut Frame, app: &App) {
+    let Some(Overlay::NewTask {
+        column,
+        fields,
+        focused,
+        error,
+    }) = &app.overlay
+    else {
+        return;
+    };
+
+    // Label + value per field, then a blank line and the hint/error line
+    let height = fields.len() as u16 * 2 + 5;
+    let area = centered_rect_fixed(60, height, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" New task — {column} "),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::new(2, 2, 1, 0));
+    let inner = block.inner(area);
+
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    for (i, (label, field)) in NEW_TASK_FIELDS.iter().zip(fields).enumerate() {
+        let label_style = if i == *focused {
+            Style::default()
+                .fg(theme::TAB_ACTIVE_FG)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme::TEXT_SECONDARY)
+        };
+        lines.push(Line::from(Span::styled(label.to_string(), label_style)));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", field.value),
+            Style::default().fg(theme::TEXT_PRIMARY),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(match error {
+        Some(msg) => Line::from(Span::styled(msg.clone(), Style::default().fg(theme::RED))),
+        None => Line::from(Span::styled(
+            "Tab next field · Enter create · Esc cancel",
+            Style::default().fg(theme::TEXT_DIM),
+        )),
+    });
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+
+    let field = &fields[*focused];
+    let cursor_x = inner.x + 2 + field.cursor_column() as u16;
+    let cursor_y = inner.y + *focused as u16 * 2 + 1;
+    if cursor_x < inner.x + inner.width && cursor_y < inner.y + inner.height {
+        f.set_cursor_position((cursor_x, cursor_y));
+    }
+}
+
+pub fn render_assignee_picker(f: &mut Frame, app: &App) {
+    let Some(Overlay::AssigneePicker {
+        filename,
+        query,
+        known,
+        options,
+        selected,
+        ..
+    }) = &app.overlay
+    else {
+        return;
+    };
+
+    let height = (options.len() as u16).clamp(1, 12) + 5;
+    let area = centered_rect_fixed(44, height, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" Assign {filename} "),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .title_bottom(Line::from(Span::styled(
+            " Enter assign · Esc cancel ",
+            Style::default().fg(theme::TEXT_DIM),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let [query_area, list_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("@ ", Style::default().fg(theme::TAB_ACTIVE_FG)),
+            Span::styled(query.value.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+        ])),
+        query_area,
+    );
+    f.set_cursor_position((query_area.x + 2 + query.cursor_column() as u16, query_area.y));
+
+    let items: Vec<ListItem> = options
+        .iter()
+        .map(|name| {
+            let line = if name.is_empty() {
+                Line::from(Span::styled("(unassigned)", Style::default().fg(theme::TEXT_DIM)))
+            } else if known.contains(name) {
+                Line::from(Span::styled(
+                    format!("@{name}"),
+                    Style::default().fg(theme::TEXT_PRIMARY),
+                ))
+            } else {
+                Line::from(vec![
+                    Span::styled(format!("@{name}"), Style::default().fg(theme::TEXT_PRIMARY)),
+                    Span::styled("  new", Style::default().fg(theme::GREEN)),
+                ])
+            };
+            ListItem::new(line)
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(*selected));
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(theme::SURFACE_1)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, list_area, &mut state);
+}
+
+pub fn render_scope_editor(f: &mut Frame, app: &App) {
+    let Some(Overlay::ScopeEditor {
+        filename,
+        query,
+        known,
+        chosen,
+        options,
+        selected,
+        ..
+    }) = &app.overlay
+    else {
+        return;
+    };
+
+    let height = (options.len() as u16).clamp(1, 12) + 5;
+    let area = centered_rect_fixed(44, height, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" Scopes of {filename} "),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .title_bottom(Line::from(Span::styled(
+            " Space toggle · Enter save · Esc cancel ",
+            Style::default().fg(theme::TEXT_DIM),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let [query_area, list_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("# ", Style::default().fg(theme::TAB_ACTIVE_FG)),
+            Span::styled(query.value.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+        ])),
+        query_area,
+    );
+    f.set_cursor_position((query_area.x + 2 + query.cursor_column() as u16, query_area.y));
+
+    let items: Vec<ListItem> = options
+        .iter()
+        .map(|scope| {
+            let on = chosen.iter().any(|s| s.eq_ignore_ascii_case(scope));
+            let mut spans = vec![
+                Span::styled(
+                    if on { "[x] " } else { "[ ] " },
+                    Style::default().fg(if on { theme::GREEN } else { theme::TEXT_DIM }),
+                ),
+                Span::styled(scope.clone(), Style::default().fg(app.scope_color(scope))),
+            ];
+            if !known.iter().any(|s| s.eq_ignore_ascii_case(scope)) {
+                spans.push(Span::styled("  new", Style::default().fg(theme::GREEN)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(*selected));
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(theme::SURFACE_1)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, list_area, &mut state);
+}
+
+pub fn render_progress(f: &mut Frame, app: &App) {
+    let Some(Overlay::Progress { rows, selected }) = &app.overlay else {
+        return;
+    };
+
+    let height = (rows.len() as u16).clamp(1, 20) + 2;
+    let area = centered_rect_fixed(70, height, f.area());
+    f.render_widget(Clear, area);
+
+    let done = rows.iter().filter(|r| r.checked == r.total).count();
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" Checklist progress ({done}/{} done) ", rows.len()),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .title_bottom(Line::from(Span::styled(
+            " Enter open · Esc close ",
+            Style::default().fg(theme::TEXT_DIM),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::horizontal(1));
+
+    let column_label = |name: &str| {
+        app.board
+            .iter()
+            .flat_map(|b| &b.columns)
+            .find(|c| c.name == name)
+            .map_or(name, |c| c.display_label())
+            .to_string()
+    };
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            let color = if row.checked == row.total {
+                theme::GREEN
+            } else {
+                theme::TEXT_SECONDARY
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:>3}% ", row.checked * 100 / row.total),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("{:<16} ", format_progress(row.checked, row.total)),
+                    Style::default().fg(color),
+                ),
+                Span::styled(row.title.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+                Span::styled(
+                    format!("  {}", column_label(&row.column)),
+                    Style::default().fg(theme::TEXT_DIM),
+                ),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(*selected));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .bg(theme::SURFACE_1)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_column_picker(f: &mut Frame, app: &App) {
+    let (Some(Overlay::ColumnPicker { columns, selected }), Some(board)) = (&app.overlay, &app.board)
+    else {
+        return;
+    };
+
+    let height = (columns.len() as u16).clamp(1, 12) + 2;
+    let area = centered_rect_fixed(30, height, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" {} more columns ", columns.len()),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .title_bottom(Line::from(Span::styled(
+            " Enter show · Esc cancel ",
+            Style::default().fg(theme::TEXT_DIM),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::horizontal(1));
+
+    let items: Vec<ListItem> = columns
+        .iter()
+        .filter_map(|&i| board.columns.get(i).map(|c| (i, c)))
+        .map(|(i, col)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    col.display_label().to_string(),
+                    Style::default().fg(theme::column_color(&col.color, i)),
+                ),
+                Span::styled(
+                    format!("  {}", col.tasks.len()),
+                    Style::default().fg(theme::TEXT_DIM),
+                ),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(*selected));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .bg(theme::SURFACE_1)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// The `O` link picker over a task or resource: every link target once,
+/// numbered for 1-9.
+pub fn render_link_picker(f: &mut Frame, app: &App) {
+    let (Some(Overlay::TaskDetail { links: Some(selected), .. })
+    | Some(Overlay::ResourceDetail { links: Some(selected), .. })) = &app.overlay
+    else {
+        return;
+    };
+    let links = hyperlink::picker_links(app);
+
+    let height = (links.len() as u16).clamp(1, 12) + 2;
+    let area = centered_rect_fixed(60, height, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" {} links ", links.len()),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .title_bottom(Line::from(Span::styled(
+            " Enter/1-9 open · y copy · Esc close ",
+            Style::default().fg(theme::TEXT_DIM),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::horizontal(1));
+
+    let items: Vec<ListItem> = links
+        .iter()
+        .enumerate()
+        .map(|(i, link)| {
+            let number = if i < 9 { format!("{} ", i + 1) } else { "  ".to_string() };
+            let mut spans = vec![Span::styled(number, Style::default().fg(theme::TEXT_DIM))];
+            let label = link.display_text();
+            if label != link.url {
+                spans.push(Span::styled(
+                    format!("{label}  "),
+                    Style::default().fg(theme::TEXT_PRIMARY),
+                ));
+            }
+            spans.push(Span::styled(link.url.clone(), Style::default().fg(theme::LINK_FG)));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(*selected));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .bg(theme::SURFACE_1)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
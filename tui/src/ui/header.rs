@@ -1,11 +1,13 @@
 use ratatui::Frame;
-use ratatui::layout::Rect;
+use ratatui::layout::{Alignment, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use unicode_width::UnicodeWidthStr;
 
 use crate::app::{App, ConnectionState, Focus, View};
 use crate::theme;
+use crate::ui::board::truncate_spans;
 
 pub fn render_header(f: &mut Frame, app: &App, area: Rect) {
     let tab_focused = app.focus == Focus::TabBar && app.overlay.is_none();
@@ -55,6 +57,30 @@ pub fn render_header(f: &mut Frame, app: &App, area: Rect) {
 
     let paragraph = Paragraph::new(Line::from(spans)).block(block);
     f.render_widget(paragraph, area);
+
+    let mut right = vec![];
+    if app.read_only {
+        right.push(Span::styled(
+            " read-only ",
+            Style::default()
+                .fg(theme::HEADER_BG)
+                .bg(theme::YELLOW)
+                .add_modifier(Modifier::BOLD),
+        ));
+        right.push(Span::raw("  "));
+    }
+    if !app.user.is_empty() {
+        right.push(Span::styled(
+            format!("@{}  ", app.user),
+            Style::default()
+                .fg(theme::TEXT_SECONDARY)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if !right.is_empty() {
+        let right = Paragraph::new(Line::from(right)).alignment(Alignment::Right);
+        f.render_widget(right, Rect::new(area.x, area.y, area.width, 1));
+    }
 }
 
 pub fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
@@ -71,6 +97,19 @@ pub fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
         ConnectionState::Connecting => {
             spans.push(Span::styled(" ◌ connecting ", Style::default().fg(theme::YELLOW)));
         }
+        ConnectionState::Offline => {
+            spans.push(Span::styled(
+                format!(
+                    " OFFLINE SNAPSHOT {} ",
+                    app.offline_since.as_deref().unwrap_or_default()
+                ),
+                Style::default()
+                    .fg(theme::HEADER_BG)
+                    .bg(theme::YELLOW)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(" "));
+        }
     }
 
     // Project name + version
@@ -81,10 +120,79 @@ pub fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
         ));
     }
 
+    // Breadcrumb for the current selection
+    // In the single-column layout the column crumb says which of how many is shown
+    let column_position = (app.view == View::Board
+        && app.overlay.is_none()
+        && app.is_narrow(area.width))
+    .then(|| app.column_position())
+    .flatten();
+    for (i, crumb) in app.breadcrumb().into_iter().enumerate() {
+        let sep = if i == 0 { "  " } else { " › " };
+        spans.push(Span::styled(sep, Style::default().fg(theme::TEXT_DIM)));
+        spans.push(Span::styled(crumb, Style::default().fg(theme::TEXT_PRIMARY)));
+        if let (1, Some((pos, total))) = (i, column_position) {
+            spans.push(Span::styled(
+                format!(" (col {pos}/{total})"),
+                Style::default().fg(theme::TEXT_DIM),
+            ));
+        }
+    }
+
+    if let (View::Board, Some(name)) = (app.view, &app.active_assignee) {
+        spans.push(Span::styled(
+            format!("  filter: @{name}"),
+            Style::default().fg(theme::TAB_ACTIVE_FG),
+        ));
+    }
+    if let (View::Board, Some(scope)) = (app.view, &app.active_scope) {
+        spans.push(Span::styled(
+            format!("  filter: [{scope}]"),
+            Style::default().fg(theme::TAB_ACTIVE_FG),
+        ));
+    }
+    if let (View::Board, Some(status)) = (app.view, &app.active_status) {
+        spans.push(Span::styled(
+            format!("  filter: status={status}"),
+            Style::default().fg(theme::TAB_ACTIVE_FG),
+        ));
+    }
+    if let (View::Board, Some(query)) = (app.view, &app.board_search) {
+        spans.push(Span::styled(
+            format!("  find: \"{query}\" ({})", app.board_matches.len()),
+            Style::default().fg(theme::TAB_ACTIVE_FG),
+        ));
+    }
+
+    // Transient status message, ahead of the WIP warnings so those are the
+    // ones cut when space runs out
+    if let Some(msg) = app.active_status() {
+        spans.push(Span::styled(
+            format!("  {msg}"),
+            Style::default().fg(theme::YELLOW),
+        ));
+    }
+
+    // Board-wide WIP warning, whichever view is open
+    if let Some(board) = &app.board {
+        for (i, count, limit) in app.over_wip_columns() {
+            let col = &board.columns[i];
+            let label = if col.label.is_empty() { &col.name } else { &col.label };
+            spans.push(Span::styled(
+                format!("  ⚠ {label} over WIP ({count}/{limit})"),
+                Style::default().fg(theme::RED),
+            ));
+        }
+    }
+
     // Right side: URL + help hint
     let right_text = " ?=help  q=quit ";
+    // A long title or message is cut rather than pushing the hint off screen
+    let mut spans = truncate_spans(spans, (area.width as usize).saturating_sub(right_text.width()));
     let left_len: usize = spans.iter().map(|s| s.width()).sum();
-    let padding = area.width as usize - left_len.min(area.width as usize) - right_text.len().min(area.width as usize);
+    let padding = (area.width as usize)
+        .saturating_sub(left_len)
+        .saturating_sub(right_text.width());
     spans.push(Span::raw(" ".repeat(padding)));
     spans.push(Span::styled(
         right_text,
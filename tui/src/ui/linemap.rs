@@ -0,0 +1,82 @@
+//! Mapping from rendered overlay lines back to the markdown source lines of
+//! the body they show, for features that act on the source (visual yank).
+
+use crate::ui::markdown::RenderedMarkdown;
+
+/// The body occupies `source.len()` rendered lines starting at `offset`;
+/// lines outside it (title, metadata, comments) have no source line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineMap {
+    offset: usize,
+    source: Vec<usize>,
+}
+
+impl LineMap {
+    /// Map for a body rendered at line `offset` of the overlay.
+    pub fn new(offset: usize, body: &RenderedMarkdown) -> Self {
+        Self {
+            offset,
+            source: body.source_lines.clone(),
+        }
+    }
+
+    /// Source line shown on rendered `line`, if it is part of the body.
+    pub fn source_of(&self, line: usize) -> Option<usize> {
+        line.checked_sub(self.offset)
+            .and_then(|i| self.source.get(i))
+            .copied()
+    }
+
+    /// Rendered line showing `source`, or the nearest rendered line before it.
+    pub fn line_of(&self, source: usize) -> Option<usize> {
+        let idx = self.source.partition_point(|&s| s <= source).checked_sub(1)?;
+        Some(self.offset + idx)
+    }
+
+    /// First body source line rendered at or after `line`, falling back to
+    /// the last one when `line` is past the body.
+    pub fn source_at_or_after(&self, line: usize) -> Option<usize> {
+        let idx = line.saturating_sub(self.offset).min(self.source.len().checked_sub(1)?);
+        Some(self.source[idx])
+    }
+
+    /// Move from `source` by `delta` rendered body lines, stopping at the ends.
+    pub fn step(&self, source: usize, delta: isize) -> usize {
+        let Some(line) = self.line_of(source) else {
+            return source;
+        };
+        let idx = (line - self.offset)
+            .saturating_add_signed(delta)
+            .min(self.source.len() - 1);
+        self.source[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::markdown::render_markdown;
+
+    #[test]
+    fn maps_rendered_lines_to_source_lines() {
+        // The reference definition on source line 2 renders nothing
+        let body = render_markdown("# Title\nSee [x].\n[x]: https://x.dev\n- item");
+        let map = LineMap::new(4, &body);
+
+        assert_eq!(map.source_of(3), None);
+        assert_eq!(map.source_of(4), Some(0));
+        assert_eq!(map.source_of(6), Some(3));
+        assert_eq!(map.source_of(7), None);
+
+        assert_eq!(map.line_of(1), Some(5));
+        assert_eq!(map.line_of(2), Some(5));
+        assert_eq!(map.line_of(3), Some(6));
+
+        assert_eq!(map.source_at_or_after(0), Some(0));
+        assert_eq!(map.source_at_or_after(20), Some(3));
+
+        assert_eq!(map.step(1, 1), 3);
+        assert_eq!(map.step(3, 1), 3);
+        assert_eq!(map.step(3, -5), 0);
+    }
+}
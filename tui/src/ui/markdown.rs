@@ -1,38 +1,260 @@
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
 
 use crate::theme;
 
+/// An image embedded in markdown via `![alt](target)` or `![alt][ref]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRef {
+    pub alt: String,
+    pub target: String,
+}
+
+/// A `[label](url)`, `[label][ref]` or `<url>` link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkRef {
+    pub label: String,
+    pub url: String,
+}
+
+impl LinkRef {
+    /// The label as rendered, with inline formatting markers removed.
+    pub fn display_text(&self) -> String {
+        parse_inline_styled(&self.label, Style::default())
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+}
+
+/// A `#`/`##`/`###` heading and the index of its rendered line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    pub line: usize,
+}
+
 /// Convert markdown text to a list of styled Lines for ratatui rendering.
-/// Handles: headers, checkboxes, bold, italic, inline code, bullet lists.
+/// Handles: headers, checkboxes, bold, italic, strikethrough, inline code, bullet lists, images, links,
+/// footnotes and definition lists. HTML comments and simple inline tags are hidden.
 pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
+    markdown_with_headings(text).0
+}
+
+/// Like `markdown_to_lines`, also returning the headings with their line offsets.
+pub fn markdown_with_headings(text: &str) -> (Vec<Line<'static>>, Vec<Heading>) {
+    let rendered = render_markdown(text);
+    (rendered.lines, rendered.headings)
+}
+
+/// Rendered markdown with its headings and, for every rendered line, the
+/// index of the source line it came from.
+#[derive(Debug, Default)]
+pub struct RenderedMarkdown {
+    pub lines: Vec<Line<'static>>,
+    pub headings: Vec<Heading>,
+    pub source_lines: Vec<usize>,
+    /// `(index, source line)` of each `<details>` header that is shown.
+    /// Indices count every section in document order, hidden ones included.
+    pub sections: Vec<(usize, usize)>,
+    /// `line_rows` for the last width asked for.
+    row_counts: RefCell<Option<(u16, Rc<[usize]>)>>,
+}
+
+impl RenderedMarkdown {
+    /// Rows each line takes wrapped at `width`. Kept until the width
+    /// changes, so redraws and scrolling don't wrap the whole document again.
+    pub fn line_rows(&self, width: u16) -> Rc<[usize]> {
+        let mut cached = self.row_counts.borrow_mut();
+        if let Some((cached_width, rows)) = cached.as_ref() {
+            if *cached_width == width {
+                return Rc::clone(rows);
+            }
+        }
+        let rows: Rc<[usize]> = self.lines.iter().map(|line| wrapped_rows(line, width)).collect();
+        *cached = Some((width, Rc::clone(&rows)));
+        rows
+    }
+}
+
+/// Rows `line` takes wrapped at `width`.
+pub fn wrapped_rows(line: &Line<'static>, width: u16) -> usize {
+    Paragraph::new(line.clone())
+        .wrap(Wrap { trim: false })
+        .line_count(width)
+}
+
+/// The rendering of the open overlay's body, keyed by a hash of its text.
+/// Overlays are rebuilt on every draw and scroll; this keeps long documents
+/// from being parsed again until their body (or shown revision) changes.
+#[derive(Debug, Default)]
+pub struct MarkdownCache {
+    entry: RefCell<Option<(u64, Rc<RenderedMarkdown>)>>,
+}
+
+impl MarkdownCache {
+    /// `render_folded(text, toggled)` (or `render_raw` when `raw`), reusing
+    /// the last result when the text and options are the same.
+    pub fn render(&self, text: &str, raw: bool, toggled: &BTreeSet<usize>) -> Rc<RenderedMarkdown> {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        text.hash(&mut hasher);
+        raw.hash(&mut hasher);
+        toggled.hash(&mut hasher);
+        let key = hasher.finish();
+
+        let mut entry = self.entry.borrow_mut();
+        if let Some((cached, rendered)) = entry.as_ref() {
+            if *cached == key {
+                return Rc::clone(rendered);
+            }
+        }
+        let rendered = Rc::new(if raw {
+            render_raw(text)
+        } else {
+            render_folded(text, toggled)
+        });
+        *entry = Some((key, Rc::clone(&rendered)));
+        rendered
+    }
+}
+
+/// `text` as written, one unstyled line per source line, for inspecting the
+/// exact syntax behind a rendering. Headings keep pointing at their source
+/// lines so the table of contents still works.
+pub fn render_raw(text: &str) -> RenderedMarkdown {
+    let style = Style::default().fg(theme::TEXT_PRIMARY);
+    let lines: Vec<Line<'static>> = text
+        .lines()
+        .map(|line| Line::from(Span::styled(line.replace('\t', "    "), style)))
+        .collect();
+    let rendered = render_markdown(text);
+    let headings = rendered
+        .headings
+        .into_iter()
+        .filter_map(|heading| {
+            let line = *rendered.source_lines.get(heading.line)?;
+            Some(Heading { line, ..heading })
+        })
+        .collect();
+    RenderedMarkdown {
+        source_lines: (0..lines.len()).collect(),
+        lines,
+        headings,
+        sections: Vec::new(),
+        row_counts: RefCell::default(),
+    }
+}
+
+/// Render `text` with every `<details>` section expanded.
+pub fn render_markdown(text: &str) -> RenderedMarkdown {
+    render_sections(text, |_, _| true)
+}
+
+/// Render `text` for an overlay: `<details>` sections are collapsed to their
+/// summary unless marked `open`, and the sections in `toggled` are flipped.
+pub fn render_folded(text: &str, toggled: &BTreeSet<usize>) -> RenderedMarkdown {
+    render_sections(text, |index, open| open != toggled.contains(&index))
+}
+
+/// `is_expanded(index, has_open_attribute)` decides each section's state.
+fn render_sections(text: &str, is_expanded: impl Fn(usize, bool) -> bool) -> RenderedMarkdown {
     let mut lines = Vec::new();
+    let mut headings = Vec::new();
+    let mut source_lines = Vec::new();
+    let mut sections = Vec::new();
+    let refs = collect_reference_defs(text);
+    let stripped = strip_html_comments(text);
+    let source_text: Vec<&str> = stripped.iter().map(|line| line.as_deref().unwrap_or_default()).collect();
+    // Set after a definition-list term until a line that isn't `: definition`
+    let mut in_definition = false;
+    // Whether each enclosing `<details>` hides its content, innermost last
+    let mut details: Vec<bool> = Vec::new();
+    let mut section_count = 0;
+    // A `<summary>` line already shown in its section's header
+    let mut summary_line = None;
 
-    for raw_line in text.lines() {
+    for (source, raw_line) in source_text.iter().copied().enumerate() {
+        if stripped[source].is_none() {
+            continue;
+        }
         let trimmed = raw_line.trim();
-
-        // Headers
-        if trimmed.starts_with("### ") {
-            lines.push(Line::from(Span::styled(
-                trimmed[4..].to_string(),
-                Style::default()
-                    .fg(theme::TEXT_PRIMARY)
-                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
-            )));
+        let hidden = details.last() == Some(&true);
+        if let Some(attrs) = details_open_tag(trimmed) {
+            let index = section_count;
+            section_count += 1;
+            let expanded = !hidden && is_expanded(index, attrs.split_whitespace().any(|a| a == "open"));
+            details.push(!expanded);
+            if hidden {
+                continue;
+            }
+            let rest = &trimmed[trimmed.find('>').map_or(trimmed.len(), |i| i + 1)..];
+            let summary = parse_summary(rest).or_else(|| {
+                let next = source_text.get(source + 1)?;
+                let summary = parse_summary(next.trim())?;
+                summary_line = Some(source + 1);
+                Some(summary)
+            });
+            sections.push((index, source));
+            source_lines.push(source);
+            lines.push(details_header(summary.unwrap_or("Details"), expanded, &refs));
             continue;
         }
-        if trimmed.starts_with("## ") {
-            lines.push(Line::from(Span::styled(
-                trimmed[3..].to_string(),
-                Style::default()
-                    .fg(theme::TEXT_PRIMARY)
-                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
-            )));
+        if trimmed == "</details>" && !details.is_empty() {
+            details.pop();
+            continue;
+        }
+        if hidden || summary_line == Some(source) {
+            continue;
+        }
+        // Every branch below renders exactly one line, except reference and
+        // footnote definitions
+        if is_definition_line(trimmed) {
+            continue;
+        }
+        source_lines.push(source);
+
+        // Definition lists: `Term` followed by `: definition` lines
+        if in_definition {
+            if let Some(rest) = trimmed.strip_prefix(": ") {
+                let spans = parse_inline_with_images(&format!("    {}", rest.trim_start()), &refs);
+                lines.push(Line::from(spans));
+                continue;
+            }
+        }
+        in_definition = false;
+        let next = source_text
+            .get(source + 1)
+            .map(|line| line.trim_start())
+            .unwrap_or_default();
+        let is_term = !trimmed.is_empty()
+            && !trimmed.starts_with("- ")
+            && parse_heading(trimmed).is_none();
+        if is_term && next.starts_with(": ") {
+            in_definition = true;
+            let spans = parse_inline_with_images(trimmed, &refs)
+                .into_iter()
+                .map(|span| span.patch_style(Style::default().add_modifier(Modifier::BOLD)))
+                .collect::<Vec<_>>();
+            lines.push(Line::from(spans));
             continue;
         }
-        if trimmed.starts_with("# ") {
+
+        // Headers
+        if let Some((level, rest)) = parse_heading(trimmed) {
+            headings.push(Heading {
+                level,
+                title: rest.to_string(),
+                line: lines.len(),
+            });
             lines.push(Line::from(Span::styled(
-                trimmed[2..].to_string(),
+                rest.to_string(),
                 Style::default()
                     .fg(theme::TEXT_PRIMARY)
                     .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
@@ -41,8 +263,11 @@ pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
         }
 
         // Checkboxes
-        if trimmed.starts_with("- [x]") || trimmed.starts_with("- [X]") {
-            let rest = trimmed[5..].to_string();
+        if let Some(rest) = trimmed
+            .strip_prefix("- [x]")
+            .or_else(|| trimmed.strip_prefix("- [X]"))
+        {
+            let rest = rest.to_string();
             lines.push(Line::from(vec![
                 Span::styled("  ✓ ", Style::default().fg(theme::GREEN)),
                 Span::styled(
@@ -54,8 +279,8 @@ pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
             ]));
             continue;
         }
-        if trimmed.starts_with("- [ ]") {
-            let rest = trimmed[5..].to_string();
+        if let Some(rest) = trimmed.strip_prefix("- [ ]") {
+            let rest = rest.to_string();
             lines.push(Line::from(vec![
                 Span::styled("  ○ ", Style::default().fg(theme::TEXT_DIM)),
                 Span::styled(rest, Style::default().fg(theme::TEXT_PRIMARY)),
@@ -64,9 +289,8 @@ pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
         }
 
         // Bullet lists
-        if trimmed.starts_with("- ") {
-            let rest = trimmed[2..].to_string();
-            let spans = parse_inline_formatting(&format!("  • {rest}"));
+        if let Some(rest) = trimmed.strip_prefix("- ") {
+            let spans = parse_inline_with_images(&format!("  • {rest}"), &refs);
             lines.push(Line::from(spans));
             continue;
         }
@@ -87,85 +311,567 @@ pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
         }
 
         // Regular text with inline formatting
-        let spans = parse_inline_formatting(raw_line);
+        let spans = parse_inline_with_images(raw_line, &refs);
         lines.push(Line::from(spans));
     }
 
-    lines
+    // Footnote definitions are gathered at the bottom. They map to the last
+    // source line so `source_lines` stays in document order.
+    if !refs.notes.is_empty() {
+        let last = source_text.len().saturating_sub(1);
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "─".repeat(20),
+            Style::default().fg(theme::BORDER_COLOR),
+        )));
+        for (i, note) in refs.notes.iter().enumerate() {
+            let mut spans = vec![footnote_marker(i + 1), Span::raw(" ")];
+            spans.extend(parse_inline_with_images(note, &refs));
+            lines.push(Line::from(spans));
+        }
+        source_lines.extend(std::iter::repeat_n(last, refs.notes.len() + 2));
+    }
+
+    RenderedMarkdown {
+        lines,
+        headings,
+        source_lines,
+        sections,
+        row_counts: RefCell::default(),
+    }
 }
 
-/// Parse inline markdown formatting: **bold**, *italic*, `code`.
-fn parse_inline_formatting(text: &str) -> Vec<Span<'static>> {
-    let mut spans = Vec::new();
-    let mut remaining = text.to_string();
-
-    while !remaining.is_empty() {
-        // Bold **text**
-        if let Some(start) = remaining.find("**") {
-            if let Some(end) = remaining[start + 2..].find("**") {
-                if start > 0 {
-                    spans.push(Span::styled(
-                        remaining[..start].to_string(),
-                        Style::default().fg(theme::TEXT_PRIMARY),
-                    ));
+/// `text` line by line with `<!-- ... -->` comments removed, including ones
+/// spanning lines. Lines that held only comments are None, so they don't
+/// render as blank lines. Comments inside inline code are kept.
+fn strip_html_comments(text: &str) -> Vec<Option<String>> {
+    let mut in_comment = false;
+    text.lines()
+        .map(|line| {
+            let mut out = String::new();
+            let mut had_comment = in_comment;
+            let mut rest = line;
+            while !rest.is_empty() {
+                if in_comment {
+                    match rest.find("-->") {
+                        Some(end) => {
+                            rest = &rest[end + 3..];
+                            in_comment = false;
+                        }
+                        None => rest = "",
+                    }
+                    continue;
+                }
+                let Some(start) = rest.find(['<', '`']) else {
+                    out.push_str(rest);
+                    break;
+                };
+                out.push_str(&rest[..start]);
+                rest = &rest[start..];
+                if rest.starts_with('`') {
+                    let run = rest.len() - rest.trim_start_matches('`').len();
+                    let end = find_backtick_run(&rest[run..], run).map_or(run, |close| run + close + run);
+                    out.push_str(&rest[..end]);
+                    rest = &rest[end..];
+                } else if let Some(comment) = rest.strip_prefix("<!--") {
+                    had_comment = true;
+                    in_comment = true;
+                    rest = comment;
+                } else {
+                    out.push('<');
+                    rest = &rest[1..];
                 }
-                spans.push(Span::styled(
-                    remaining[start + 2..start + 2 + end].to_string(),
-                    Style::default()
-                        .fg(theme::TEXT_PRIMARY)
-                        .add_modifier(Modifier::BOLD),
-                ));
-                remaining = remaining[start + 2 + end + 2..].to_string();
-                continue;
             }
+            (!had_comment || !out.trim().is_empty()).then_some(out)
+        })
+        .collect()
+}
+
+/// Inline HTML tags whose markup is hidden, keeping what they enclose.
+/// Other tags, and these with attributes, show as written.
+const INLINE_TAGS: [&str; 15] = [
+    "b", "br", "del", "em", "i", "ins", "kbd", "mark", "s", "small", "strong", "sub", "sup", "u", "wbr",
+];
+
+/// A known inline tag (`<kbd>`, `</kbd>`, `<br/>`) at the start of `text`:
+/// its name and length.
+fn inline_tag_at(text: &str) -> Option<(&'static str, usize)> {
+    let end = text.find('>')?;
+    let inner = text[1..end].trim_start_matches('/').trim_end_matches('/').trim_end();
+    let name = INLINE_TAGS.iter().find(|tag| tag.eq_ignore_ascii_case(inner))?;
+    Some((name, end + 1))
+}
+
+/// Attributes of a `<details ...>` opening tag.
+fn details_open_tag(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("<details")?;
+    let end = rest.find('>')?;
+    let attrs = &rest[..end];
+    (attrs.is_empty() || attrs.starts_with(' ')).then_some(attrs)
+}
+
+/// Text of a `<summary>...</summary>` element at the start of `line`.
+fn parse_summary(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("<summary>")?;
+    let end = rest.find("</summary>").unwrap_or(rest.len());
+    Some(rest[..end].trim())
+}
+
+fn details_header(summary: &str, expanded: bool, refs: &References) -> Line<'static> {
+    let marker = if expanded { "▾ " } else { "▸ " };
+    let mut spans = vec![Span::styled(marker, Style::default().fg(theme::LINK_FG))];
+    spans.extend(
+        parse_inline_with_images(summary, refs)
+            .into_iter()
+            .map(|span| span.patch_style(Style::default().add_modifier(Modifier::BOLD))),
+    );
+    Line::from(spans)
+}
+
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    ["# ", "## ", "### "]
+        .iter()
+        .enumerate()
+        .find_map(|(i, prefix)| line.strip_prefix(prefix).map(|rest| (i + 1, rest)))
+}
+
+/// Highlight case-insensitive occurrences of `query` in reverse video, splitting
+/// spans as needed. Returns the indices of lines that contain a match.
+pub fn highlight_matches(lines: &mut [Line<'static>], query: &str) -> Vec<usize> {
+    let needle: Vec<char> = query.chars().map(fold_case).collect();
+    if needle.is_empty() {
+        return vec![];
+    }
+
+    let mut hits = Vec::new();
+    for (idx, line) in lines.iter_mut().enumerate() {
+        let hay: Vec<char> = line
+            .spans
+            .iter()
+            .flat_map(|s| s.content.chars())
+            .map(fold_case)
+            .collect();
+
+        // Char ranges of non-overlapping matches
+        let mut ranges = Vec::new();
+        let mut i = 0;
+        while i + needle.len() <= hay.len() {
+            if hay[i..i + needle.len()] == needle[..] {
+                ranges.push((i, i + needle.len()));
+                i += needle.len();
+            } else {
+                i += 1;
+            }
+        }
+        if ranges.is_empty() {
+            continue;
         }
+        hits.push(idx);
+
+        let mut spans = Vec::new();
+        let mut pos = 0;
+        for span in line.spans.drain(..) {
+            let chars: Vec<char> = span.content.chars().collect();
+            let (start, end) = (pos, pos + chars.len());
+            pos = end;
 
-        // Inline code `code`
-        if let Some(start) = remaining.find('`') {
-            if let Some(end) = remaining[start + 1..].find('`') {
-                if start > 0 {
-                    spans.push(Span::styled(
-                        remaining[..start].to_string(),
-                        Style::default().fg(theme::TEXT_PRIMARY),
-                    ));
+            // Cut points inside this span, from match boundaries
+            let mut cuts = vec![start];
+            for &(a, b) in &ranges {
+                for c in [a, b] {
+                    if c > start && c < end {
+                        cuts.push(c);
+                    }
                 }
-                spans.push(Span::styled(
-                    remaining[start + 1..start + 1 + end].to_string(),
-                    Style::default()
-                        .fg(theme::YELLOW)
-                        .bg(theme::SURFACE_1),
-                ));
-                remaining = remaining[start + 1 + end + 1..].to_string();
-                continue;
             }
+            cuts.push(end);
+            cuts.dedup();
+
+            for w in cuts.windows(2) {
+                let text: String = chars[w[0] - start..w[1] - start].iter().collect();
+                let inside = ranges.iter().any(|&(a, b)| w[0] >= a && w[1] <= b);
+                let style = if inside {
+                    span.style.add_modifier(Modifier::REVERSED)
+                } else {
+                    span.style
+                };
+                spans.push(Span::styled(text, style));
+            }
+        }
+        line.spans = spans;
+    }
+    hits
+}
+
+/// Lowercase a char without changing the char count (needed to map match offsets back).
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Collect every image in the markdown body, in document order.
+pub fn find_images(text: &str) -> Vec<ImageRef> {
+    let refs = collect_reference_defs(text);
+    text.lines()
+        .filter(|line| parse_reference_def(line.trim()).is_none())
+        .flat_map(|line| split_images(line, &refs))
+        .filter_map(|segment| match segment {
+            InlineSegment::Image(image) => Some(image),
+            InlineSegment::Text(_) | InlineSegment::Link(_) | InlineSegment::Footnote(_) => None,
+        })
+        .collect()
+}
+
+/// Collect every link in the markdown body, in document order.
+pub fn find_links(text: &str) -> Vec<LinkRef> {
+    let refs = collect_reference_defs(text);
+    text.lines()
+        .filter(|line| parse_reference_def(line.trim()).is_none())
+        .flat_map(|line| split_images(line, &refs))
+        .flat_map(|segment| match segment {
+            InlineSegment::Text(t) => split_links(t, &refs),
+            other => vec![other],
+        })
+        .filter_map(|segment| match segment {
+            InlineSegment::Link(link) => Some(link),
+            InlineSegment::Text(_) | InlineSegment::Image(_) | InlineSegment::Footnote(_) => None,
+        })
+        .collect()
+}
+
+/// Words a reader gets through per minute, for `reading_stats`.
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Word count of the prose in a markdown body and the estimated reading time
+/// in minutes (at least 1 for non-empty text). Fenced code, link targets and
+/// bare markdown markers (`#`, `-`, `>`, `|`, ...) are not counted.
+pub fn reading_stats(text: &str) -> (usize, usize) {
+    let mut in_fence = false;
+    let mut words = 0;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || is_definition_line(trimmed.trim_end()) {
+            continue;
         }
+        words += line
+            .split_whitespace()
+            // `[label](target)` counts the label only
+            .map(|word| word.split_once("](").map_or(word, |(label, _)| label))
+            .filter(|word| !word.contains("://"))
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .count();
+    }
+    let minutes = words.div_ceil(WORDS_PER_MINUTE);
+    (words, minutes)
+}
 
-        // *italic* (single asterisk, not inside bold)
-        if let Some(start) = remaining.find('*') {
-            if let Some(end) = remaining[start + 1..].find('*') {
-                if start > 0 {
-                    spans.push(Span::styled(
-                        remaining[..start].to_string(),
-                        Style::default().fg(theme::TEXT_PRIMARY),
-                    ));
+enum InlineSegment<'a> {
+    Text(&'a str),
+    Image(ImageRef),
+    Link(LinkRef),
+    Footnote(usize),
+}
+
+/// Link reference and footnote definitions of one markdown body.
+#[derive(Debug, Default)]
+struct References {
+    /// `[label]: target`, keyed by lowercased label.
+    links: HashMap<String, String>,
+    /// `[^label]: text` labels (lowercased) and their number. Footnotes are
+    /// numbered in order of first reference; unreferenced ones come last.
+    footnotes: HashMap<String, usize>,
+    /// Footnote texts, indexed by number - 1.
+    notes: Vec<String>,
+}
+
+fn collect_reference_defs(text: &str) -> References {
+    let links = text
+        .lines()
+        .filter_map(|line| parse_reference_def(line.trim()))
+        .collect();
+    let defs: Vec<(String, String)> = text
+        .lines()
+        .filter_map(|line| parse_footnote_def(line.trim()))
+        .collect();
+
+    let mut refs = References {
+        links,
+        ..Default::default()
+    };
+    let referenced = text
+        .lines()
+        .filter(|line| !is_definition_line(line.trim()))
+        .flat_map(|line| line.match_indices("[^").map(move |(i, _)| &line[i + 2..]))
+        .filter_map(|rest| Some(rest[..rest.find(']')?].to_lowercase()));
+    let unreferenced = defs.iter().map(|(label, _)| label.clone());
+    for label in referenced.chain(unreferenced) {
+        if refs.footnotes.contains_key(&label) {
+            continue;
+        }
+        if let Some((_, note)) = defs.iter().find(|(l, _)| *l == label) {
+            refs.notes.push(note.clone());
+            refs.footnotes.insert(label, refs.notes.len());
+        }
+    }
+    refs
+}
+
+/// Reference and footnote definitions are not rendered in place.
+fn is_definition_line(line: &str) -> bool {
+    parse_reference_def(line).is_some() || parse_footnote_def(line).is_some()
+}
+
+/// `[^label]: text`, returning the lowercased label and the text.
+fn parse_footnote_def(line: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix("[^")?;
+    let close = rest.find("]:")?;
+    let label = &rest[..close];
+    if label.is_empty() || label.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label.to_lowercase(), rest[close + 2..].trim().to_string()))
+}
+
+fn footnote_marker(number: usize) -> Span<'static> {
+    Span::styled(format!("[{number}]"), Style::default().fg(theme::TEXT_SECONDARY))
+}
+
+fn parse_reference_def(line: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix('[')?;
+    let close = rest.find("]:")?;
+    let label = &rest[..close];
+    if label.is_empty() || label.starts_with('^') {
+        return None;
+    }
+    let target = rest[close + 2..].split_whitespace().next()?;
+    let target = target.trim_start_matches('<').trim_end_matches('>');
+    Some((label.to_lowercase(), target.to_string()))
+}
+
+/// Split a line into literal text and image segments. Image syntax whose
+/// reference cannot be resolved is left as literal text.
+fn split_images<'a>(text: &'a str, refs: &References) -> Vec<InlineSegment<'a>> {
+    let mut segments = Vec::new();
+    let mut literal_start = 0;
+    let mut pos = 0;
+
+    while let Some(offset) = text[pos..].find("![") {
+        let start = pos + offset;
+        match parse_image_at(&text[start..], refs) {
+            Some((image, len)) => {
+                if start > literal_start {
+                    segments.push(InlineSegment::Text(&text[literal_start..start]));
                 }
-                spans.push(Span::styled(
-                    remaining[start + 1..start + 1 + end].to_string(),
-                    Style::default()
-                        .fg(theme::TEXT_PRIMARY)
-                        .add_modifier(Modifier::ITALIC),
-                ));
-                remaining = remaining[start + 1 + end + 1..].to_string();
-                continue;
+                segments.push(InlineSegment::Image(image));
+                pos = start + len;
+                literal_start = pos;
             }
+            None => pos = start + 2,
         }
+    }
+
+    if literal_start < text.len() {
+        segments.push(InlineSegment::Text(&text[literal_start..]));
+    }
+    segments
+}
+
+/// Parse an image starting at `![`. Returns the image and the consumed length.
+fn parse_image_at(text: &str, refs: &References) -> Option<(ImageRef, usize)> {
+    let alt_end = 2 + text[2..].find(']')?;
+    let alt = text[2..alt_end].to_string();
+    let rest = &text[alt_end + 1..];
+
+    if let Some(inner) = rest.strip_prefix('(') {
+        let close = inner.find(')')?;
+        let target = inner[..close].split_whitespace().next().unwrap_or("");
+        let target = target.trim_start_matches('<').trim_end_matches('>');
+        let len = alt_end + 1 + 1 + close + 1;
+        return Some((ImageRef { alt, target: target.to_string() }, len));
+    }
+
+    if let Some(inner) = rest.strip_prefix('[') {
+        let close = inner.find(']')?;
+        let label = if close == 0 { &alt } else { &inner[..close] };
+        let target = refs.links.get(&label.to_lowercase())?.clone();
+        let len = alt_end + 1 + 1 + close + 1;
+        return Some((ImageRef { alt, target }, len));
+    }
 
-        // No more formatting — emit remainder
+    None
+}
+
+/// Split literal text into text and link segments. Link syntax inside code
+/// spans, and references that cannot be resolved, stay literal.
+fn split_links<'a>(text: &'a str, refs: &References) -> Vec<InlineSegment<'a>> {
+    let mut segments = Vec::new();
+    let mut literal_start = 0;
+    let mut pos = 0;
+
+    while let Some(offset) = text[pos..].find(['[', '<', '`']) {
+        let start = pos + offset;
+        if text[start..].starts_with('`') {
+            let run = text[start..].len() - text[start..].trim_start_matches('`').len();
+            pos = match find_backtick_run(&text[start + run..], run) {
+                Some(close) => start + run + close + run,
+                None => start + run,
+            };
+            continue;
+        }
+        if let Some((number, len)) = parse_footnote_ref_at(&text[start..], refs) {
+            if start > literal_start {
+                segments.push(InlineSegment::Text(&text[literal_start..start]));
+            }
+            segments.push(InlineSegment::Footnote(number));
+            pos = start + len;
+            literal_start = pos;
+            continue;
+        }
+        match parse_link_at(&text[start..], refs) {
+            Some((link, len)) => {
+                if start > literal_start {
+                    segments.push(InlineSegment::Text(&text[literal_start..start]));
+                }
+                segments.push(InlineSegment::Link(link));
+                pos = start + len;
+                literal_start = pos;
+            }
+            None => pos = start + 1,
+        }
+    }
+
+    if literal_start < text.len() {
+        segments.push(InlineSegment::Text(&text[literal_start..]));
+    }
+    segments
+}
+
+/// Parse a link starting at `[` or `<`. Returns the link and the consumed length.
+fn parse_link_at(text: &str, refs: &References) -> Option<(LinkRef, usize)> {
+    if let Some(inner) = text.strip_prefix('<') {
+        let close = inner.find('>')?;
+        let url = &inner[..close];
+        let is_url = ["http://", "https://", "mailto:"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme));
+        if !is_url || url.contains(char::is_whitespace) {
+            return None;
+        }
+        let link = LinkRef {
+            label: url.to_string(),
+            url: url.to_string(),
+        };
+        return Some((link, close + 2));
+    }
+
+    let label_end = 1 + text.get(1..)?.find(']')?;
+    let label = &text[1..label_end];
+    if label.trim().is_empty() || label.starts_with('^') {
+        return None;
+    }
+    let rest = &text[label_end + 1..];
+    let link = |url: String| LinkRef {
+        label: label.to_string(),
+        url,
+    };
+
+    if let Some(inner) = rest.strip_prefix('(') {
+        let close = inner.find(')')?;
+        let url = inner[..close].split_whitespace().next()?;
+        let url = url.trim_start_matches('<').trim_end_matches('>');
+        return Some((link(url.to_string()), label_end + 1 + 1 + close + 1));
+    }
+
+    if let Some(inner) = rest.strip_prefix('[') {
+        let close = inner.find(']')?;
+        let key = if close == 0 { label } else { &inner[..close] };
+        let url = refs.links.get(&key.to_lowercase())?.clone();
+        return Some((link(url), label_end + 1 + 1 + close + 1));
+    }
+
+    // Shortcut reference: `[label]` with a matching definition
+    let url = refs.links.get(&label.to_lowercase())?.clone();
+    Some((link(url), label_end + 1))
+}
+
+/// Parse a `[^label]` reference to a defined footnote. Returns its number
+/// and the consumed length.
+fn parse_footnote_ref_at(text: &str, refs: &References) -> Option<(usize, usize)> {
+    let rest = text.strip_prefix("[^")?;
+    let close = rest.find(']')?;
+    let number = *refs.footnotes.get(&rest[..close].to_lowercase())?;
+    Some((number, close + 3))
+}
+
+/// Render a line with images replaced by `🖼 alt (target)` placeholders and
+/// links shown as their underlined label.
+fn parse_inline_with_images(text: &str, refs: &References) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for segment in split_images(text, refs) {
+        let InlineSegment::Text(text) = segment else {
+            if let InlineSegment::Image(image) = segment {
+                spans.push(image_placeholder(&image));
+            }
+            continue;
+        };
+        for segment in split_links(text, refs) {
+            match segment {
+                InlineSegment::Text(t) => spans.extend(parse_inline_formatting(t)),
+                InlineSegment::Link(link) => spans.extend(parse_inline_styled(
+                    &link.label,
+                    Style::default()
+                        .fg(theme::LINK_FG)
+                        .add_modifier(Modifier::UNDERLINED),
+                )),
+                InlineSegment::Image(image) => spans.push(image_placeholder(&image)),
+                InlineSegment::Footnote(number) => spans.push(footnote_marker(number)),
+            }
+        }
+    }
+    if spans.is_empty() {
         spans.push(Span::styled(
-            remaining.clone(),
+            text.to_string(),
             Style::default().fg(theme::TEXT_PRIMARY),
         ));
-        break;
+    }
+    spans
+}
+
+fn image_placeholder(image: &ImageRef) -> Span<'static> {
+    let label = if image.alt.is_empty() {
+        format!("🖼 ({})", image.target)
+    } else {
+        format!("🖼 {} ({})", image.alt, image.target)
+    };
+    Span::styled(
+        label,
+        Style::default()
+            .fg(theme::GREEN)
+            .add_modifier(Modifier::ITALIC),
+    )
+}
+
+/// Parse inline markdown formatting: **bold**, *italic*, `code`, ~~strikethrough~~,
+/// and `@name` mentions.
+fn parse_inline_formatting(text: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for span in parse_inline_styled(text, Style::default().fg(theme::TEXT_PRIMARY)) {
+        // Code spans have their own color, so mentions in them stay literal
+        if span.style.fg != Some(theme::TEXT_PRIMARY) {
+            spans.push(span);
+            continue;
+        }
+        for (part, mention) in split_mentions(&span.content) {
+            let style = if mention {
+                span.style.fg(theme::TAB_ACTIVE_FG).add_modifier(Modifier::BOLD)
+            } else {
+                span.style
+            };
+            spans.push(Span::styled(part.to_string(), style));
+        }
     }
 
     if spans.is_empty() {
@@ -177,3 +883,590 @@ fn parse_inline_formatting(text: &str) -> Vec<Span<'static>> {
 
     spans
 }
+
+/// Split `text` into runs, flagging `@name` mentions. An `@` after a word
+/// character (as in an email address) does not start one, and trailing
+/// punctuation is not part of the name.
+fn split_mentions(text: &str) -> Vec<(&str, bool)> {
+    let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.');
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    for (i, c) in text.char_indices() {
+        let after_word = prev.is_some_and(|p| p.is_alphanumeric() || matches!(p, '_' | '@'));
+        prev = Some(c);
+        if c != '@' || after_word || i < start {
+            continue;
+        }
+        let rest = &text[i + 1..];
+        let len = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+        let name = rest[..len].trim_end_matches(['.', '-']);
+        if !name.starts_with(char::is_alphanumeric) {
+            continue;
+        }
+        if i > start {
+            parts.push((&text[start..i], false));
+        }
+        start = i + 1 + name.len();
+        parts.push((&text[i..start], true));
+    }
+    if start < text.len() || parts.is_empty() {
+        parts.push((&text[start..], false));
+    }
+    parts
+}
+
+/// Restyle mentions of `user` so they stand out from other mentions.
+pub fn highlight_own_mentions(lines: &mut [Line<'static>], user: &str) {
+    if user.is_empty() {
+        return;
+    }
+    for span in lines.iter_mut().flat_map(|line| line.spans.iter_mut()) {
+        let mention = span.style.fg == Some(theme::TAB_ACTIVE_FG)
+            && span.style.add_modifier.contains(Modifier::BOLD)
+            && span
+                .content
+                .strip_prefix('@')
+                .is_some_and(|name| name.eq_ignore_ascii_case(user));
+        if mention {
+            span.style = span.style.fg(theme::HEADER_BG).bg(theme::YELLOW);
+        }
+    }
+}
+
+#[derive(Debug)]
+enum InlineToken {
+    Text(String),
+    Code(String),
+    Delim {
+        marker: &'static str,
+        can_open: bool,
+        can_close: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DelimRole {
+    Literal,
+    Open,
+    Close,
+}
+
+/// Parse inline formatting on top of `base`: tokenize left-to-right, pair up
+/// delimiters, then emit spans. Unmatched markers are rendered as literal text.
+fn parse_inline_styled(text: &str, base: Style) -> Vec<Span<'static>> {
+    let tokens = tokenize_inline(text);
+    let roles = match_delimiters(&tokens);
+
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut depth = [0usize; 3]; // bold, italic, strikethrough
+    let modifiers = |depth: &[usize; 3]| {
+        let mut m = Modifier::empty();
+        for (count, modifier) in depth.iter().zip(INLINE_MODIFIERS) {
+            if *count > 0 {
+                m |= modifier;
+            }
+        }
+        m
+    };
+
+    for (token, role) in tokens.iter().zip(roles) {
+        match token {
+            InlineToken::Text(t) => {
+                push_merged(&mut spans, t, base.add_modifier(modifiers(&depth)));
+            }
+            InlineToken::Code(code) => {
+                let style = Style::default()
+                    .fg(theme::YELLOW)
+                    .bg(theme::SURFACE_1)
+                    .add_modifier(modifiers(&depth));
+                push_merged(&mut spans, code, style);
+            }
+            InlineToken::Delim { marker, .. } => {
+                let slot = marker_slot(marker);
+                match role {
+                    DelimRole::Open => depth[slot] += 1,
+                    DelimRole::Close => depth[slot] = depth[slot].saturating_sub(1),
+                    DelimRole::Literal => {
+                        push_merged(&mut spans, marker, base.add_modifier(modifiers(&depth)));
+                    }
+                }
+            }
+        }
+    }
+
+    spans
+}
+
+const INLINE_MODIFIERS: [Modifier; 3] = [Modifier::BOLD, Modifier::ITALIC, Modifier::CROSSED_OUT];
+
+fn marker_slot(marker: &str) -> usize {
+    match marker {
+        "**" => 0,
+        "*" => 1,
+        _ => 2,
+    }
+}
+
+/// Append text, extending the previous span when the style is unchanged.
+fn push_merged(spans: &mut Vec<Span<'static>>, text: &str, style: Style) {
+    if let Some(last) = spans.last_mut() {
+        if last.style == style {
+            last.content = format!("{}{}", last.content, text).into();
+            return;
+        }
+    }
+    spans.push(Span::styled(text.to_string(), style));
+}
+
+/// Split a line into text, code spans, and emphasis delimiter runs.
+fn tokenize_inline(text: &str) -> Vec<InlineToken> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut i = 0;
+
+    while let Some(c) = text[i..].chars().next() {
+        let rest = &text[i..];
+        match c {
+            '`' => {
+                let run = rest.len() - rest.trim_start_matches('`').len();
+                match find_backtick_run(&rest[run..], run) {
+                    Some(close) => {
+                        flush_literal(&mut tokens, &mut literal);
+                        tokens.push(InlineToken::Code(rest[run..run + close].to_string()));
+                        i += run + close + run;
+                    }
+                    None => {
+                        literal.push_str(&rest[..run]);
+                        i += run;
+                    }
+                }
+            }
+            '*' | '~' => {
+                let run = rest.len() - rest.trim_start_matches(c).len();
+                let prev = text[..i].chars().next_back();
+                let next = rest[run..].chars().next();
+                // Flanking: an opener must touch the text after it, a closer the text before it
+                let can_open = next.is_some_and(|n| !n.is_whitespace());
+                let can_close = prev.is_some_and(|p| !p.is_whitespace());
+                let markers: &[&'static str] = match (c, run) {
+                    _ if !can_open && !can_close => &[],
+                    ('~', 2) => &["~~"],
+                    ('*', 1) => &["*"],
+                    ('*', 2) => &["**"],
+                    ('*', 3) if can_close && !can_open => &["*", "**"],
+                    ('*', 3) => &["**", "*"],
+                    _ => &[],
+                };
+                if markers.is_empty() {
+                    literal.push_str(&rest[..run]);
+                } else {
+                    flush_literal(&mut tokens, &mut literal);
+                    for marker in markers {
+                        tokens.push(InlineToken::Delim {
+                            marker,
+                            can_open,
+                            can_close,
+                        });
+                    }
+                }
+                i += run;
+            }
+            '<' => match inline_tag_at(rest) {
+                Some((name, len)) => {
+                    // A line break can't be shown inside a line; keep the words apart
+                    if name == "br" {
+                        literal.push(' ');
+                    }
+                    i += len;
+                }
+                None => {
+                    literal.push(c);
+                    i += 1;
+                }
+            },
+            _ => {
+                literal.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+
+    flush_literal(&mut tokens, &mut literal);
+    tokens
+}
+
+fn flush_literal(tokens: &mut Vec<InlineToken>, literal: &mut String) {
+    if !literal.is_empty() {
+        tokens.push(InlineToken::Text(std::mem::take(literal)));
+    }
+}
+
+/// Find a run of exactly `len` backticks, returning its byte offset.
+fn find_backtick_run(text: &str, len: usize) -> Option<usize> {
+    let mut i = 0;
+    while let Some(offset) = text[i..].find('`') {
+        let start = i + offset;
+        let run = text[start..].len() - text[start..].trim_start_matches('`').len();
+        if run == len {
+            return Some(start);
+        }
+        i = start + run;
+    }
+    None
+}
+
+/// Pair closers with the nearest matching opener. Openers left between a
+/// matched pair can no longer close and fall back to literal text.
+fn match_delimiters(tokens: &[InlineToken]) -> Vec<DelimRole> {
+    let mut roles = vec![DelimRole::Literal; tokens.len()];
+    let mut openers: Vec<(usize, &str)> = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        let InlineToken::Delim {
+            marker,
+            can_open,
+            can_close,
+        } = token
+        else {
+            continue;
+        };
+        if *can_close {
+            if let Some(pos) = openers.iter().rposition(|(_, m)| m == marker) {
+                roles[openers[pos].0] = DelimRole::Open;
+                roles[i] = DelimRole::Close;
+                openers.truncate(pos);
+                continue;
+            }
+        }
+        if *can_open {
+            openers.push((i, marker));
+        }
+    }
+
+    roles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flatten spans into (text, modifiers) pairs for comparison.
+    fn styled(spans: &[Span]) -> Vec<(String, Modifier)> {
+        spans
+            .iter()
+            .map(|s| (s.content.to_string(), s.style.add_modifier))
+            .collect()
+    }
+
+    #[test]
+    fn cache_renders_again_only_when_the_body_changes() {
+        let cache = MarkdownCache::default();
+        let none = BTreeSet::new();
+        let first = cache.render("# Title\nbody", false, &none);
+        assert!(Rc::ptr_eq(&first, &cache.render("# Title\nbody", false, &none)));
+
+        let changed = cache.render("# Title\nedited body", false, &none);
+        assert!(!Rc::ptr_eq(&first, &changed));
+        assert_eq!(changed.lines.len(), 2);
+        assert_eq!(changed.headings[0].title, "Title");
+        assert!(!Rc::ptr_eq(&changed, &cache.render("# Title\nedited body", true, &none)));
+
+        let rows = changed.line_rows(6);
+        assert_eq!(&*rows, &[1, 2]);
+        assert!(Rc::ptr_eq(&rows, &changed.line_rows(6)));
+        assert_eq!(&*changed.line_rows(20), &[1, 1]);
+    }
+
+    #[test]
+    fn details_sections_collapse_to_their_summary() {
+        let text = "Intro\n<details>\n<summary>Logs</summary>\n\n## Trace\nline\n</details>\n\
+                    <details open><summary>Inner</summary>\nshown\n</details>\nEnd";
+        let shown = |toggled: &[usize]| {
+            let rendered = render_folded(text, &toggled.iter().copied().collect());
+            let lines: Vec<String> = rendered.lines.iter().map(|l| l.to_string()).collect();
+            (lines, rendered.source_lines, rendered.sections, rendered.headings.len())
+        };
+
+        let (lines, source, sections, headings) = shown(&[]);
+        assert_eq!(lines, ["Intro", "▸ Logs", "▾ Inner", "shown", "End"]);
+        assert_eq!(source, [0, 1, 7, 8, 10]);
+        assert_eq!(sections, [(0, 1), (1, 7)]);
+        assert_eq!(headings, 0);
+
+        let (lines, _, _, headings) = shown(&[0, 1]);
+        assert_eq!(lines, ["Intro", "▾ Logs", "", "Trace", "line", "▸ Inner", "End"]);
+        assert_eq!(headings, 1);
+
+        let all: Vec<String> = markdown_to_lines(text).iter().map(|l| l.to_string()).collect();
+        assert_eq!(all.len(), 8);
+    }
+
+    #[test]
+    fn raw_keeps_every_source_line_unformatted() {
+        let raw = render_raw("Intro\n\n## Setup\n- [x] **done**\n[x]: https://x.dev");
+        let text: Vec<String> = raw.lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(text, ["Intro", "", "## Setup", "- [x] **done**", "[x]: https://x.dev"]);
+        assert_eq!(raw.source_lines, [0, 1, 2, 3, 4]);
+        assert_eq!(raw.headings[0].title, "Setup");
+        assert_eq!(raw.headings[0].line, 2);
+    }
+
+    #[test]
+    fn strikethrough_is_crossed_out() {
+        let spans = parse_inline_formatting("a ~~b~~ c");
+        assert_eq!(
+            styled(&spans),
+            vec![
+                ("a ".to_string(), Modifier::empty()),
+                ("b".to_string(), Modifier::CROSSED_OUT),
+                (" c".to_string(), Modifier::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn adjacent_bold_and_strikethrough() {
+        let spans = parse_inline_formatting("**a** ~~b~~");
+        assert_eq!(
+            styled(&spans),
+            vec![
+                ("a".to_string(), Modifier::BOLD),
+                (" ".to_string(), Modifier::empty()),
+                ("b".to_string(), Modifier::CROSSED_OUT),
+            ]
+        );
+    }
+
+    #[test]
+    fn bold_inside_strikethrough() {
+        let spans = parse_inline_formatting("~~x **b** y~~");
+        assert_eq!(
+            styled(&spans),
+            vec![
+                ("x ".to_string(), Modifier::CROSSED_OUT),
+                ("b".to_string(), Modifier::CROSSED_OUT | Modifier::BOLD),
+                (" y".to_string(), Modifier::CROSSED_OUT),
+            ]
+        );
+    }
+
+    #[test]
+    fn single_tilde_is_literal() {
+        let spans = parse_inline_formatting("about ~5 min or ~10");
+        assert_eq!(
+            styled(&spans),
+            vec![("about ~5 min or ~10".to_string(), Modifier::empty())]
+        );
+    }
+
+    #[test]
+    fn tricky_inline_inputs() {
+        let empty = Modifier::empty();
+        let cases: Vec<(&str, Vec<(&str, Modifier)>)> = vec![
+            ("plain text", vec![("plain text", empty)]),
+            ("a * b * c", vec![("a * b * c", empty)]),
+            ("2 ** 3", vec![("2 ** 3", empty)]),
+            ("*unclosed", vec![("*unclosed", empty)]),
+            ("**unclosed bold", vec![("**unclosed bold", empty)]),
+            ("`unclosed code", vec![("`unclosed code", empty)]),
+            ("``", vec![("``", empty)]),
+            ("~~~", vec![("~~~", empty)]),
+            (
+                "*a* **b**",
+                vec![("a", Modifier::ITALIC), (" ", empty), ("b", Modifier::BOLD)],
+            ),
+            ("***both***", vec![("both", Modifier::BOLD | Modifier::ITALIC)]),
+            (
+                "**bold with `code` inside**",
+                vec![("bold with ", Modifier::BOLD), ("code", Modifier::BOLD), (" inside", Modifier::BOLD)],
+            ),
+            ("`a**b` **c**", vec![("a**b", empty), (" ", empty), ("c", Modifier::BOLD)]),
+            ("**a *b** c*", vec![("a *b", Modifier::BOLD), (" c*", empty)]),
+            ("emoji ✓ *ok*", vec![("emoji ✓ ", empty), ("ok", Modifier::ITALIC)]),
+        ];
+
+        for (input, expected) in cases {
+            let expected: Vec<(String, Modifier)> = expected
+                .into_iter()
+                .map(|(t, m)| (t.to_string(), m))
+                .collect();
+            assert_eq!(styled(&parse_inline_formatting(input)), expected, "input: {input:?}");
+        }
+    }
+
+    #[test]
+    fn code_keeps_code_style_inside_bold() {
+        let spans = parse_inline_formatting("**see `x`**");
+        let code = &spans[1];
+        assert_eq!(code.content, "x");
+        assert_eq!(code.style.fg, Some(theme::YELLOW));
+        assert!(code.style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn headings_record_rendered_line_offsets() {
+        let text = "# Intro\n\n[logo]: logo.png\ntext\n## Setup\n#### not a heading\n### Details";
+        let (lines, headings) = markdown_with_headings(text);
+        let summary: Vec<(usize, &str, usize)> = headings
+            .iter()
+            .map(|h| (h.level, h.title.as_str(), h.line))
+            .collect();
+        assert_eq!(summary, vec![(1, "Intro", 0), (2, "Setup", 3), (3, "Details", 5)]);
+        assert_eq!(lines[3].spans[0].content, "Setup");
+    }
+
+    #[test]
+    fn renders_links_as_underlined_labels() {
+        let text = "See [the **docs**](https://x.dev/docs \"Docs\"), <https://a.b> and [spec].\n\
+                    `[not](a link)` [missing][nope]\n\n[spec]: https://x.dev/spec";
+        let links = find_links(text);
+        let summary: Vec<(String, &str)> = links
+            .iter()
+            .map(|l| (l.display_text(), l.url.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("the docs".to_string(), "https://x.dev/docs"),
+                ("https://a.b".to_string(), "https://a.b"),
+                ("spec".to_string(), "https://x.dev/spec"),
+            ]
+        );
+
+        let lines = markdown_to_lines(text);
+        let underlined: Vec<String> = lines[0]
+            .spans
+            .iter()
+            .filter(|s| s.style.fg == Some(theme::LINK_FG))
+            .map(|s| s.content.to_string())
+            .collect();
+        assert_eq!(underlined, vec!["the ", "docs", "https://a.b", "spec"]);
+        let plain: String = lines[1].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(plain, "[not](a link) [missing][nope]");
+        assert!(lines[1].spans.iter().all(|s| s.style.fg != Some(theme::LINK_FG)));
+    }
+
+    #[test]
+    fn renders_footnotes_and_definition_lists() {
+        let text = "Uses TLS[^tls] and caching[^1].\n\
+                    [^1]: See <https://x.dev>.\n\
+                    [^tls]: Version 1.3\n\
+                    [^unused]: Extra\n\
+                    Term\n\
+                    : First *meaning*\n\
+                    : Second\n\
+                    : Third";
+        let rendered = render_markdown(text);
+        let plain: Vec<String> = rendered
+            .lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            plain,
+            vec![
+                "Uses TLS[1] and caching[2].",
+                "Term",
+                "    First meaning",
+                "    Second",
+                "    Third",
+                "",
+                "────────────────────",
+                "[1] Version 1.3",
+                "[2] See https://x.dev.",
+                "[3] Extra",
+            ]
+        );
+        assert_eq!(rendered.source_lines, vec![0, 4, 5, 6, 7, 7, 7, 7, 7, 7]);
+        assert!(rendered.lines[1].spans[0].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(find_links(text)[0].url, "https://x.dev");
+
+        // Without a term, a `: ` line is plain text
+        let plain: String = markdown_to_lines("\n: just text")[1]
+            .spans
+            .iter()
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(plain, ": just text");
+    }
+
+    #[test]
+    fn reading_stats_skip_code_and_markers() {
+        let text = "# Title here\n\n- one *two* three\n> quoted [link](https://x.dev/a)\n\n\
+                    ```rust\nlet not_counted = 1;\n```\n| a | b |\n---\n<https://x.dev>\n[x]: https://x.dev";
+        assert_eq!(reading_stats(text), (9, 1));
+        assert_eq!(reading_stats(""), (0, 0));
+        assert_eq!(reading_stats(&"word ".repeat(401)), (401, 3));
+    }
+
+    #[test]
+    fn hides_html_comments_and_known_tags() {
+        let text = "a <!-- note --> b\n<!-- one\ntwo -->\nc `<!-- kept -->`\nPress <kbd>Ctrl</kbd>+<KBD>C</KBD><br/>H<sub>2</sub>O <span>x</span> 1 < 2";
+        let rendered = render_markdown(text);
+        let text_of = |line: &Line| line.spans.iter().map(|s| s.content.as_ref()).collect::<String>();
+        let lines: Vec<String> = rendered.lines.iter().map(text_of).collect();
+        assert_eq!(
+            lines,
+            ["a  b", "c <!-- kept -->", "Press Ctrl+C H2O <span>x</span> 1 < 2"]
+        );
+        assert_eq!(rendered.source_lines, [0, 3, 4]);
+        assert_eq!(render_raw(text).lines.len(), 5);
+    }
+
+    #[test]
+    fn mentions_skip_emails_and_code() {
+        let mentions = |input: &str| -> Vec<String> {
+            parse_inline_formatting(input)
+                .iter()
+                .filter(|s| s.style.fg == Some(theme::TAB_ACTIVE_FG))
+                .map(|s| s.content.to_string())
+                .collect()
+        };
+        assert_eq!(mentions("ask @alice, or @bob.smith."), ["@alice", "@bob.smith"]);
+        assert_eq!(mentions("mail bob@example.com or `@carol`"), Vec::<String>::new());
+        assert_eq!(mentions("**@dave** and @ alone, @@x"), ["@dave"]);
+        assert_eq!(
+            parse_inline_formatting("hi @eve!").iter().map(|s| s.content.as_ref()).collect::<String>(),
+            "hi @eve!"
+        );
+
+        let mut lines = vec![Line::from(parse_inline_formatting("@Alice and @alicia"))];
+        highlight_own_mentions(&mut lines, "alice");
+        let own: Vec<&str> = lines[0]
+            .spans
+            .iter()
+            .filter(|s| s.style.bg == Some(theme::YELLOW))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(own, ["@Alice"]);
+    }
+
+    #[test]
+    fn highlights_matches_across_spans() {
+        let mut lines = vec![
+            Line::from(vec![Span::raw("Fix lo"), Span::styled("gin bug", Style::default().add_modifier(Modifier::BOLD))]),
+            Line::from("nothing here"),
+            Line::from("LOGIN and login"),
+        ];
+        let hits = highlight_matches(&mut lines, "login");
+        assert_eq!(hits, vec![0, 2]);
+
+        let reversed: Vec<String> = lines[0]
+            .spans
+            .iter()
+            .filter(|s| s.style.add_modifier.contains(Modifier::REVERSED))
+            .map(|s| s.content.to_string())
+            .collect();
+        assert_eq!(reversed, vec!["lo", "gin"]);
+        assert!(lines[0].spans[2].style.add_modifier.contains(Modifier::BOLD));
+
+        let reversed = lines[2]
+            .spans
+            .iter()
+            .filter(|s| s.style.add_modifier.contains(Modifier::REVERSED))
+            .count();
+        assert_eq!(reversed, 2);
+        assert!(highlight_matches(&mut lines, "").is_empty());
+    }
+}
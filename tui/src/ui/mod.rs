@@ -1,23 +1,44 @@
 pub mod activity;
 pub mod board;
 pub mod common;
+pub mod forms;
 pub mod header;
+pub mod linemap;
 pub mod markdown;
+pub mod quick_open;
 pub mod resources;
 pub mod task_detail;
 
+use std::ops::Range;
+use std::rc::Rc;
+
 use ratatui::Frame;
-use ratatui::layout::{Constraint, Layout};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
 
 use crate::app::{App, Overlay, View};
+use crate::theme;
+use crate::ui::linemap::LineMap;
+use crate::ui::markdown::{wrapped_rows, RenderedMarkdown};
 
 pub fn render(f: &mut Frame, app: &App) {
-    let chunks = Layout::vertical([
-        Constraint::Length(3), // header/tabs
-        Constraint::Min(0),   // main content
-        Constraint::Length(1), // status bar
-    ])
-    .split(f.area());
+    if app.awaiting_first_data() {
+        common::render_connecting(f, app);
+        return;
+    }
+
+    if app.focus_mode && matches!(app.overlay, Some(Overlay::TaskDetail { .. })) {
+        task_detail::render_task_detail(f, app);
+        forms::render_link_picker(f, app);
+        if app.input.is_some() {
+            common::render_input_prompt(f, app);
+        }
+        return;
+    }
+
+    let chunks = screen_layout(f.area());
 
     header::render_header(f, app, chunks[0]);
 
@@ -38,6 +59,328 @@ pub fn render(f: &mut Frame, app: &App) {
             Overlay::TaskDetail { .. } => task_detail::render_task_detail(f, app),
             Overlay::ResourceDetail { .. } => resources::render_detail(f, app),
             Overlay::Help { .. } => common::render_help(f, app),
+            Overlay::CommandOutput { title, output, scroll } => {
+                common::render_command_output(f, title, output, *scroll)
+            }
+            Overlay::Debug => common::render_debug(f, app),
+            Overlay::NewTask { .. } => forms::render_new_task(f, app),
+            Overlay::QuickOpen { .. } => quick_open::render_quick_open(f, app),
+            Overlay::AssigneePicker { .. } => forms::render_assignee_picker(f, app),
+            Overlay::ScopeEditor { .. } => forms::render_scope_editor(f, app),
+            Overlay::ColumnPicker { .. } => forms::render_column_picker(f, app),
+            Overlay::Progress { .. } => forms::render_progress(f, app),
+            Overlay::Confirm { message, .. } => common::render_confirm(f, message),
         }
     }
+    forms::render_link_picker(f, app);
+
+    if app.input.is_some() {
+        common::render_input_prompt(f, app);
+    }
+}
+
+fn screen_layout(screen: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::vertical([
+        Constraint::Length(3), // header/tabs
+        Constraint::Min(0),   // main content
+        Constraint::Length(1), // status bar
+    ])
+    .split(screen)
+}
+
+/// Area the current view is drawn in on a terminal of `screen` size.
+pub fn content_area(screen: Rect) -> Rect {
+    screen_layout(screen)[1]
+}
+
+/// A detail overlay's lines: those above the markdown body, the body as
+/// cached by `MarkdownCache`, and those after it, indexed as one list. The
+/// body is shared rather than copied, and its wrapped rows are cached.
+pub struct OverlayLines {
+    pub head: Vec<Line<'static>>,
+    pub body: Rc<RenderedMarkdown>,
+    pub tail: Vec<Line<'static>>,
+}
+
+impl OverlayLines {
+    pub fn len(&self) -> usize {
+        self.head.len() + self.body.lines.len() + self.tail.len()
+    }
+
+    /// Indices of the body's lines.
+    pub fn body_range(&self) -> Range<usize> {
+        self.head.len()..self.head.len() + self.body.lines.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Line<'static>> {
+        self.head.iter().chain(&self.body.lines).chain(&self.tail)
+    }
+
+    /// Rows each line takes wrapped at `width`.
+    pub fn rows(&self, width: u16) -> Vec<usize> {
+        let wrap = |line| wrapped_rows(line, width);
+        self.head
+            .iter()
+            .map(wrap)
+            .chain(self.body.line_rows(width).iter().copied())
+            .chain(self.tail.iter().map(wrap))
+            .collect()
+    }
+
+    /// Row at which line `line` starts once wrapped to `width`.
+    pub fn row_of(&self, line: usize, width: u16) -> usize {
+        self.rows(width).iter().take(line).sum()
+    }
+
+    /// Index of the first line whose first row is at or below `row`.
+    pub fn line_at_row(&self, row: usize, width: u16) -> usize {
+        let mut start = 0;
+        for (i, rows) in self.rows(width).into_iter().enumerate() {
+            if start >= row {
+                return i;
+            }
+            start += rows;
+        }
+        self.len()
+    }
+}
+
+/// The open task/resource overlay laid out for a terminal of a given size.
+pub struct OverlayContent {
+    pub lines: OverlayLines,
+    pub map: LineMap,
+    /// Width the body wraps at
+    pub width: u16,
+    /// Rows of body visible at once
+    pub height: u16,
+}
+
+/// Lines of the open task/resource overlay for a terminal of `screen` size.
+/// Used to turn line offsets into scroll positions.
+pub fn overlay_content(app: &App, screen: Rect) -> Option<OverlayContent> {
+    match &app.overlay {
+        Some(Overlay::TaskDetail { .. }) => task_detail::detail_content(app, screen),
+        Some(Overlay::ResourceDetail { .. }) => {
+            resources::detail_content(app, screen).map(|(content, _)| content)
+        }
+        _ => None,
+    }
+}
+
+/// Background the lines of a `V` selection; `lines` starts at overlay line
+/// `first`.
+pub fn highlight_selection(
+    lines: &mut [Line<'static>],
+    first: usize,
+    map: &LineMap,
+    selection: Option<(usize, usize)>,
+) {
+    let Some((anchor, cursor)) = selection else {
+        return;
+    };
+    let (lo, hi) = (anchor.min(cursor), anchor.max(cursor));
+    for (i, line) in lines.iter_mut().enumerate() {
+        if map.source_of(first + i).is_some_and(|s| (lo..=hi).contains(&s)) {
+            line.style = line.style.bg(theme::SURFACE_1);
+            for span in &mut line.spans {
+                span.style = span.style.bg(theme::SURFACE_1);
+            }
+        }
+    }
+}
+
+/// Border label describing a `V` selection, or None without one.
+pub fn selection_label(selection: Option<(usize, usize)>) -> Option<String> {
+    let (anchor, cursor) = selection?;
+    let count = anchor.abs_diff(cursor) + 1;
+    let noun = if count == 1 { "line" } else { "lines" };
+    Some(format!(" VISUAL {count} {noun} · y copy · Esc cancel "))
+}
+
+/// Columns taken by the overlay line-number gutter for `count` lines (0 when
+/// numbers are off).
+pub fn gutter_width(app: &App, count: usize) -> u16 {
+    if !app.line_numbers {
+        return 0;
+    }
+    count.max(1).to_string().len() as u16 + 1
+}
+
+/// Render overlay body `lines` scrolled to `scroll`, with the line-number
+/// gutter on the left when enabled. Numbers sit on each line's first row.
+/// Only the lines on screen are copied; `decorate` highlights them, given
+/// those lines and the index of the first.
+pub fn render_body(
+    f: &mut Frame,
+    app: &App,
+    lines: &OverlayLines,
+    scroll: usize,
+    area: Rect,
+    decorate: impl FnOnce(&mut [Line<'static>], usize),
+) {
+    let gutter = gutter_width(app, lines.len());
+    let [gutter_area, text_area] =
+        Layout::horizontal([Constraint::Length(gutter), Constraint::Min(0)]).areas(area);
+    if text_area.width == 0 {
+        return;
+    }
+
+    // The first line on screen, the rows of it scrolled past, and the line
+    // after the last one on screen
+    let rows = lines.rows(text_area.width);
+    let (mut first, mut skip) = (0, scroll);
+    while first < rows.len() && skip >= rows[first] {
+        skip -= rows[first];
+        first += 1;
+    }
+    let (mut end, mut shown) = (first, 0);
+    while end < rows.len() && shown < skip + area.height as usize {
+        shown += rows[end];
+        end += 1;
+    }
+
+    if gutter > 0 {
+        let style = Style::default().fg(theme::TEXT_DIM);
+        let mut numbers: Vec<Line> = Vec::new();
+        for (i, &line_rows) in rows.iter().enumerate().take(end).skip(first) {
+            numbers.push(Line::from(Span::styled(
+                format!("{:>width$}", i + 1, width = gutter as usize - 1),
+                style,
+            )));
+            numbers.extend(std::iter::repeat_n(Line::default(), line_rows.max(1) - 1));
+        }
+        f.render_widget(
+            Paragraph::new(numbers).scroll((skip as u16, 0)),
+            gutter_area,
+        );
+    }
+
+    let mut window: Vec<Line<'static>> = lines.iter().skip(first).take(end - first).cloned().collect();
+    decorate(&mut window, first);
+    f.render_widget(
+        Paragraph::new(window)
+            .wrap(Wrap { trim: false })
+            .scroll((skip as u16, 0)),
+        text_area,
+    );
+}
+
+/// Border notice for an overlay whose item was deleted on the server.
+pub fn gone_notice() -> Span<'static> {
+    Span::styled(
+        " This item no longer exists — Esc to close ",
+        Style::default().fg(theme::RED).add_modifier(Modifier::BOLD),
+    )
+}
+
+/// " 42% " for the bottom border of an overlay whose body `lines` are shown
+/// in `area` at `scroll`.
+pub fn scroll_percent_label(app: &App, lines: &OverlayLines, scroll: usize, area: Rect) -> String {
+    let width = area.width.saturating_sub(gutter_width(app, lines.len()));
+    let total: usize = lines.rows(width).iter().sum();
+    let max_scroll = total.saturating_sub(area.height as usize);
+    // Everything fits when there is nowhere to scroll
+    let percent = (scroll.min(max_scroll) * 100)
+        .checked_div(max_scroll)
+        .unwrap_or(100);
+    format!(" {percent}% ")
+}
+
+/// Width of the key column in the table metadata layout.
+const META_KEY_WIDTH: usize = 11;
+
+/// One frontmatter field in a detail overlay's metadata block.
+pub struct MetaField {
+    pub key: String,
+    pub value: Vec<Span<'static>>,
+    /// Inline layout shows `key:value`; otherwise the value alone (`@alice`, `[auth]`).
+    pub keyed: bool,
+}
+
+impl MetaField {
+    /// A `key:value` field drawn in one style.
+    pub fn new(key: impl Into<String>, value: String, style: Style) -> Self {
+        Self {
+            key: key.into(),
+            value: vec![Span::styled(value, style)],
+            keyed: true,
+        }
+    }
+
+    /// A field whose inline form is its value alone.
+    pub fn unkeyed(key: impl Into<String>, value: Vec<Span<'static>>) -> Self {
+        Self {
+            key: key.into(),
+            value,
+            keyed: false,
+        }
+    }
+}
+
+/// Metadata as one inline line, or with `app.metadata_table` a line per field
+/// with the keys aligned (`assignee   @alice`).
+pub fn metadata_lines(app: &App, fields: Vec<MetaField>) -> Vec<Line<'static>> {
+    if fields.is_empty() {
+        return Vec::new();
+    }
+    if app.metadata_table {
+        return fields
+            .into_iter()
+            .map(|field| {
+                let key = format!("{:<width$} ", field.key, width = META_KEY_WIDTH - 1);
+                let mut spans = vec![Span::styled(key, Style::default().fg(theme::TEXT_DIM))];
+                spans.extend(field.value);
+                Line::from(spans)
+            })
+            .collect();
+    }
+    let mut spans = Vec::new();
+    for field in fields {
+        let mut value = field.value.into_iter();
+        if field.keyed {
+            if let Some(first) = value.next() {
+                spans.push(Span::styled(format!("{}:{}", field.key, first.content), first.style));
+            }
+        }
+        spans.extend(value);
+        spans.push(Span::raw("  "));
+    }
+    vec![Line::from(spans)]
+}
+
+/// `[scope]` tags in their configured colors, separated by spaces.
+pub fn scope_spans(app: &App, scopes: &[&str]) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for (i, scope) in scopes.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(
+            format!("[{scope}]"),
+            Style::default().fg(app.scope_color(scope)),
+        ));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_lines_count_rows_across_head_body_and_tail() {
+        let lines = OverlayLines {
+            head: vec![Line::from("Title"), Line::from("")],
+            body: Rc::new(markdown::render_markdown("one two three\nfour")),
+            tail: vec![Line::from("tail")],
+        };
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines.body_range(), 2..4);
+        assert_eq!(lines.rows(5), [1, 1, 3, 1, 1]);
+        assert_eq!(lines.row_of(3, 5), 5);
+        assert_eq!(lines.row_of(lines.len(), 5), 7);
+        assert_eq!(lines.line_at_row(3, 5), 3);
+        assert_eq!(lines.line_at_row(99, 5), 5);
+        assert_eq!(lines.iter().last().unwrap().to_string(), "tail");
+    }
 }
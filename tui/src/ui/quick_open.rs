@@ -0,0 +1,103 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph};
+
+use crate::app::{App, Overlay};
+use crate::quick_open::QuickOpenTarget;
+use crate::theme;
+use crate::ui::common::centered_rect;
+
+pub fn render_quick_open(f: &mut Frame, app: &App) {
+    let Some(Overlay::QuickOpen {
+        title,
+        query,
+        items,
+        matches,
+        selected,
+    }) = &app.overlay
+    else {
+        return;
+    };
+
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            format!(" {title} ({}/{}) ", matches.len(), items.len()),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let [query_area, list_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme::TAB_ACTIVE_FG)),
+            Span::styled(query.value.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+        ])),
+        query_area,
+    );
+    f.set_cursor_position((query_area.x + 2 + query.cursor_column() as u16, query_area.y));
+
+    if matches.is_empty() {
+        f.render_widget(
+            Paragraph::new(Span::styled("No matches", Style::default().fg(theme::TEXT_DIM))),
+            list_area,
+        );
+        return;
+    }
+
+    let list_items: Vec<ListItem> = matches
+        .iter()
+        .enumerate()
+        .map(|(i, &idx)| {
+            let item = &items[idx];
+            let is_selected = i == *selected;
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:<7}", item.target.tag()),
+                    Style::default().fg(tag_color(&item.target)),
+                ),
+                Span::styled(
+                    item.title.clone(),
+                    Style::default()
+                        .fg(theme::TEXT_PRIMARY)
+                        .add_modifier(if is_selected {
+                            Modifier::BOLD
+                        } else {
+                            Modifier::empty()
+                        }),
+                ),
+            ];
+            if let QuickOpenTarget::Task { column, .. } = &item.target {
+                spans.push(Span::styled(
+                    format!("  {column}"),
+                    Style::default().fg(theme::TEXT_DIM),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut state = ListState::default().with_selected(Some(*selected));
+    let list = List::new(list_items).highlight_style(Style::default().bg(theme::SURFACE_1));
+    f.render_stateful_widget(list, list_area, &mut state);
+}
+
+fn tag_color(target: &QuickOpenTarget) -> ratatui::style::Color {
+    match target {
+        QuickOpenTarget::Task { .. } => theme::TAB_ACTIVE_FG,
+        QuickOpenTarget::Resource { .. } => theme::GREEN,
+    }
+}
@@ -1,19 +1,25 @@
 use ratatui::Frame;
-use ratatui::layout::Rect;
+use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph, Wrap};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph};
 
-use crate::app::{App, Focus, Overlay, ResourceType};
-use crate::model::Resource;
+use crate::app::{App, Focus, Overlay, ResourceSort, ResourceType};
+use crate::model::{Resource, Revision};
+use crate::prompt;
 use crate::theme;
-use crate::ui::common::centered_rect;
-use crate::ui::markdown::markdown_to_lines;
+use crate::ui::common::{centered_rect, render_empty_state};
+use crate::ui::linemap::LineMap;
+use crate::ui::markdown::{Heading, highlight_matches, reading_stats};
+use crate::ui::{
+    gone_notice, gutter_width, highlight_selection, metadata_lines, render_body, scope_spans,
+    scroll_percent_label, selection_label, MetaField, OverlayContent, OverlayLines,
+};
 
 pub fn render_list(f: &mut Frame, app: &App, area: Rect, rtype: ResourceType) {
-    let (resources, selected) = match rtype {
-        ResourceType::Prompt => (&app.prompts, app.prompt_index),
-        ResourceType::Document => (&app.documents, app.document_index),
+    let (resources, selected, has_more) = match rtype {
+        ResourceType::Prompt => (&app.prompts, app.prompt_index, app.prompts_has_more),
+        ResourceType::Document => (&app.documents, app.document_index, app.documents_has_more),
     };
 
     let type_label = match rtype {
@@ -22,27 +28,44 @@ pub fn render_list(f: &mut Frame, app: &App, area: Rect, rtype: ResourceType) {
     };
 
     if resources.is_empty() {
-        let msg = if app.loading {
-            "Loading...".to_string()
+        if app.loading {
+            render_empty_state(f, area, "Loading...", "");
         } else {
-            format!("No {}", type_label.to_lowercase())
-        };
-        let p = Paragraph::new(msg)
-            .style(Style::default().fg(theme::TEXT_DIM))
-            .centered();
-        f.render_widget(p, area);
+            let hint = format!(
+                "Each {} is a directory with a current.md under {}",
+                rtype.label().to_lowercase(),
+                app.data_dir.join(rtype.collection()).display()
+            );
+            render_empty_state(f, area, &format!("No {}", type_label.to_lowercase()), &hint);
+        }
         return;
     }
 
-    let items: Vec<ListItem> = resources
-        .iter()
+    let items: Vec<ListItem> = app
+        .shown_resources(rtype)
+        .into_iter()
         .enumerate()
-        .map(|(i, res)| make_list_item(res, i == selected && app.overlay.is_none() && app.focus == Focus::Content))
+        .map(|(i, idx)| {
+            make_list_item(app, &resources[idx], i == selected && app.overlay.is_none() && app.focus == Focus::Content)
+        })
         .collect();
+    let sort = match rtype {
+        ResourceType::Prompt => app.prompt_sort,
+        ResourceType::Document => app.document_sort,
+    };
 
     let block = Block::default()
         .title(Line::from(Span::styled(
-            format!(" {type_label} ({}) ", resources.len()),
+            format!(
+                " {type_label} ({}{}){} ",
+                resources.len(),
+                if has_more { "+" } else { "" },
+                if sort == ResourceSort::Server {
+                    String::new()
+                } else {
+                    format!(" by {}", sort.label())
+                }
+            ),
             Style::default()
                 .fg(theme::TEXT_PRIMARY)
                 .add_modifier(Modifier::BOLD),
@@ -60,7 +83,7 @@ pub fn render_list(f: &mut Frame, app: &App, area: Rect, rtype: ResourceType) {
     f.render_stateful_widget(list, area, &mut state);
 }
 
-fn make_list_item(res: &Resource, is_selected: bool) -> ListItem<'static> {
+fn make_list_item(app: &App, res: &Resource, is_selected: bool) -> ListItem<'static> {
     let title = if res.meta.title.is_empty() {
         &res.dir_name
     } else {
@@ -93,6 +116,12 @@ fn make_list_item(res: &Resource, is_selected: bool) -> ListItem<'static> {
             Style::default().fg(theme::TEXT_DIM),
         ));
     }
+    if let Some(count) = res.meta.revision_count.filter(|&n| n > 1) {
+        spans.push(Span::styled(
+            format!(" ({count} revs)"),
+            Style::default().fg(theme::TEXT_DIM),
+        ));
+    }
 
     // Updated/created date
     let date = if !res.meta.updated.is_empty() {
@@ -102,7 +131,7 @@ fn make_list_item(res: &Resource, is_selected: bool) -> ListItem<'static> {
     };
     if !date.is_empty() {
         spans.push(Span::styled(
-            format!("  {date}"),
+            format!("  {}", app.format_date(date)),
             Style::default().fg(theme::TEXT_DIM),
         ));
     }
@@ -112,28 +141,205 @@ fn make_list_item(res: &Resource, is_selected: bool) -> ListItem<'static> {
     for scope in scopes.iter().take(3) {
         spans.push(Span::styled(
             format!("  [{scope}]"),
-            Style::default().fg(theme::SCOPE_FG),
+            Style::default().fg(app.scope_color(scope)),
         ));
     }
 
     ListItem::new(Line::from(spans))
 }
 
+/// Width of the table-of-contents side panel, including its divider.
+const TOC_WIDTH: u16 = 32;
+
 pub fn render_detail(f: &mut Frame, app: &App) {
-    let (resource, revisions, current_rev, scroll, rtype) = match &app.overlay {
+    let (resource, revisions, current_rev, scroll, rtype, toc, search, visual, gone) = match &app.overlay {
         Some(Overlay::ResourceDetail {
             resource,
             revisions,
             current_rev,
             scroll,
             resource_type,
-        }) => (resource, revisions, current_rev, *scroll, *resource_type),
+            toc,
+            search,
+            visual,
+            gone,
+            ..
+        }) => (
+            resource,
+            revisions,
+            *current_rev,
+            *scroll,
+            *resource_type,
+            *toc,
+            search,
+            *visual,
+            *gone,
+        ),
         _ => return,
     };
 
     let area = centered_rect(80, 85, f.area());
     f.render_widget(Clear, area);
 
+    let (lines, headings, map) = detail_lines(app, rtype, resource, revisions, current_rev);
+
+    let mut block = detail_block(rtype, resource);
+    // Stats for the body on screen, so they follow revision browsing
+    if let Some(body) = app.overlay_body() {
+        block = block.title(
+            Line::from(Span::styled(
+                reading_label(body),
+                Style::default().fg(theme::TEXT_DIM),
+            ))
+            .right_aligned(),
+        );
+    }
+    let (toc_area, body_area) = detail_areas(block.inner(area), toc.is_some());
+    let mut block = block.title_bottom(
+        Line::from(scroll_percent_label(app, &lines, scroll, body_area)).right_aligned(),
+    );
+    if gone {
+        block = block.title_bottom(gone_notice());
+    }
+    if let Some(label) = selection_label(visual) {
+        block = block.title_bottom(Span::styled(label, Style::default().fg(theme::YELLOW)));
+    }
+    f.render_widget(block, area);
+    render_body(f, app, &lines, scroll, body_area, |shown, first| {
+        if let Some(query) = search {
+            highlight_matches(shown, query);
+        }
+        highlight_selection(shown, first, &map, visual);
+    });
+
+    if let Some(selected) = toc {
+        render_toc(f, &headings, selected, toc_area);
+    }
+}
+
+/// " 1,240 words · ~6 min " for the overlay header.
+fn reading_label(body: &str) -> String {
+    let (words, minutes) = reading_stats(body);
+    let digits = words.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    let unit = if words == 1 { "word" } else { "words" };
+    format!(" {grouped} {unit} · ~{} min ", minutes.max(1))
+}
+
+fn detail_block(rtype: ResourceType, resource: &Resource) -> Block<'static> {
+    Block::default()
+        .title(Line::from(Span::styled(
+            format!(" {} — {} ", rtype.label(), resource.dir_name),
+            Style::default()
+                .fg(theme::TEXT_SECONDARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::new(2, 2, 1, 1))
+}
+
+/// Split the detail overlay's inner area into (TOC panel, body).
+fn detail_areas(inner: Rect, show_toc: bool) -> (Rect, Rect) {
+    if !show_toc || inner.width <= TOC_WIDTH * 2 {
+        return (Rect::default(), inner);
+    }
+    let [toc, body] =
+        Layout::horizontal([Constraint::Length(TOC_WIDTH), Constraint::Min(0)]).areas(inner);
+    (toc, body)
+}
+
+/// Lines and headings of the open resource overlay laid out for a terminal of
+/// `screen` size (mirrors the layout in `render_detail`).
+pub fn detail_content(app: &App, screen: Rect) -> Option<(OverlayContent, Vec<Heading>)> {
+    let Some(Overlay::ResourceDetail {
+        resource,
+        revisions,
+        current_rev,
+        resource_type,
+        toc,
+        ..
+    }) = &app.overlay
+    else {
+        return None;
+    };
+    let inner = detail_block(*resource_type, resource).inner(centered_rect(80, 85, screen));
+    let (_, body_area) = detail_areas(inner, toc.is_some());
+    let (lines, headings, map) = detail_lines(app, *resource_type, resource, revisions, *current_rev);
+    let width = body_area.width.saturating_sub(gutter_width(app, lines.len()));
+    let content = OverlayContent {
+        lines,
+        map,
+        width,
+        height: body_area.height,
+    };
+    Some((content, headings))
+}
+
+/// Number of headings in the resource overlay's currently shown body.
+pub fn detail_heading_count(app: &App) -> usize {
+    detail_content(app, Rect::default()).map_or(0, |(_, headings)| headings.len())
+}
+
+/// Scroll offset that puts the given heading at the top of the body.
+pub fn heading_scroll_target(app: &App, heading: usize, screen: Rect) -> Option<usize> {
+    let (content, headings) = detail_content(app, screen)?;
+    let line = headings.get(heading)?.line;
+    Some(content.lines.row_of(line, content.width))
+}
+
+fn render_toc(f: &mut Frame, headings: &[Heading], selected: usize, area: Rect) {
+    let items: Vec<ListItem> = headings
+        .iter()
+        .map(|h| {
+            let indent = "  ".repeat(h.level - 1);
+            let style = if h.level == 1 {
+                Style::default()
+                    .fg(theme::TEXT_PRIMARY)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme::TEXT_SECONDARY)
+            };
+            ListItem::new(Line::from(Span::styled(format!("{indent}{}", h.title), style)))
+        })
+        .collect();
+
+    let block = Block::default()
+        .title(Span::styled("Contents", Style::default().fg(theme::TEXT_DIM)))
+        .borders(Borders::RIGHT)
+        .border_style(Style::default().fg(theme::BORDER_COLOR));
+
+    if items.is_empty() {
+        let empty = Paragraph::new(Span::styled("No headings", Style::default().fg(theme::TEXT_DIM)))
+            .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let selected = selected.min(items.len() - 1);
+    let mut state = ListState::default().with_selected(Some(selected));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme::SURFACE_1));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Title, metadata and body lines of the resource overlay, plus the body's
+/// headings with offsets into the returned lines and its source line map.
+fn detail_lines(
+    app: &App,
+    resource_type: ResourceType,
+    resource: &Resource,
+    revisions: &[Revision],
+    current_rev: Option<usize>,
+) -> (OverlayLines, Vec<Heading>, LineMap) {
     let title = if resource.meta.title.is_empty() {
         &resource.dir_name
     } else {
@@ -152,49 +358,60 @@ pub fn render_detail(f: &mut Frame, app: &App) {
     lines.push(Line::from(""));
 
     // Metadata
-    let mut meta_spans: Vec<Span<'static>> = Vec::new();
-
+    let mut fields = Vec::new();
     if let Some(rev) = resource.meta.revision {
-        meta_spans.push(Span::styled(
-            format!("rev:{rev}"),
+        fields.push(MetaField::new(
+            "rev",
+            rev.to_string(),
             Style::default().fg(theme::TEXT_SECONDARY),
         ));
-        meta_spans.push(Span::raw("  "));
     }
-
     if !resource.meta.created.is_empty() {
-        meta_spans.push(Span::styled(
-            format!("created:{}", resource.meta.created),
+        fields.push(MetaField::new(
+            "created",
+            app.format_date(&resource.meta.created),
             Style::default().fg(theme::TEXT_DIM),
         ));
-        meta_spans.push(Span::raw("  "));
     }
     if !resource.meta.updated.is_empty() {
-        meta_spans.push(Span::styled(
-            format!("updated:{}", resource.meta.updated),
+        fields.push(MetaField::new(
+            "updated",
+            app.format_date(&resource.meta.updated),
             Style::default().fg(theme::TEXT_DIM),
         ));
-        meta_spans.push(Span::raw("  "));
     }
-
     let scopes = resource.meta.scopes.as_vec();
-    for scope in &scopes {
-        meta_spans.push(Span::styled(
-            format!("[{scope}]"),
-            Style::default().fg(theme::SCOPE_FG),
-        ));
-        meta_spans.push(Span::raw(" "));
+    if !scopes.is_empty() {
+        fields.push(MetaField::unkeyed("scopes", scope_spans(app, &scopes)));
     }
-
-    if !meta_spans.is_empty() {
-        lines.push(Line::from(meta_spans));
+    // Body — if viewing a revision, show that revision's body
+    let body = match current_rev {
+        Some(idx) => &revisions[idx].body,
+        None => &resource.body,
+    };
+    // What a prompt expects to be filled in before use
+    let names = match resource_type {
+        ResourceType::Prompt => prompt::placeholders(body),
+        ResourceType::Document => Vec::new(),
+    };
+    if !names.is_empty() {
+        let spans = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let sep = if i == 0 { "" } else { " " };
+                Span::styled(format!("{sep}{{{{{name}}}}}"), Style::default().fg(theme::YELLOW))
+            })
+            .collect();
+        fields.push(MetaField::unkeyed("placeholders", spans));
     }
+    lines.extend(metadata_lines(app, fields));
 
     // Revision navigation hint
     if !revisions.is_empty() {
         let rev_info = match current_rev {
             Some(idx) => {
-                let rev = &revisions[*idx];
+                let rev = &revisions[idx];
                 format!(
                     "Viewing revision {} of {} ([ ] to navigate, current = latest)",
                     rev.meta.revision.unwrap_or(0),
@@ -219,31 +436,18 @@ pub fn render_detail(f: &mut Frame, app: &App) {
     )));
     lines.push(Line::from(""));
 
-    // Body — if viewing a revision, show that revision's body
-    let body = match current_rev {
-        Some(idx) => &revisions[*idx].body,
-        None => &resource.body,
-    };
-
-    let body_lines = markdown_to_lines(body);
-    lines.extend(body_lines);
-
-    let block = Block::default()
-        .title(Line::from(Span::styled(
-            format!(" {} — {} ", rtype.label(), resource.dir_name),
-            Style::default()
-                .fg(theme::TEXT_SECONDARY)
-                .add_modifier(Modifier::BOLD),
-        )))
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
-        .style(Style::default().bg(theme::OVERLAY_BG))
-        .padding(Padding::new(2, 2, 1, 1));
-
-    let paragraph = Paragraph::new(lines)
-        .block(block)
-        .wrap(Wrap { trim: false })
-        .scroll((scroll as u16, 0));
+    let body = app.markdown_cache
+        .render(body, app.raw_markdown, app.overlay_sections());
+    let map = LineMap::new(lines.len(), &body);
+    let mut headings = body.headings.clone();
+    for heading in &mut headings {
+        heading.line += lines.len();
+    }
 
-    f.render_widget(paragraph, area);
+    let lines = OverlayLines {
+        head: lines,
+        body,
+        tail: Vec::new(),
+    };
+    (lines, headings, map)
 }
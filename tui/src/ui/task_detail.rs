@@ -1,27 +1,148 @@
 use ratatui::Frame;
+use ratatui::layout::Rect;
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap};
+use ratatui::widgets::{Block, Borders, Clear, Padding};
 
 use crate::app::{App, Overlay};
+use crate::model::{Comment, Task};
 use crate::theme;
-use crate::ui::board::{count_checkboxes, format_progress};
+use crate::ui::board::{count_checkboxes, due_color, format_progress, priority_marker, short_id, status_pill};
 use crate::ui::common::centered_rect;
-use crate::ui::markdown::markdown_to_lines;
+use crate::ui::linemap::LineMap;
+use crate::ui::markdown::{highlight_matches, highlight_own_mentions, markdown_to_lines, render_raw};
+use crate::ui::{
+    gone_notice, gutter_width, highlight_selection, metadata_lines, render_body, scope_spans,
+    scroll_percent_label, selection_label, MetaField, OverlayContent, OverlayLines,
+};
 
 pub fn render_task_detail(f: &mut Frame, app: &App) {
-    let (task, comments, scroll) = match &app.overlay {
+    let (task, comments, scroll, search, visual, collapsed, gone) = match &app.overlay {
         Some(Overlay::TaskDetail {
             task,
             comments,
             scroll,
-        }) => (task, comments, *scroll),
+            search,
+            visual,
+            comments_collapsed,
+            gone,
+            ..
+        }) => (task, comments, *scroll, search, *visual, *comments_collapsed, *gone),
         _ => return,
     };
 
-    let area = centered_rect(80, 85, f.area());
+    let area = detail_area(app, f.area());
     f.render_widget(Clear, area);
 
+    let (lines, map, _) = detail_lines(app, task, comments, collapsed);
+
+    let inner = detail_block(task).inner(area);
+    let mut block = detail_block(task).title_bottom(
+        Line::from(scroll_percent_label(app, &lines, scroll, inner)).right_aligned(),
+    );
+    if gone {
+        block = block.title_bottom(gone_notice());
+    }
+    if let Some(label) = selection_label(visual) {
+        block = block.title_bottom(Span::styled(label, Style::default().fg(theme::YELLOW)));
+    }
+    // Focus mode hides the status bar, so show status messages on the border
+    if app.focus_mode && visual.is_none() {
+        if let Some(msg) = app.active_status() {
+            block = block.title_bottom(Span::styled(
+                format!(" {msg} "),
+                Style::default().fg(theme::YELLOW),
+            ));
+        }
+    }
+
+    f.render_widget(block, area);
+    let body = lines.body_range();
+    render_body(f, app, &lines, scroll, inner, |shown, first| {
+        // Comments have their mentions marked as they are laid out
+        let (start, end) = (body.start.max(first), body.end.min(first + shown.len()));
+        if start < end {
+            highlight_own_mentions(&mut shown[start - first..end - first], &app.user);
+        }
+        if let Some(query) = search {
+            highlight_matches(shown, query);
+        }
+        highlight_selection(shown, first, &map, visual);
+    });
+}
+
+/// Lines of the open task overlay laid out for a terminal of `screen` size
+/// (mirrors the layout in `render_task_detail`).
+pub fn detail_content(app: &App, screen: Rect) -> Option<OverlayContent> {
+    let Some(Overlay::TaskDetail {
+        task,
+        comments,
+        comments_collapsed,
+        ..
+    }) = &app.overlay
+    else {
+        return None;
+    };
+    let inner = detail_block(task).inner(detail_area(app, screen));
+    let (lines, map, _) = detail_lines(app, task, comments, *comments_collapsed);
+    let width = inner.width.saturating_sub(gutter_width(app, lines.len()));
+    Some(OverlayContent {
+        lines,
+        map,
+        width,
+        height: inner.height,
+    })
+}
+
+/// Scroll offset that puts the comment saved as `filename` at the top.
+pub fn comment_scroll_target(app: &App, filename: &str, screen: Rect) -> Option<usize> {
+    let Some(Overlay::TaskDetail {
+        task,
+        comments,
+        comments_collapsed,
+        ..
+    }) = &app.overlay
+    else {
+        return None;
+    };
+    let index = comments.iter().position(|c| c.filename == filename)?;
+    let content = detail_content(app, screen)?;
+    let (_, _, starts) = detail_lines(app, task, comments, *comments_collapsed);
+    Some(content.lines.row_of(*starts.get(index)?, content.width))
+}
+
+/// The whole screen in focus mode, otherwise a centered popup.
+fn detail_area(app: &App, screen: Rect) -> Rect {
+    if app.focus_mode {
+        screen
+    } else {
+        centered_rect(80, 85, screen)
+    }
+}
+
+fn detail_block(task: &Task) -> Block<'static> {
+    Block::default()
+        .title(Line::from(Span::styled(
+            format!(" {} ", task.filename),
+            Style::default()
+                .fg(theme::TEXT_SECONDARY)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
+        .style(Style::default().bg(theme::OVERLAY_BG))
+        .padding(Padding::new(2, 2, 1, 1))
+}
+
+/// Overlay lines, the map from them back to the task body's source lines and
+/// the line each comment starts on. `collapsed` replaces the comment thread
+/// with a one-line summary.
+fn detail_lines(
+    app: &App,
+    task: &Task,
+    comments: &[Comment],
+    collapsed: bool,
+) -> (OverlayLines, LineMap, Vec<usize>) {
     let title = if task.meta.title.is_empty() {
         &task.filename
     } else {
@@ -41,62 +162,68 @@ pub fn render_task_detail(f: &mut Frame, app: &App) {
     lines.push(Line::from(""));
 
     // Metadata
-    let mut meta_parts: Vec<Span<'static>> = Vec::new();
-
+    let mut fields = Vec::new();
+    let id = short_id(&task.meta.id);
+    if !id.is_empty() {
+        fields.push(MetaField::new("id", id, Style::default().fg(theme::TEXT_DIM)));
+    }
     if !task.meta.assignee.is_empty() {
-        meta_parts.push(Span::styled(
-            format!("@{}", task.meta.assignee),
-            Style::default().fg(theme::TEXT_SECONDARY),
+        fields.push(MetaField::unkeyed(
+            "assignee",
+            vec![Span::styled(
+                format!("@{}", task.meta.assignee),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            )],
         ));
-        meta_parts.push(Span::raw("  "));
     }
-
     if !task.column.is_empty() {
-        meta_parts.push(Span::styled(
-            format!("column:{}", task.column),
+        fields.push(MetaField::new(
+            "column",
+            task.column.clone(),
             Style::default().fg(theme::TEXT_SECONDARY),
         ));
-        meta_parts.push(Span::raw("  "));
     }
-
     let scopes = task.meta.scopes.as_vec();
     if !scopes.is_empty() {
-        for scope in &scopes {
-            meta_parts.push(Span::styled(
-                format!("[{scope}]"),
-                Style::default().fg(theme::SCOPE_FG),
-            ));
-            meta_parts.push(Span::raw(" "));
-        }
-        meta_parts.push(Span::raw(" "));
+        fields.push(MetaField::unkeyed("scopes", scope_spans(app, &scopes)));
+    }
+    if let (Some(priority), Some((_, color))) = (&task.meta.priority, priority_marker(task)) {
+        fields.push(MetaField::new("priority", priority.clone(), Style::default().fg(color)));
+    }
+    if let Some(status) = task.meta.status() {
+        fields.push(MetaField::unkeyed("status", vec![status_pill(app, status)]));
     }
-
     if !task.meta.created.is_empty() {
-        meta_parts.push(Span::styled(
-            format!("created:{}", task.meta.created),
+        fields.push(MetaField::new(
+            "created",
+            app.format_date(&task.meta.created),
             Style::default().fg(theme::TEXT_DIM),
         ));
-        meta_parts.push(Span::raw("  "));
     }
-
     if !task.meta.due.is_empty() {
-        meta_parts.push(Span::styled(
-            format!("due:{}", task.meta.due),
-            Style::default().fg(theme::YELLOW),
+        fields.push(MetaField::new(
+            "due",
+            app.format_date(&task.meta.due),
+            Style::default().fg(due_color(&task.meta.due)),
         ));
-        meta_parts.push(Span::raw("  "));
     }
-
     if !task.meta.completed.is_empty() {
-        meta_parts.push(Span::styled(
-            format!("completed:{}", task.meta.completed),
+        fields.push(MetaField::new(
+            "completed",
+            app.format_date(&task.meta.completed),
             Style::default().fg(theme::GREEN),
         ));
     }
+    lines.extend(metadata_lines(app, fields));
 
-    if !meta_parts.is_empty() {
-        lines.push(Line::from(meta_parts));
-    }
+    // Custom frontmatter the board doesn't know about
+    let extra = task
+        .meta
+        .extra_fields()
+        .into_iter()
+        .map(|(key, value)| MetaField::new(key, value, Style::default().fg(theme::TEXT_DIM)))
+        .collect();
+    lines.extend(metadata_lines(app, extra));
 
     // Progress bar
     let (checked, total) = count_checkboxes(&task.body);
@@ -121,25 +248,39 @@ pub fn render_task_detail(f: &mut Frame, app: &App) {
     lines.push(Line::from(""));
 
     // Body
-    let body_lines = markdown_to_lines(&task.body);
-    lines.extend(body_lines);
+    let body = app.markdown_cache
+        .render(&task.body, app.raw_markdown, app.overlay_sections());
+    let map = LineMap::new(lines.len(), &body);
+    let head = std::mem::take(&mut lines);
+    let tail_start = head.len() + body.lines.len();
 
-    // Comments
+    // Comments, remembering the line each one starts on
+    let mut starts = Vec::new();
     if !comments.is_empty() {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             "─".repeat(60),
             Style::default().fg(theme::BORDER_COLOR),
         )));
-        lines.push(Line::from(Span::styled(
+        let mut heading = vec![Span::styled(
             format!(" Comments ({})", comments.len()),
             Style::default()
                 .fg(theme::TEXT_PRIMARY)
                 .add_modifier(Modifier::BOLD),
-        )));
+        )];
+        if collapsed {
+            heading.push(Span::styled(
+                " — press C to expand",
+                Style::default().fg(theme::TEXT_DIM),
+            ));
+            lines.push(Line::from(heading));
+            return (OverlayLines { head, body, tail: lines }, map, vec![]);
+        }
+        lines.push(Line::from(heading));
         lines.push(Line::from(""));
 
         for comment in comments {
+            starts.push(tail_start + lines.len());
             lines.push(Line::from(vec![
                 Span::styled(
                     format!("@{}", comment.meta.author),
@@ -148,32 +289,20 @@ pub fn render_task_detail(f: &mut Frame, app: &App) {
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
-                    format!("  {}", comment.meta.created),
+                    format!("  {}", app.format_date(&comment.meta.created)),
                     Style::default().fg(theme::TEXT_DIM),
                 ),
             ]));
-            let comment_lines = markdown_to_lines(&comment.body);
-            lines.extend(comment_lines);
+            let mut body = if app.raw_markdown {
+                render_raw(&comment.body).lines
+            } else {
+                markdown_to_lines(&comment.body)
+            };
+            highlight_own_mentions(&mut body, &app.user);
+            lines.extend(body);
             lines.push(Line::from(""));
         }
     }
 
-    let block = Block::default()
-        .title(Line::from(Span::styled(
-            format!(" {} ", task.filename),
-            Style::default()
-                .fg(theme::TEXT_SECONDARY)
-                .add_modifier(Modifier::BOLD),
-        )))
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme::BORDER_HIGHLIGHT))
-        .style(Style::default().bg(theme::OVERLAY_BG))
-        .padding(Padding::new(2, 2, 1, 1));
-
-    let paragraph = Paragraph::new(lines)
-        .block(block)
-        .wrap(Wrap { trim: false })
-        .scroll((scroll as u16, 0));
-
-    f.render_widget(paragraph, area);
+    (OverlayLines { head, body, tail: lines }, map, starts)
 }